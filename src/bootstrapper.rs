@@ -0,0 +1,605 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Instant;
+use std::{fs, io};
+
+use color_eyre::eyre::WrapErr;
+use ethers::abi::Address;
+use starknet::accounts::Account;
+use starknet_types_core::felt::Felt;
+use tracing::Instrument;
+use zaun_utils::LocalWalletSignerMiddleware;
+
+use crate::contract_clients::config::Clients;
+use crate::contract_clients::deployer::{DeploymentMethod, L2DeployerAccount};
+use crate::contract_clients::proxy_upgrade::PreparedProxyUpgrade;
+use crate::contract_clients::utils::{build_single_owner_account, RpcAccount};
+use crate::guardrails::{is_production_chain, plan_summary, production_violations};
+use crate::ledger::{Ledger, LedgerRecorder};
+use crate::preflight::{estimate_l1_cost, preflight, PreflightReport};
+use crate::settlement::ethereum::EthereumSettlement;
+use crate::settlement::piltover::PiltoverSettlement;
+use crate::settlement::{address_from_felt, felt_from_address, SettlementLayer};
+use crate::setup_scripts::accept_governance::{accept_governance, GovernanceAcceptanceOutput};
+use crate::setup_scripts::argent::ArgentSetupOutput;
+use crate::setup_scripts::braavos::BraavosSetupOutput;
+use crate::setup_scripts::core_contract::{get_l2_head, CoreContractStarknetL1Output};
+use crate::setup_scripts::custom_contracts::CustomContractOutput;
+use crate::setup_scripts::erc20_bridge::Erc20BridgeSetupOutput;
+use crate::setup_scripts::eth_bridge::EthBridgeSetupOutput;
+use crate::setup_scripts::genesis::{GenesisSetup, GenesisSetupOutput};
+use crate::setup_scripts::operators::{manage_operators, OperatorsOutput};
+use crate::setup_scripts::piltover::{PiltoverSetup, PiltoverSetupOutput};
+use crate::setup_scripts::udc::UdcSetupOutput;
+use crate::setup_scripts::update_core_contract::{update_core_contract, CoreContractUpdateOutput};
+use crate::setup_scripts::upgrade_token_bridge::TokenBridgeUpgradeOutput;
+use crate::steps::{missing_prerequisite, Step};
+use crate::utils::artifacts::{init_artifact_registry, write_artifacts_manifest};
+use crate::utils::enable_addresses_file;
+use crate::{
+    apply_pending_upgrades, get_account, get_core_contract_client, setup_argent, setup_braavos, setup_core_contract,
+    setup_custom_contracts, setup_erc20_bridge, setup_eth_bridge, setup_udc, upgrade_erc20_bridge, upgrade_eth_bridge,
+    BootstrapperOutput, ConfigFile, SettlementLayerKind,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BootstrapperError {
+    #[error("`{0}` is required in the config for this step")]
+    MissingConfig(&'static str),
+    #[error("Invalid `{field}` in config : {value}")]
+    InvalidConfig { field: &'static str, value: String },
+    #[error("`{step}` needs the outputs of `{dependency}` : run it as well or pass a manifest with them")]
+    MissingPrerequisite { step: Step, dependency: Step },
+    #[error("Preflight checks failed :\n{0}")]
+    Preflight(PreflightReport),
+    #[error("The settlement layer is a production chain, the config is refused :\n- {}", .0.join("\n- "))]
+    ProductionGuardrails(Vec<String>),
+    #[error(
+        "The settlement layer is a production chain : pass `--i-understand-mainnet` or confirm the plan interactively \
+         (`BootstrapperBuilder::confirm_production` in the library)"
+    )]
+    MainnetNotConfirmed,
+    #[error("{0:?}")]
+    Step(color_eyre::Report),
+    /// A step of [`Bootstrapper::run_steps`] failed, with the transactions sent until then.
+    #[error("`{step}` failed : {source}")]
+    StepFailed { step: &'static str, ledger: Option<Ledger>, source: Box<BootstrapperError> },
+}
+
+/// The config errors raised by the steps keep their variant.
+impl From<color_eyre::Report> for BootstrapperError {
+    fn from(report: color_eyre::Report) -> Self {
+        match report.downcast::<BootstrapperError>() {
+            Ok(err) => err,
+            Err(report) => Self::Step(report),
+        }
+    }
+}
+
+pub type BootstrapperResult<T> = Result<T, BootstrapperError>;
+
+/// Entry point to use the bootstrapper as a library.
+///
+/// ```ignore
+/// let bootstrapper = Bootstrapper::builder(config_file).build().await?;
+/// let core_contract = bootstrapper.setup_core_contract().await?;
+/// ```
+///
+/// Nothing is written to disk unless [`BootstrapperBuilder::save_addresses`] is set. On a
+/// production settlement layer, `build` refuses the config (see [`crate::guardrails`]) and the run
+/// unless it is confirmed with [`BootstrapperBuilder::confirm_production`].
+pub struct Bootstrapper {
+    config_file: ConfigFile,
+    clients: Clients,
+}
+
+pub struct BootstrapperBuilder {
+    config_file: ConfigFile,
+    clients: Option<Clients>,
+    artifacts_dir: Option<PathBuf>,
+    verify_artifacts: bool,
+    save_addresses: bool,
+    plan: Vec<Step>,
+    plan_description: Option<String>,
+    confirm_production: Option<ProductionConfirmation>,
+}
+
+/// Gets the plan summary of a run on a production settlement layer, returns whether to go on.
+type ProductionConfirmation = Box<dyn FnOnce(&str) -> io::Result<bool> + Send>;
+
+impl BootstrapperBuilder {
+    /// Clients to use instead of the ones built from the config.
+    pub fn clients(mut self, clients: Clients) -> Self {
+        self.clients = Some(clients);
+        self
+    }
+
+    /// Directory to load the contract artifacts from, see [`crate::utils::artifacts`].
+    pub fn artifacts_dir(mut self, artifacts_dir: PathBuf) -> Self {
+        self.artifacts_dir = Some(artifacts_dir);
+        self
+    }
+
+    /// Check the artifacts against the pinned manifest (default). Without it the artifacts are used
+    /// as found.
+    pub fn verify_artifacts(mut self, verify_artifacts: bool) -> Self {
+        self.verify_artifacts = verify_artifacts;
+        self
+    }
+
+    /// Record the deployed addresses and class hashes in `./data/addresses.json`.
+    pub fn save_addresses(mut self, save_addresses: bool) -> Self {
+        self.save_addresses = save_addresses;
+        self
+    }
+
+    /// Steps shown in the production confirmation, described as `description` (the step names by
+    /// default).
+    pub fn plan(mut self, steps: Vec<Step>, description: Option<String>) -> Self {
+        self.plan = steps;
+        self.plan_description = description;
+        self
+    }
+
+    /// Confirms a run on a production settlement layer : `confirm` gets the plan summary (see
+    /// [`crate::guardrails::plan_summary`]) and returns whether to go on. Without it, `build`
+    /// refuses a production settlement layer.
+    pub fn confirm_production(mut self, confirm: impl FnOnce(&str) -> io::Result<bool> + Send + 'static) -> Self {
+        self.confirm_production = Some(Box::new(confirm));
+        self
+    }
+
+    pub async fn build(self) -> BootstrapperResult<Bootstrapper> {
+        if self.artifacts_dir.is_some() || !self.verify_artifacts {
+            init_artifact_registry(self.artifacts_dir, self.verify_artifacts)?;
+        }
+        enable_addresses_file(self.save_addresses);
+        let clients = match self.clients {
+            Some(clients) => clients,
+            None => Clients::init_from_config(&self.config_file).await?,
+        };
+        let bootstrapper = Bootstrapper { config_file: self.config_file, clients };
+
+        if bootstrapper.check_production_guardrails().await? {
+            let description = self
+                .plan_description
+                .unwrap_or_else(|| self.plan.iter().map(|step| step.name()).collect::<Vec<_>>().join(", "));
+            let summary = bootstrapper.plan_summary(&self.plan, &description).await;
+            let confirmed = match self.confirm_production {
+                Some(confirm) => confirm(&summary).map_err(|err| BootstrapperError::Step(err.into()))?,
+                None => false,
+            };
+            if !confirmed {
+                return Err(BootstrapperError::MainnetNotConfirmed);
+            }
+        }
+        Ok(bootstrapper)
+    }
+}
+
+impl Bootstrapper {
+    pub fn builder(config_file: ConfigFile) -> BootstrapperBuilder {
+        BootstrapperBuilder {
+            config_file,
+            clients: None,
+            artifacts_dir: None,
+            verify_artifacts: true,
+            save_addresses: false,
+            plan: vec![],
+            plan_description: None,
+            confirm_production: None,
+        }
+    }
+
+    pub fn config(&self) -> &ConfigFile {
+        &self.config_file
+    }
+
+    pub fn clients(&self) -> &Clients {
+        &self.clients
+    }
+
+    /// Uses the latest L2 block as initial state of the core contract (see `--from-l2-head`).
+    pub async fn use_l2_head_as_initial_state(&mut self) -> BootstrapperResult<()> {
+        let (block_number, state_root, block_hash) = get_l2_head(self.clients.provider_l2()).await?;
+        log::info!("⏳ Using L2 head as core contract initial state [block number : {:?}]", block_number);
+        self.config_file.initial_block_number = Some(block_number);
+        self.config_file.initial_state_root = Some(state_root.to_hex_string());
+        self.config_file.initial_block_hash = Some(block_hash.to_hex_string());
+        Ok(())
+    }
+
+    /// Core contract and L2 setup.
+    pub async fn bootstrap(&mut self) -> BootstrapperResult<BootstrapperOutput> {
+        self.run_steps(&Step::ALL).await
+    }
+
+    pub async fn setup_core_contract(&self) -> BootstrapperResult<CoreContractStarknetL1Output> {
+        Ok(setup_core_contract(&self.config_file, &self.clients).await?)
+    }
+
+    /// Every L2 component. The config is updated with the deployed addresses.
+    pub async fn setup_l2(&mut self) -> BootstrapperResult<BootstrapperOutput> {
+        let steps: Vec<Step> = Step::ALL.into_iter().filter(|step| *step != Step::CoreContract).collect();
+        self.run_steps(&steps).await
+    }
+
+    /// Checks the config, the chains and the artifacts needed by `steps` without sending any
+    /// transaction.
+    pub async fn preflight(&self, steps: &[Step]) -> PreflightReport {
+        preflight(&self.config_file, &self.clients, steps).await
+    }
+
+    /// Refuses the dev options and the unsafe multisigs when the settlement layer is a production
+    /// chain. Returns whether it is one.
+    pub async fn check_production_guardrails(&self) -> BootstrapperResult<bool> {
+        if !is_production_chain(&self.config_file, &self.clients).await {
+            return Ok(false);
+        }
+        let violations = production_violations(&self.config_file, &self.clients).await;
+        if !violations.is_empty() {
+            return Err(BootstrapperError::ProductionGuardrails(violations));
+        }
+        Ok(true)
+    }
+
+    /// Summary of the chains, the multisigs and the estimated L1 cost of `steps`, described as
+    /// `plan`.
+    pub async fn plan_summary(&self, steps: &[Step], plan: &str) -> String {
+        let l1_cost = match self.config_file.settlement_layer() {
+            SettlementLayerKind::Ethereum => estimate_l1_cost(&self.clients, steps).await.ok(),
+            SettlementLayerKind::Starknet => None,
+        };
+        plan_summary(&self.config_file, plan, l1_cost)
+    }
+
+    /// Runs the steps in order. The outputs of each step are written to the config for the next
+    /// ones, the dependencies which are not part of `steps` must already be in the config.
+    pub async fn run_steps(&mut self, steps: &[Step]) -> BootstrapperResult<BootstrapperOutput> {
+        if let Some((step, dependency)) = missing_prerequisite(steps, &self.config_file) {
+            return Err(BootstrapperError::MissingPrerequisite { step, dependency });
+        }
+        if steps.iter().any(|step| *step != Step::CoreContract) {
+            check_l2_deployer(&self.config_file)?;
+        }
+
+        let mut ledger = match LedgerRecorder::start(&self.clients, &self.config_file).await {
+            Ok(ledger) => Some(ledger),
+            Err(err) => {
+                log::warn!("⚠️ The transactions of the run will not be recorded : {:?}", err);
+                None
+            }
+        };
+
+        let mut output = BootstrapperOutput::default();
+        if steps.iter().any(|step| *step != Step::CoreContract) && self.config_file.l2_deployer_address.is_none() {
+            let account = match get_account(&self.clients, &self.config_file).await {
+                Ok(account) => account,
+                Err(err) => {
+                    if let Some(ref mut ledger) = ledger {
+                        record_transactions(ledger, &self.clients, "l2_deployer_account").await;
+                    }
+                    return Err(step_failed("l2_deployer_account", ledger, err.into()));
+                }
+            };
+            let address = account.address();
+            self.config_file.l2_deployer_address = Some(address.to_hex_string());
+            output.l2_deployer_address = Some(address.to_hex_string());
+            if let Some(ref mut ledger) = ledger {
+                ledger.add_l2_sender(address);
+                record_transactions(ledger, &self.clients, "l2_deployer_account").await;
+            }
+        }
+
+        let settlement = self.config_file.settlement_layer();
+        for step in steps {
+            log::info!("▶️ Running step {}", step);
+            let span = tracing::info_span!(
+                "step",
+                step = step.name(),
+                chain = step.chains(),
+                settlement = ?settlement,
+                outcome = tracing::field::Empty,
+                duration_ms = tracing::field::Empty,
+                l1_tx_hashes = tracing::field::Empty,
+            );
+            let started = Instant::now();
+            let step_output = self.run_step(*step).instrument(span.clone()).await;
+            span.record("duration_ms", started.elapsed().as_millis() as u64);
+            span.record("outcome", if step_output.is_ok() { "ok" } else { "failed" });
+            if let Some(ref mut ledger) = ledger {
+                let l1_tx_hashes = record_transactions(ledger, &self.clients, step.name()).await;
+                if !l1_tx_hashes.is_empty() {
+                    span.record("l1_tx_hashes", tracing::field::debug(&l1_tx_hashes));
+                }
+            }
+            match step_output {
+                Ok(step_output) => output = output.merge(step_output),
+                Err(err) => return Err(step_failed(step.name(), ledger, err)),
+            }
+        }
+
+        output.ledger = ledger.map(LedgerRecorder::finish);
+        if let Some(ref ledger) = output.ledger {
+            log::info!("🧾 Transactions cost :\n{}", ledger.cost_report);
+        }
+        Ok(output)
+    }
+
+    async fn run_step(&mut self, step: Step) -> BootstrapperResult<BootstrapperOutput> {
+        if !self.settlement().supports(step) {
+            log::info!(
+                "ℹ️ {} is not used with a {:?} settlement layer. Skipping....",
+                step,
+                self.config_file.settlement_layer()
+            );
+            return Ok(BootstrapperOutput::default());
+        }
+        let output = match step {
+            Step::CoreContract => {
+                let core_contract = self.settlement().deploy_core_contract().await?;
+                let address = self.settlement().config_address(core_contract.address);
+                let implementation_address =
+                    core_contract.implementation_address.map(|address| self.settlement().config_address(address));
+                self.config_file.core_contract_address = Some(address);
+                self.config_file.core_contract_implementation_address = implementation_address;
+                core_contract.output
+            }
+            Step::EthBridge => {
+                let eth_bridge = self.setup_eth_bridge().await?;
+                let l1_eth_bridge_address = self.settlement().config_address(eth_bridge.l1_bridge_address);
+                self.config_file.l1_eth_bridge_address = Some(l1_eth_bridge_address);
+                self.config_file.l2_eth_token_proxy_address = Some(eth_bridge.l2_eth_proxy_address.to_hex_string());
+                self.config_file.l2_eth_bridge_proxy_address =
+                    Some(eth_bridge.l2_eth_bridge_proxy_address.to_hex_string());
+                eth_bridge.into()
+            }
+            Step::Erc20Bridge => self.setup_erc20_bridge().await?.into(),
+            Step::Udc => {
+                let udc = self.setup_udc().await?;
+                self.config_file.udc_address = Some(udc.udc_address.to_hex_string());
+                udc.into()
+            }
+            Step::Argent => self.setup_argent().await?.into(),
+            Step::Braavos => self.setup_braavos().await?.into(),
+            Step::CustomContracts => match self.config_file.custom_contracts {
+                Some(_) => self.setup_custom_contracts().await?.into(),
+                None => {
+                    log::info!("ℹ️ No custom contracts in config. Skipping....");
+                    BootstrapperOutput::default()
+                }
+            },
+            Step::UpgradeEthBridge => vec![self.upgrade_eth_bridge().await?].into(),
+        };
+        Ok(output)
+    }
+
+    /// Settlement layer of the config. Ethereum bridges are initialised with the core contract of
+    /// the config.
+    pub fn settlement(&self) -> Box<dyn SettlementLayer + '_> {
+        match self.config_file.settlement_layer() {
+            SettlementLayerKind::Ethereum => Box::new(EthereumSettlement::new(&self.config_file, &self.clients)),
+            SettlementLayerKind::Starknet => Box::new(PiltoverSettlement::new(&self.config_file, &self.clients)),
+        }
+    }
+
+    /// Piltover core contract (`settlement_layer: starknet`).
+    pub async fn setup_piltover(&self) -> BootstrapperResult<PiltoverSetupOutput> {
+        let account = PiltoverSettlement::new(&self.config_file, &self.clients).account().await?;
+        Ok(PiltoverSetup::new(account, &self.config_file).setup().await?)
+    }
+
+    pub async fn setup_eth_bridge(&self) -> BootstrapperResult<EthBridgeSetupOutput> {
+        let settlement = self.settlement();
+        Ok(setup_eth_bridge(self.account().await, settlement.as_ref(), &self.config_file, &self.clients).await?)
+    }
+
+    pub async fn setup_erc20_bridge(&self) -> BootstrapperResult<Erc20BridgeSetupOutput> {
+        let settlement = self.settlement();
+        Ok(setup_erc20_bridge(self.account().await, settlement.as_ref(), &self.config_file, &self.clients).await?)
+    }
+
+    pub async fn setup_udc(&self) -> BootstrapperResult<UdcSetupOutput> {
+        if let Some(ref udc_address) = self.config_file.udc_address {
+            Felt::from_hex(udc_address)
+                .map_err(|_| BootstrapperError::InvalidConfig { field: "udc_address", value: udc_address.clone() })?;
+        }
+        Ok(setup_udc(self.account().await, &self.config_file, &self.clients).await?)
+    }
+
+    pub async fn setup_argent(&self) -> BootstrapperResult<ArgentSetupOutput> {
+        Ok(setup_argent(self.account().await, &self.config_file, &self.clients).await?)
+    }
+
+    pub async fn setup_braavos(&self) -> BootstrapperResult<BraavosSetupOutput> {
+        let udc_address =
+            self.config_file.udc_address.as_ref().ok_or(BootstrapperError::MissingConfig("udc_address"))?;
+        let udc_address = Felt::from_str(udc_address)
+            .map_err(|_| BootstrapperError::InvalidConfig { field: "udc_address", value: udc_address.clone() })?;
+        Ok(setup_braavos(self.account().await, &self.config_file, &self.clients, udc_address).await?)
+    }
+
+    pub async fn setup_custom_contracts(&self) -> BootstrapperResult<Vec<CustomContractOutput>> {
+        Ok(setup_custom_contracts(self.account().await, &self.config_file, &self.clients).await?)
+    }
+
+    pub async fn upgrade_eth_bridge(&self) -> BootstrapperResult<PreparedProxyUpgrade> {
+        Ok(upgrade_eth_bridge(self.account().await, &self.config_file, &self.clients).await?)
+    }
+
+    pub async fn upgrade_token_bridge(&self) -> BootstrapperResult<TokenBridgeUpgradeOutput> {
+        Ok(upgrade_erc20_bridge(self.account().await, &self.config_file, &self.clients).await?)
+    }
+
+    pub async fn update_core_contract(&self) -> BootstrapperResult<CoreContractUpdateOutput> {
+        let core_contract = self.core_contract()?;
+        Ok(update_core_contract(core_contract.core_contract_client.address(), self.eth_signer(), &self.config_file)
+            .await?)
+    }
+
+    pub async fn apply_pending_upgrades(&self) -> BootstrapperResult<Vec<PreparedProxyUpgrade>> {
+        Ok(apply_pending_upgrades(&self.config_file, &self.clients).await?)
+    }
+
+    pub async fn manage_operators(&self) -> BootstrapperResult<OperatorsOutput> {
+        let core_contract = self.core_contract()?;
+        Ok(manage_operators(core_contract.core_contract_client.as_ref(), self.eth_signer(), &self.config_file).await?)
+    }
+
+    pub async fn accept_governance(&self) -> BootstrapperResult<GovernanceAcceptanceOutput> {
+        Ok(accept_governance(&self.config_file, &self.clients).await?)
+    }
+
+    /// Pins the available artifacts, see [`crate::utils::artifacts`].
+    pub fn pin_artifacts(&self, artifacts_dir: &Option<PathBuf>) -> BootstrapperResult<PathBuf> {
+        Ok(write_artifacts_manifest(artifacts_dir)?)
+    }
+
+    /// Writes the genesis bundle (see [`crate::setup_scripts::genesis`]) to `genesis_dir`. Without
+    /// `l1_eth_bridge_address`, the L1 ETH bridge is deployed and linked to the genesis L2 bridge.
+    /// The genesis addresses are written to the config for the next steps.
+    pub async fn genesis(&mut self) -> BootstrapperResult<GenesisSetupOutput> {
+        let genesis = GenesisSetup::new(&self.config_file)?;
+        let base_chain_config = self
+            .config_file
+            .genesis_base_chain_config
+            .as_ref()
+            .ok_or(BootstrapperError::MissingConfig("genesis_base_chain_config"))?;
+        let base_chain_config = fs::read_to_string(base_chain_config)
+            .wrap_err_with(|| format!("Failed to read the base chain config {}", base_chain_config))?;
+        let addresses = genesis.addresses()?;
+        // The UDC is predeployed at the Starknet address, the next steps would deploy through any other
+        // one.
+        if let Some(ref udc_address) = self.config_file.udc_address {
+            if Felt::from_hex(udc_address).ok() != Some(addresses.udc) {
+                return Err(BootstrapperError::InvalidConfig { field: "udc_address", value: udc_address.clone() });
+            }
+        }
+
+        let (l1_eth_bridge_address, governance_nominations) = match self.config_file.l1_eth_bridge_address {
+            Some(ref address) => {
+                let address = Address::from_str(address).map_err(|_| BootstrapperError::InvalidConfig {
+                    field: "l1_eth_bridge_address",
+                    value: address.clone(),
+                })?;
+                log::info!(
+                    "ℹ️ Using the L1 ETH bridge {:?}, its L2 bridge must be {:?}",
+                    address,
+                    addresses.eth_bridge_proxy
+                );
+                (felt_from_address(address), vec![])
+            }
+            None if !self.settlement().supports(Step::EthBridge) => {
+                return Err(BootstrapperError::MissingConfig("l1_eth_bridge_address"));
+            }
+            None => {
+                let l1_bridge = self.settlement().deploy_eth_bridge().await?;
+                l1_bridge.link_l2_bridge(addresses.eth_bridge_proxy).await?;
+                log::info!("🌱 L1 ETH bridge linked to the genesis L2 bridge {:?}", addresses.eth_bridge_proxy);
+                (l1_bridge.address(), l1_bridge.governance_nominations())
+            }
+        };
+
+        let state = genesis.genesis_state(l1_eth_bridge_address)?;
+        let l2_governance_nominee = genesis.l2_governance_nominee(addresses.l2_deployer)?;
+        match l2_governance_nominee {
+            Some(nominee) => log::info!(
+                "🏛️ {:?} nominated governor of the genesis ETH proxies, it has to call accept_governance on them",
+                nominee
+            ),
+            None => {
+                log::warn!("⚠️ No l2_multisig_address, the deployer account stays the only governor of the ETH proxies")
+            }
+        }
+        let genesis_dir = PathBuf::from(self.config_file.genesis_dir.as_deref().unwrap_or("genesis"));
+        let (genesis_file, chain_config_file) = genesis.write_bundle(
+            &genesis_dir,
+            &state,
+            &base_chain_config,
+            self.config_file.core_contract_address.as_deref(),
+        )?;
+        log::info!("🌱 Genesis bundle written to {}", genesis_dir.display());
+
+        self.config_file.l2_deployer_address = Some(addresses.l2_deployer.to_hex_string());
+        self.config_file.udc_address = Some(addresses.udc.to_hex_string());
+        self.config_file.l2_eth_token_proxy_address = Some(addresses.eth_token_proxy.to_hex_string());
+        self.config_file.l2_eth_bridge_proxy_address = Some(addresses.eth_bridge_proxy.to_hex_string());
+        let l1_eth_bridge_address = address_from_felt(l1_eth_bridge_address);
+        self.config_file.l1_eth_bridge_address = Some(format!("{:?}", l1_eth_bridge_address));
+
+        Ok(GenesisSetupOutput {
+            genesis_file,
+            chain_config_file,
+            l2_deployer_address: addresses.l2_deployer,
+            udc_address: addresses.udc,
+            l2_eth_token_proxy_address: addresses.eth_token_proxy,
+            l2_eth_bridge_proxy_address: addresses.eth_bridge_proxy,
+            l1_eth_bridge_address,
+            l2_governance_nominee,
+            governance_nominations,
+        })
+    }
+
+    /// The L2 deployer account when `l2_deployer_address` is set. Otherwise the steps deploy it
+    /// from `rollup_priv_key`.
+    async fn account(&self) -> Option<RpcAccount<'_>> {
+        match self.config_file.l2_deployer_address {
+            Some(ref address) => Some(
+                build_single_owner_account(
+                    self.clients.provider_l2(),
+                    &self.config_file.rollup_priv_key,
+                    address,
+                    false,
+                )
+                .await,
+            ),
+            None => None,
+        }
+    }
+
+    fn eth_signer(&self) -> Arc<LocalWalletSignerMiddleware> {
+        self.clients.eth_client().signer().clone()
+    }
+
+    fn core_contract(&self) -> BootstrapperResult<CoreContractStarknetL1Output> {
+        get_core_contract_client(&self.config_file, &self.clients)
+    }
+}
+
+/// Only the account deployed by the bootstrapper can deploy from its own `deploy_contract`, an
+/// existing account must be given with its address.
+fn check_l2_deployer(config: &ConfigFile) -> BootstrapperResult<()> {
+    if config.l2_deployer_account() == L2DeployerAccount::Bootstrapper {
+        return Ok(());
+    }
+    if config.l2_deployer_address.is_none() {
+        return Err(BootstrapperError::MissingConfig("l2_deployer_address"));
+    }
+    match config.l2_deployment_method() {
+        DeploymentMethod::Account => Err(BootstrapperError::InvalidConfig {
+            field: "l2_deployment_method",
+            value: DeploymentMethod::Account.to_string(),
+        }),
+        DeploymentMethod::LegacyUdc | DeploymentMethod::Udc => Ok(()),
+    }
+}
+
+/// Finishes the ledger of a run which stopped on `step`, so that the transactions sent until then
+/// are not lost.
+fn step_failed(step: &'static str, ledger: Option<LedgerRecorder>, source: BootstrapperError) -> BootstrapperError {
+    let ledger = ledger.map(LedgerRecorder::finish);
+    if let Some(ref ledger) = ledger {
+        log::info!("🧾 Transactions cost until `{}` failed :\n{}", step, ledger.cost_report);
+    }
+    BootstrapperError::StepFailed { step, ledger, source: Box::new(source) }
+}
+
+/// Returns the hashes of the settlement transactions of `step`. A failure to read the transactions
+/// back doesn't fail the run, the ledger is only incomplete.
+async fn record_transactions(ledger: &mut LedgerRecorder, clients: &Clients, step: &str) -> Vec<String> {
+    ledger.record(clients, step).await.unwrap_or_else(|err| {
+        log::warn!("⚠️ Failed to record the transactions of {} : {:?}", step, err);
+        vec![]
+    })
+}
@@ -2,11 +2,14 @@ use ethereum_instance::EthereumClient;
 use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use starknet::providers::Url;
 
-use crate::ConfigFile;
+use crate::contract_clients::declare_v0::DeclareV0;
+use crate::{BootstrapperError, BootstrapperResult, ConfigFile};
 
 pub struct Clients {
     eth_client: EthereumClient,
     provider_l2: JsonRpcClient<HttpTransport>,
+    provider_settlement: Option<JsonRpcClient<HttpTransport>>,
+    declare_v0: DeclareV0,
 }
 
 impl Clients {
@@ -14,6 +17,16 @@ impl Clients {
         &self.provider_l2
     }
 
+    /// Starknet settlement chain (`settlement_rpc`).
+    pub fn provider_settlement(&self) -> Option<&JsonRpcClient<HttpTransport>> {
+        self.provider_settlement.as_ref()
+    }
+
+    /// Backend declaring the legacy classes on the appchain (`rollup_declare_v0_seq_url`).
+    pub fn declare_v0(&self) -> &DeclareV0 {
+        &self.declare_v0
+    }
+
     pub fn eth_client(&self) -> &EthereumClient {
         &self.eth_client
     }
@@ -34,18 +47,35 @@ impl Clients {
     //     Self { eth_client: client_instance, provider_l2 }
     // }
 
-    pub async fn init_from_config(config_file: &ConfigFile) -> Self {
+    pub async fn init_from_config(config_file: &ConfigFile) -> BootstrapperResult<Self> {
+        parse_url("eth_rpc", &config_file.eth_rpc)?;
         let client_instance = EthereumClient::attach(
             Option::from(config_file.eth_rpc.clone()),
             Option::from(config_file.eth_priv_key.clone()),
             Option::from(config_file.eth_chain_id),
         )
-        .unwrap();
+        // The error is reported instead of the key.
+        .map_err(|err| BootstrapperError::InvalidConfig { field: "eth_priv_key", value: format!("{err:?}") })?;
 
-        let provider_l2 = JsonRpcClient::new(HttpTransport::new(
-            Url::parse(&config_file.rollup_seq_url).expect("Failed to declare provider for app chain"),
-        ));
+        let provider_l2 =
+            JsonRpcClient::new(HttpTransport::new(parse_url("rollup_seq_url", &config_file.rollup_seq_url)?));
 
-        Self { eth_client: client_instance, provider_l2 }
+        let provider_settlement = match config_file.settlement_rpc {
+            Some(ref settlement_rpc) => {
+                Some(JsonRpcClient::new(HttpTransport::new(parse_url("settlement_rpc", settlement_rpc)?)))
+            }
+            None => None,
+        };
+
+        let declare_v0 = DeclareV0::new(
+            config_file.declare_v0_backend.unwrap_or_default(),
+            config_file.rollup_declare_v0_seq_url.clone(),
+        );
+
+        Ok(Self { eth_client: client_instance, provider_l2, provider_settlement, declare_v0 })
     }
 }
+
+fn parse_url(field: &'static str, value: &str) -> BootstrapperResult<Url> {
+    Url::parse(value).map_err(|_| BootstrapperError::InvalidConfig { field, value: value.to_string() })
+}
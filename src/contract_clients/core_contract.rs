@@ -2,15 +2,44 @@ use std::future::Future;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use ethers::abi::AbiEncode;
+use color_eyre::eyre::eyre;
 use ethers::addressbook::Address;
-use ethers::prelude::{Bytes, I256, U256};
+use ethers::prelude::{abigen, Bytes, I256, U256};
+use ethers::providers::Middleware;
+use serde::Serialize;
 use starknet::core::types::Felt;
 use starknet_proxy_client::interfaces::proxy::{CoreContractInitData, CoreContractState};
 use zaun_utils::LocalWalletSignerMiddleware;
 
 use crate::contract_clients::config::Clients;
-use crate::utils::convert_felt_to_u256;
+use crate::contract_clients::proxy_upgrade::build_upgrade_calldata;
+use crate::utils::logging::record_tx_hash;
+use crate::utils::{convert_felt_to_u256, convert_u256_to_felt};
+
+abigen!(
+    StarknetCoreContractState,
+    r"[
+        function programHash() external view returns (uint256)
+        function configHash() external view returns (uint256)
+        function stateBlockNumber() external view returns (int256)
+        function stateRoot() external view returns (uint256)
+        function stateBlockHash() external view returns (uint256)
+        function setProgramHash(uint256 newProgramHash)
+        function setConfigHash(uint256 newConfigHash)
+    ]",
+);
+
+abigen!(
+    StarknetCoreContractOperators,
+    r"[
+        function isOperator(address user) external view returns (bool)
+        function registerOperator(address newOperator)
+        function unregisterOperator(address removedOperator)
+        function starknetIsGovernor(address user) external view returns (bool)
+        event LogOperatorAdded(address operator)
+        event LogOperatorRemoved(address operator)
+    ]",
+);
 
 #[async_trait]
 pub trait CoreContract {
@@ -18,36 +47,8 @@ pub trait CoreContract {
 
     fn implementation_address(&self) -> Address;
 
-    fn client(&self) -> Arc<LocalWalletSignerMiddleware>;
-
     async fn initialize_with(&self, init_data: CoreContractInitData);
 
-    #[allow(clippy::too_many_arguments)]
-    async fn add_implementation_core_contract(
-        &self,
-        block_number: Felt,
-        state_root: Felt,
-        block_hash: Felt,
-        program_hash: Felt,
-        config_hash: Felt,
-        implementation_address: Address,
-        verifier_address: Address,
-        finalized: bool,
-    );
-
-    #[allow(clippy::too_many_arguments)]
-    async fn upgrade_to_core_contract(
-        &self,
-        block_number: Felt,
-        state_root: Felt,
-        block_hash: Felt,
-        program_hash: Felt,
-        config_hash: Felt,
-        implementation_address: Address,
-        verifier_address: Address,
-        finalized: bool,
-    );
-
     async fn register_operator_core_contract(&self, operator_address: Address);
 
     async fn nominate_governor_core_contract(&self, l1_governor_address: Address);
@@ -93,11 +94,140 @@ pub fn get_init_data_core_contract(
 }
 
 pub fn get_calldata_bytes(calldata: CoreContractInitData) -> Bytes {
-    let mut bytes_final = Address::zero().encode();
-    let bytes: Vec<u8> = <CoreContractInitData as Into<Vec<u8>>>::into(calldata.clone());
-    for x in bytes {
-        bytes_final.push(x);
+    let bytes: Vec<u8> = <CoreContractInitData as Into<Vec<u8>>>::into(calldata);
+    build_upgrade_calldata(None, &bytes)
+}
+
+/// State of the core contract as currently stored on L1.
+#[derive(Debug, Clone, Serialize)]
+pub struct CoreContractL1State {
+    pub block_number: Felt,
+    pub state_root: Felt,
+    pub block_hash: Felt,
+    pub program_hash: Felt,
+    pub config_hash: Felt,
+}
+
+pub async fn get_core_contract_state(
+    core_contract: Address,
+    client: Arc<LocalWalletSignerMiddleware>,
+) -> color_eyre::Result<CoreContractL1State> {
+    let contract = StarknetCoreContractState::new(core_contract, client);
+
+    let block_number = contract.state_block_number().call().await?;
+    if block_number.is_negative() {
+        return Err(eyre!("Core contract state block number is negative ({block_number})"));
+    }
+
+    Ok(CoreContractL1State {
+        block_number: convert_u256_to_felt(block_number.into_raw()),
+        state_root: convert_u256_to_felt(contract.state_root().call().await?),
+        block_hash: convert_u256_to_felt(contract.state_block_hash().call().await?),
+        program_hash: convert_u256_to_felt(contract.program_hash().call().await?),
+        config_hash: convert_u256_to_felt(contract.config_hash().call().await?),
+    })
+}
+
+pub async fn set_program_hash_core_contract(
+    core_contract: Address,
+    client: Arc<LocalWalletSignerMiddleware>,
+    program_hash: Felt,
+) -> color_eyre::Result<()> {
+    let receipt = StarknetCoreContractState::new(core_contract, client)
+        .set_program_hash(convert_felt_to_u256(program_hash))
+        .send()
+        .await?
+        .await?
+        .ok_or_else(|| eyre!("setProgramHash transaction dropped"))?;
+    record_tx_hash(receipt.transaction_hash);
+    log::info!("ℹ️  set_program_hash : done");
+    Ok(())
+}
+
+pub async fn set_config_hash_core_contract(
+    core_contract: Address,
+    client: Arc<LocalWalletSignerMiddleware>,
+    config_hash: Felt,
+) -> color_eyre::Result<()> {
+    let receipt = StarknetCoreContractState::new(core_contract, client)
+        .set_config_hash(convert_felt_to_u256(config_hash))
+        .send()
+        .await?
+        .await?
+        .ok_or_else(|| eyre!("setConfigHash transaction dropped"))?;
+    record_tx_hash(receipt.transaction_hash);
+    log::info!("ℹ️  set_config_hash : done");
+    Ok(())
+}
+
+/// Blocks covered by a single `eth_getLogs` request, RPC providers cap the range of a query.
+const LOGS_PAGE_SIZE: u64 = 10_000;
+
+/// Lists the current operators of the core contract. Operators are collected from the
+/// `LogOperatorAdded` events emitted since `from_block` (the core contract deployment block) and
+/// filtered with `isOperator`.
+pub async fn get_operators_core_contract(
+    core_contract: Address,
+    client: Arc<LocalWalletSignerMiddleware>,
+    from_block: u64,
+) -> color_eyre::Result<Vec<Address>> {
+    let latest_block = client.get_block_number().await?.as_u64();
+    let contract = StarknetCoreContractOperators::new(core_contract, client);
+
+    let mut added = Vec::new();
+    for (start, end) in block_ranges(from_block, latest_block, LOGS_PAGE_SIZE) {
+        for LogOperatorAddedFilter { operator } in
+            contract.log_operator_added_filter().from_block(start).to_block(end).query().await?
+        {
+            if !added.contains(&operator) {
+                added.push(operator);
+            }
+        }
+    }
+
+    let mut operators = Vec::new();
+    for operator in added {
+        if contract.is_operator(operator).call().await? {
+            operators.push(operator);
+        }
     }
+    Ok(operators)
+}
+
+/// Splits `[from_block, to_block]` in inclusive ranges of at most `page_size` blocks.
+pub fn block_ranges(from_block: u64, to_block: u64, page_size: u64) -> Vec<(u64, u64)> {
+    (from_block..=to_block)
+        .step_by(page_size as usize)
+        .map(|start| (start, to_block.min(start + page_size - 1)))
+        .collect()
+}
+
+pub async fn is_operator_core_contract(
+    core_contract: Address,
+    client: Arc<LocalWalletSignerMiddleware>,
+    address: Address,
+) -> color_eyre::Result<bool> {
+    Ok(StarknetCoreContractOperators::new(core_contract, client).is_operator(address).call().await?)
+}
+
+pub async fn is_governor_core_contract(
+    core_contract: Address,
+    client: Arc<LocalWalletSignerMiddleware>,
+    address: Address,
+) -> color_eyre::Result<bool> {
+    Ok(StarknetCoreContractOperators::new(core_contract, client).starknet_is_governor(address).call().await?)
+}
 
-    Bytes::from(bytes_final)
+pub async fn unregister_operator_core_contract(
+    core_contract: Address,
+    client: Arc<LocalWalletSignerMiddleware>,
+    operator_address: Address,
+) -> color_eyre::Result<()> {
+    StarknetCoreContractOperators::new(core_contract, client)
+        .unregister_operator(operator_address)
+        .send()
+        .await?
+        .await?;
+    log::info!("ℹ️  unregister_operator : done");
+    Ok(())
 }
@@ -0,0 +1,326 @@
+//! Declaration of the legacy (Cairo 0) classes. Declare v0 transactions can't be sent through the
+//! Starknet JSON-RPC, each sequencer has its own way to accept them.
+
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use color_eyre::eyre::bail;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use starknet::core::types::contract::legacy::LegacyContractClass;
+use starknet::core::types::{BlockId, BlockTag, DeclareTransactionResult, Felt};
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+use starknet::providers::Provider;
+use tokio::time::sleep;
+
+use crate::contract_clients::legacy_class::CompressedLegacyContractClass;
+use crate::contract_clients::utils::BroadcastedDeclareTransactionV0;
+
+/// Attempts at sending a declaration which failed for a transient reason.
+const DECLARE_V0_ATTEMPTS: u32 = 5;
+/// Multiplied by the attempt number between two attempts.
+const DECLARE_V0_RETRY_DELAY: Duration = Duration::from_secs(2);
+/// Polls of `get_class` before giving up on a declaration.
+const CLASS_POLL_ATTEMPTS: u32 = 60;
+const CLASS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// `CLASS_ALREADY_DECLARED` of the Starknet JSON-RPC spec.
+const CLASS_ALREADY_DECLARED: i64 = 51;
+const METHOD_NOT_FOUND: i64 = -32601;
+/// Sender of the declare v0 transactions, they are not signed.
+const DECLARE_V0_SENDER: Felt = Felt::ONE;
+
+/// How the legacy classes are declared on the appchain (`declare_v0_backend` in the config).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeclareV0Backend {
+    /// Madara admin RPC if `rollup_declare_v0_seq_url` serves it, the gateway otherwise, and the
+    /// classes must be predeclared when neither answers.
+    #[default]
+    Auto,
+    /// `madara_addDeclareV0Transaction` on the admin RPC of Madara.
+    MadaraAdmin,
+    /// `add_transaction` of the feeder gateway API (`<url>/gateway/add_transaction`).
+    Gateway,
+    /// Nothing is sent, the classes must already be declared (from genesis for instance).
+    Predeclared,
+}
+
+impl fmt::Display for DeclareV0Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DeclareV0Backend::Auto => "auto",
+            DeclareV0Backend::MadaraAdmin => "madara_admin",
+            DeclareV0Backend::Gateway => "gateway",
+            DeclareV0Backend::Predeclared => "predeclared",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Declare v0 backend of the appchain, `auto` is resolved on first use.
+pub struct DeclareV0 {
+    backend: DeclareV0Backend,
+    url: String,
+    detected: OnceLock<DeclareV0Backend>,
+}
+
+impl DeclareV0 {
+    pub fn new(backend: DeclareV0Backend, url: String) -> Self {
+        Self { backend, url, detected: OnceLock::new() }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Configured backend, or the detected one for `auto`.
+    pub async fn backend(&self) -> DeclareV0Backend {
+        if self.backend != DeclareV0Backend::Auto {
+            return self.backend;
+        }
+        if let Some(backend) = self.detected.get() {
+            return *backend;
+        }
+        let backend = if self.is_served(DeclareV0Backend::MadaraAdmin).await.unwrap_or(false) {
+            DeclareV0Backend::MadaraAdmin
+        } else if self.is_served(DeclareV0Backend::Gateway).await.unwrap_or(false) {
+            DeclareV0Backend::Gateway
+        } else {
+            log::warn!("⚠️ {} serves no declare v0 backend, the legacy classes must be predeclared", self.url);
+            DeclareV0Backend::Predeclared
+        };
+        log::info!("🔎 Declare v0 backend : {}", backend);
+        *self.detected.get_or_init(|| backend)
+    }
+
+    /// Whether `url` answers to `backend`.
+    pub async fn is_served(&self, backend: DeclareV0Backend) -> color_eyre::Result<bool> {
+        match backend {
+            DeclareV0Backend::MadaraAdmin => {
+                let response: Value = reqwest::Client::new()
+                    .post(&self.url)
+                    .json(
+                        &json!({ "jsonrpc": "2.0", "method": "madara_addDeclareV0Transaction", "params": [], "id": 1 }),
+                    )
+                    .send()
+                    .await?
+                    .json()
+                    .await?;
+                Ok(response.get("result").is_some()
+                    || response["error"]["code"].as_i64().is_some_and(|code| code != METHOD_NOT_FOUND))
+            }
+            DeclareV0Backend::Gateway => {
+                let response = reqwest::Client::new().get(gateway_url(&self.url, "is_alive")).send().await?;
+                Ok(response.status().is_success())
+            }
+            DeclareV0Backend::Auto | DeclareV0Backend::Predeclared => Ok(true),
+        }
+    }
+
+    /// Declares `contract_class` through the backend, retrying the transient failures, and waits
+    /// until `provider` serves the class.
+    pub async fn declare(
+        &self,
+        contract_class: LegacyContractClass,
+        provider: &JsonRpcClient<HttpTransport>,
+    ) -> color_eyre::Result<Felt> {
+        let class_hash = contract_class.class_hash()?;
+        if provider.get_class(BlockId::Tag(BlockTag::Pending), class_hash).await.is_ok() {
+            return Ok(class_hash);
+        }
+
+        let backend = self.backend().await;
+        if backend == DeclareV0Backend::Predeclared {
+            bail!(
+                "Class {:#x} is not declared on the appchain and the `predeclared` declare v0 backend can't declare \
+                 it : add it to the genesis or use another `declare_v0_backend`",
+                class_hash
+            );
+        }
+
+        let compressed_class = contract_class.compress()?;
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let sent = match backend {
+                DeclareV0Backend::Gateway => send_gateway(&self.url, &compressed_class).await,
+                _ => send_madara_admin(&self.url, &compressed_class).await,
+            };
+            match sent {
+                Ok(transaction_hash) => {
+                    log::info!("🚧 Txn Sent Successfully : {:#x} ({})", transaction_hash, backend);
+                    break;
+                }
+                Err(err) if err.is_already_declared() => {
+                    log::info!("ℹ️ Class {:#x} is already declared", class_hash);
+                    break;
+                }
+                Err(err) if err.is_transient() && attempt < DECLARE_V0_ATTEMPTS => {
+                    log::warn!("⚠️ Declaration of {:#x} failed ({}), retrying", class_hash, err);
+                    sleep(DECLARE_V0_RETRY_DELAY * attempt).await;
+                }
+                Err(err) => bail!("{} rejected the declaration of {:#x} : {}", self.url, class_hash, err),
+            }
+        }
+
+        wait_for_class(provider, class_hash).await?;
+        Ok(class_hash)
+    }
+}
+
+/// Error object of a JSON-RPC response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<Value>,
+}
+
+impl fmt::Display for JsonRpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "JSON-RPC error {} : {}", self.code, self.message)?;
+        if let Some(data) = &self.data {
+            write!(f, " ({})", data)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse<T> {
+    result: Option<T>,
+    error: Option<JsonRpcError>,
+}
+
+/// Answer of `gateway/add_transaction`, `code` is `TRANSACTION_RECEIVED` or a
+/// `StarknetErrorCode`.
+#[derive(Debug, Deserialize)]
+struct GatewayResponse {
+    code: String,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    transaction_hash: Option<Felt>,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum DeclareV0Error {
+    #[error("{0}")]
+    Http(#[from] reqwest::Error),
+    #[error("{0}")]
+    Rpc(JsonRpcError),
+    #[error("Gateway error {code} ({status}) : {message}")]
+    Gateway { status: StatusCode, code: String, message: String },
+    #[error("Invalid response : {0}")]
+    InvalidResponse(String),
+}
+
+impl DeclareV0Error {
+    fn is_already_declared(&self) -> bool {
+        match self {
+            DeclareV0Error::Rpc(err) => err.code == CLASS_ALREADY_DECLARED,
+            DeclareV0Error::Gateway { code, .. } => code.ends_with("CLASS_ALREADY_DECLARED"),
+            _ => false,
+        }
+    }
+
+    /// Connection failures, timeouts, server errors and rate limits. The node refusing the class
+    /// is final.
+    fn is_transient(&self) -> bool {
+        match self {
+            DeclareV0Error::Http(err) => {
+                err.is_connect() || err.is_timeout() || err.status().is_some_and(|status| status.is_server_error())
+            }
+            // Internal error, limit exceeded and `UNEXPECTED_ERROR` of the Starknet spec.
+            DeclareV0Error::Rpc(err) => matches!(err.code, -32603 | -32005 | 63),
+            DeclareV0Error::Gateway { status, code, .. } => {
+                *status == StatusCode::TOO_MANY_REQUESTS
+                    || (status.is_server_error() && !code.starts_with("StarknetErrorCode."))
+            }
+            DeclareV0Error::InvalidResponse(_) => false,
+        }
+    }
+}
+
+async fn send_madara_admin(
+    url: &str,
+    compressed_class: &starknet::core::types::CompressedLegacyContractClass,
+) -> Result<Felt, DeclareV0Error> {
+    let params = BroadcastedDeclareTransactionV0 {
+        sender_address: DECLARE_V0_SENDER,
+        max_fee: Felt::ZERO,
+        signature: Vec::new(),
+        contract_class: Arc::new(CompressedLegacyContractClass::from(compressed_class.clone())),
+        is_query: false,
+    };
+    // TODO: method can be updated based on the madara PR
+    let json_body = &json!({
+        "jsonrpc": "2.0",
+        "method": "madara_addDeclareV0Transaction",
+        "params": [params],
+        "id": 4
+    });
+
+    let response = reqwest::Client::new().post(url).json(json_body).send().await?.error_for_status()?;
+    let body = response.text().await?;
+    let response: JsonRpcResponse<DeclareTransactionResult> =
+        serde_json::from_str(&body).map_err(|_| DeclareV0Error::InvalidResponse(body.clone()))?;
+    match response {
+        JsonRpcResponse { error: Some(err), .. } => Err(DeclareV0Error::Rpc(err)),
+        JsonRpcResponse { result: Some(result), .. } => Ok(result.transaction_hash),
+        JsonRpcResponse { result: None, error: None } => Err(DeclareV0Error::InvalidResponse(body)),
+    }
+}
+
+async fn send_gateway(
+    url: &str,
+    compressed_class: &starknet::core::types::CompressedLegacyContractClass,
+) -> Result<Felt, DeclareV0Error> {
+    let json_body = &json!({
+        "type": "DECLARE",
+        "version": "0x0",
+        "sender_address": DECLARE_V0_SENDER,
+        "max_fee": "0x0",
+        "signature": [],
+        "nonce": "0x0",
+        "contract_class": compressed_class,
+    });
+
+    let response = reqwest::Client::new().post(gateway_url(url, "add_transaction")).json(json_body).send().await?;
+    let status = response.status();
+    let body = response.text().await?;
+    let response: GatewayResponse =
+        serde_json::from_str(&body).map_err(|_| DeclareV0Error::InvalidResponse(format!("{} : {}", status, body)))?;
+    match response {
+        GatewayResponse { code, transaction_hash: Some(transaction_hash), .. }
+            if status.is_success() && code == "TRANSACTION_RECEIVED" =>
+        {
+            Ok(transaction_hash)
+        }
+        GatewayResponse { code, message, .. } => {
+            Err(DeclareV0Error::Gateway { status, code, message: message.unwrap_or_default() })
+        }
+    }
+}
+
+fn gateway_url(url: &str, endpoint: &str) -> String {
+    format!("{}/gateway/{}", url.trim_end_matches('/'), endpoint)
+}
+
+/// Waits until `class_hash` is served by `provider`, at the pending block.
+pub async fn wait_for_class(provider: &JsonRpcClient<HttpTransport>, class_hash: Felt) -> color_eyre::Result<()> {
+    for _ in 0..CLASS_POLL_ATTEMPTS {
+        if provider.get_class(BlockId::Tag(BlockTag::Pending), class_hash).await.is_ok() {
+            return Ok(());
+        }
+        sleep(CLASS_POLL_INTERVAL).await;
+    }
+    bail!(
+        "Class {:#x} is still not declared after {}s",
+        class_hash,
+        CLASS_POLL_ATTEMPTS as u64 * CLASS_POLL_INTERVAL.as_secs()
+    )
+}
@@ -0,0 +1,196 @@
+//! Deployment of the L2 contracts. Only the account deployed by the bootstrapper has a
+//! `deploy_contract` entrypoint, any other deployer account (OZ, Argent, Braavos) deploys through a
+//! UDC.
+
+use std::fmt;
+
+use color_eyre::eyre::{bail, eyre};
+use serde::{Deserialize, Serialize};
+use starknet::accounts::{Account, ConnectedAccount};
+use starknet::core::types::{Event, ExecutionResult, Felt, TransactionReceipt};
+use starknet::core::utils::{
+    get_contract_address, get_selector_from_name, get_udc_deployed_address, UdcUniqueSettings, UdcUniqueness,
+};
+use starknet_crypto::poseidon_hash_many;
+
+use crate::contract_clients::utils::RpcAccount;
+use crate::helpers::account_actions::{get_transaction_receipt, AccountActions};
+use crate::utils::constants::{STARKNET_CAIRO_1_UDC_ADDRESS, STARKNET_UDC_ADDRESS};
+use crate::utils::wait_for_transaction;
+use crate::ConfigFile;
+
+/// Account at `l2_deployer_address`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum L2DeployerAccount {
+    /// OZ account with a `deploy_contract` entrypoint, deployed by the bootstrapper from
+    /// `rollup_priv_key`.
+    #[default]
+    Bootstrapper,
+    Oz,
+    Argent,
+    Braavos,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeploymentMethod {
+    /// `deploy_contract(class_hash, salt, deploy_from_zero, calldata)` of the bootstrapper account.
+    Account,
+    /// `deployContract(classHash, salt, unique, calldata)` of the legacy UDC.
+    LegacyUdc,
+    /// `deploy_contract(class_hash, salt, not_from_zero, calldata)` of the Cairo 1 UDC.
+    Udc,
+}
+
+impl DeploymentMethod {
+    /// The UDC of Starknet, used when `udc_address` is not set.
+    pub fn default_udc_address(&self) -> Felt {
+        match self {
+            DeploymentMethod::Udc => Felt::from_hex_unchecked(STARKNET_CAIRO_1_UDC_ADDRESS),
+            DeploymentMethod::Account | DeploymentMethod::LegacyUdc => Felt::from_hex_unchecked(STARKNET_UDC_ADDRESS),
+        }
+    }
+}
+
+impl fmt::Display for DeploymentMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DeploymentMethod::Account => "account",
+            DeploymentMethod::LegacyUdc => "legacy_udc",
+            DeploymentMethod::Udc => "udc",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Deploys contracts from the L2 deployer account with its [`DeploymentMethod`].
+#[derive(Clone)]
+pub struct Deployer<'a> {
+    account: RpcAccount<'a>,
+    method: DeploymentMethod,
+    udc_address: Felt,
+}
+
+impl<'a> Deployer<'a> {
+    pub fn new(account: RpcAccount<'a>, method: DeploymentMethod, udc_address: Felt) -> Self {
+        Self { account, method, udc_address }
+    }
+
+    /// `l2_deployment_method` through the UDC at `udc_address`.
+    pub fn from_config(account: RpcAccount<'a>, config: &ConfigFile) -> color_eyre::Result<Self> {
+        let method = config.l2_deployment_method();
+        let udc_address = match config.udc_address {
+            Some(ref address) => Felt::from_hex(address)?,
+            None => method.default_udc_address(),
+        };
+        Ok(Self::new(account, method, udc_address))
+    }
+
+    /// Deployer of the contracts granting their roles to the caller of their constructor (the
+    /// Cairo 1 StarkGate bridges). Through a UDC that caller is the UDC, which would keep the
+    /// governance admin role, so they are deployed from the `deploy_contract` entrypoint of the
+    /// bootstrapper account whatever the `l2_deployment_method`.
+    pub fn for_caller_roles(account: RpcAccount<'a>, config: &ConfigFile) -> color_eyre::Result<Self> {
+        if config.l2_deployer_account() != L2DeployerAccount::Bootstrapper {
+            bail!(
+                "The StarkGate bridges grant their roles to the caller of their constructor : deployed through the \
+                 UDC from a {:?} account, the UDC would hold them. Use the `bootstrapper` l2_deployer_account to \
+                 deploy the bridges",
+                config.l2_deployer_account()
+            );
+        }
+        Ok(Self { method: DeploymentMethod::Account, ..Self::from_config(account, config)? })
+    }
+
+    pub fn account(&self) -> &RpcAccount<'a> {
+        &self.account
+    }
+
+    pub fn method(&self) -> DeploymentMethod {
+        self.method
+    }
+
+    /// `udc_address` of the config, or the default UDC of the method.
+    pub fn udc_address(&self) -> Felt {
+        self.udc_address
+    }
+
+    /// Contract receiving the deployments, the emitter of the `ContractDeployed` events.
+    pub fn address(&self) -> Felt {
+        match self.method {
+            DeploymentMethod::Account => self.account.address(),
+            DeploymentMethod::LegacyUdc | DeploymentMethod::Udc => self.udc_address,
+        }
+    }
+
+    /// Address of the contract [`Deployer::deploy`] deploys. Unless `unique`, it is deployed from
+    /// zero and only depends on the class, the salt and the calldata.
+    pub fn deployed_address(&self, class_hash: Felt, salt: Felt, unique: bool, constructor_calldata: &[Felt]) -> Felt {
+        if !unique {
+            return get_contract_address(salt, class_hash, constructor_calldata, Felt::ZERO);
+        }
+        let deployer_address = self.account.address();
+        match self.method {
+            DeploymentMethod::Account => get_contract_address(salt, class_hash, constructor_calldata, deployer_address),
+            DeploymentMethod::LegacyUdc => get_udc_deployed_address(
+                salt,
+                class_hash,
+                &UdcUniqueness::Unique(UdcUniqueSettings { deployer_address, udc_contract_address: self.udc_address }),
+                constructor_calldata,
+            ),
+            DeploymentMethod::Udc => get_contract_address(
+                poseidon_hash_many(&[deployer_address, salt]),
+                class_hash,
+                constructor_calldata,
+                self.udc_address,
+            ),
+        }
+    }
+
+    /// Deploys `class_hash` and returns the address of its `ContractDeployed` event.
+    pub async fn deploy(
+        &self,
+        class_hash: Felt,
+        salt: Felt,
+        unique: bool,
+        constructor_calldata: &[Felt],
+    ) -> color_eyre::Result<Felt> {
+        // The account flag is `deploy_from_zero`, the UDC ones mix the deployer in the address.
+        let (method, flag) = match self.method {
+            DeploymentMethod::Account => ("deploy_contract", !unique),
+            DeploymentMethod::LegacyUdc => ("deployContract", unique),
+            DeploymentMethod::Udc => ("deploy_contract", unique),
+        };
+        let mut calldata =
+            vec![class_hash, salt, if flag { Felt::ONE } else { Felt::ZERO }, Felt::from(constructor_calldata.len())];
+        calldata.extend_from_slice(constructor_calldata);
+
+        let txn = self.account.invoke_contract(self.address(), method, calldata, None).send().await?;
+        wait_for_transaction(self.account.provider(), txn.transaction_hash, "deploy")
+            .await
+            .map_err(|err| eyre!("{err:#}"))?;
+        let receipt = get_transaction_receipt(self.account.provider(), txn.transaction_hash).await?.receipt;
+        if let ExecutionResult::Reverted { reason } = receipt.execution_result() {
+            bail!("Deployment of {} reverted : {}", class_hash.to_hex_string(), reason);
+        }
+        let TransactionReceipt::Invoke(receipt) = receipt else {
+            bail!("{} is not an invoke transaction", txn.transaction_hash.to_hex_string());
+        };
+        let address = contract_deployed_address(&receipt.events, self.address())?;
+        log::debug!("Deployed {:?} at {:?} ({})", class_hash, address, self.method);
+        Ok(address)
+    }
+}
+
+/// Address of the `ContractDeployed` event emitted by `deployer`. The events of the account, the
+/// legacy and the Cairo 1 UDC all start their data with it. The events of other contracts (a
+/// constructor deploying through a UDC) are ignored.
+pub fn contract_deployed_address(events: &[Event], deployer: Felt) -> color_eyre::Result<Felt> {
+    let selector = get_selector_from_name("ContractDeployed")?;
+    events
+        .iter()
+        .find(|event| event.from_address == deployer && event.keys.first() == Some(&selector))
+        .and_then(|event| event.data.first().copied())
+        .ok_or_else(|| eyre!("No ContractDeployed event emitted by {}", deployer.to_hex_string()))
+}
@@ -3,8 +3,8 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use ethers::addressbook::Address;
 use ethers::providers::Middleware;
-use ethers::types::{Bytes, U256};
-use starknet::accounts::{Account, ConnectedAccount};
+use ethers::types::U256;
+use starknet::accounts::Account;
 use starknet::core::types::Felt;
 use starknet_eth_bridge_client::clients::eth_bridge::StarknetEthBridgeContractClient;
 use starknet_eth_bridge_client::interfaces::eth_bridge::StarknetEthBridgeTrait;
@@ -16,9 +16,10 @@ use starknet_providers::JsonRpcClient;
 use starknet_proxy_client::interfaces::proxy::ProxySupport3_0_2Trait;
 use zaun_utils::{LocalWalletSignerMiddleware, StarknetContractClient};
 
+use crate::contract_clients::deployer::Deployer;
+use crate::contract_clients::proxy_upgrade::{build_upgrade_calldata, ImplementationSource, ProxyUpgrade};
 use crate::contract_clients::utils::{field_element_to_u256, RpcAccount};
-use crate::helpers::account_actions::{get_contract_address_from_deploy_tx, AccountActions};
-use crate::utils::{invoke_contract, wait_for_transaction};
+use crate::utils::{invoke_contract, pad_bytes, wait_for_transaction};
 
 #[async_trait]
 pub trait BridgeDeployable {
@@ -47,6 +48,12 @@ impl BridgeDeployable for StarknetLegacyEthBridge {
 }
 
 impl StarknetLegacyEthBridge {
+    /// Client of an already deployed bridge proxy. The implementation is unknown, it can't be
+    /// upgraded through this client.
+    pub fn at(address: Address, client: Arc<LocalWalletSignerMiddleware>) -> Self {
+        Self { eth_bridge: StarknetEthBridgeContractClient::new(address, client, Address::zero()) }
+    }
+
     pub fn address(&self) -> Address {
         self.eth_bridge.address()
     }
@@ -63,26 +70,13 @@ impl StarknetLegacyEthBridge {
         rpc_provider_l2: &JsonRpcClient<HttpTransport>,
         legacy_eth_bridge_class_hash: Felt,
         legacy_eth_bridge_proxy_address: Felt,
-        account: &RpcAccount<'_>,
+        deployer: &Deployer<'_>,
     ) -> Felt {
-        let deploy_tx = account
-            .invoke_contract(
-                account.address(),
-                "deploy_contract",
-                vec![legacy_eth_bridge_class_hash, Felt::ZERO, Felt::ZERO, Felt::ZERO],
-                None,
-            )
-            .send()
+        let account = deployer.account();
+        let contract_address = deployer
+            .deploy(legacy_eth_bridge_class_hash, Felt::ZERO, true, &[])
             .await
             .expect("Error deploying the contract proxy.");
-        wait_for_transaction(
-            rpc_provider_l2,
-            deploy_tx.transaction_hash,
-            "deploy_l2_contracts : deploy_contract : eth bridge",
-        )
-        .await
-        .unwrap();
-        let contract_address = get_contract_address_from_deploy_tx(account.provider(), &deploy_tx).await.unwrap();
 
         log::info!("🎡 contract address (eth bridge) : {:?}", contract_address);
 
@@ -124,74 +118,29 @@ impl StarknetLegacyEthBridge {
     /// Initialize Starknet Legacy Eth Bridge
     /// IMP : only need to be called when using unsafe proxy
     pub async fn initialize(&self, messaging_contract: Address) {
-        let empty_bytes = [0u8; 32];
-
-        let messaging_bytes = messaging_contract.as_bytes();
-
-        let mut padded_messaging_bytes = Vec::with_capacity(32);
-        padded_messaging_bytes.extend(vec![0u8; 32 - messaging_bytes.len()]);
-        padded_messaging_bytes.extend_from_slice(messaging_bytes);
-
-        let mut calldata = Vec::new();
-        calldata.extend(empty_bytes);
-        calldata.extend(empty_bytes);
-        calldata.extend(padded_messaging_bytes);
-
-        self.eth_bridge.initialize(Bytes::from(calldata)).await.expect("Failed to initialize eth bridge");
-    }
-
-    /// Add Implementation Starknet Legacy Eth Bridge
-    pub async fn add_implementation_eth_bridge(&self, messaging_contract: Address) {
-        let empty_bytes = [0u8; 32];
-
-        let messaging_bytes = messaging_contract.as_bytes();
-
-        let mut padded_messaging_bytes = Vec::with_capacity(32);
-        padded_messaging_bytes.extend(vec![0u8; 32 - messaging_bytes.len()]);
-        padded_messaging_bytes.extend_from_slice(messaging_bytes);
-
-        let mut calldata = Vec::new();
-        // `empty_bytes` act as an empty params for the calldata we are passing in bytes.
-        // Here in this case of ETH Bridge it represents the EIC contract address, Token Address (ETH)
-        // EIC = 0x0000000000000000000000000000000000000000
-        // ETH Address to be passed in bridge = 0x0000000000000000000000000000000000000000
-        calldata.extend(empty_bytes);
-        calldata.extend(empty_bytes);
-        calldata.extend(padded_messaging_bytes);
-
-        log::info!("🎡 add_implementation_eth_bridge : bytes : {:?}", Bytes::from(calldata.clone()));
-
         self.eth_bridge
-            .add_implementation(Bytes::from(calldata), self.implementation_address(), false)
+            .initialize(build_upgrade_calldata(None, &Self::init_data(messaging_contract)))
             .await
             .expect("Failed to initialize eth bridge");
     }
 
-    /// Upgrade To Starknet Legacy Eth Bridge
-    pub async fn upgrade_to_eth_bridge(&self, messaging_contract: Address) {
-        let empty_bytes = [0u8; 32];
-
-        let messaging_bytes = messaging_contract.as_bytes();
-
-        let mut padded_messaging_bytes = Vec::with_capacity(32);
-        padded_messaging_bytes.extend(vec![0u8; 32 - messaging_bytes.len()]);
-        padded_messaging_bytes.extend_from_slice(messaging_bytes);
-
-        let mut calldata = Vec::new();
-        // `empty_bytes` act as an empty params for the calldata we are passing in bytes.
-        // Here in this case of ETH Bridge it represents the EIC contract address, Token Address (ETH)
-        // EIC = 0x0000000000000000000000000000000000000000
-        // ETH Address to be passed in bridge = 0x0000000000000000000000000000000000000000
-        calldata.extend(empty_bytes);
-        calldata.extend(empty_bytes);
-        calldata.extend(padded_messaging_bytes);
-
-        log::info!("🎡 upgrade_to_eth_bridge : bytes : {:?}", Bytes::from(calldata.clone()));
+    /// Init data of the proxy : [ETH token address, messaging contract].
+    ///
+    /// The ETH token address is left empty for the legacy ETH bridge.
+    fn init_data(messaging_contract: Address) -> Vec<u8> {
+        [[0u8; 32].to_vec(), pad_bytes(messaging_contract)].concat()
+    }
 
-        self.eth_bridge
-            .upgrade_to(Bytes::from(calldata), self.implementation_address(), false)
-            .await
-            .expect("Failed to initialize eth bridge");
+    /// Points the safe proxy to the implementation deployed along with it.
+    pub fn proxy_upgrade(&self, messaging_contract: Address) -> ProxyUpgrade {
+        ProxyUpgrade {
+            proxy_address: self.address(),
+            implementation: ImplementationSource::Deployed(self.implementation_address()),
+            eic: None,
+            init_data: Self::init_data(messaging_contract),
+            finalize: false,
+            post_upgrade: vec![],
+        }
     }
 
     /// Sets up the Eth bridge with the specified data
@@ -213,12 +162,13 @@ impl StarknetLegacyEthBridge {
         }
     }
 
+    /// Initializes the L2 bridge and points it to `l1_bridge` (its settlement layer counterpart).
     pub async fn setup_l2_bridge(
-        &self,
         rpc_provider: &JsonRpcClient<HttpTransport>,
         l2_bridge_address: Felt,
         erc20_address: Felt,
         l2_deployer_address: &str,
+        l1_bridge: Felt,
         account: &RpcAccount<'_>,
     ) {
         let tx = invoke_contract(
@@ -237,13 +187,7 @@ impl StarknetLegacyEthBridge {
         log::info!("🎡 setup_l2_bridge : l2 token set //");
         wait_for_transaction(rpc_provider, tx.transaction_hash, "setup_l2_bridge : set_l2_token").await.unwrap();
 
-        let tx = invoke_contract(
-            l2_bridge_address,
-            "set_l1_bridge",
-            vec![Felt::from_bytes_be_slice(self.eth_bridge.address().as_bytes())],
-            account,
-        )
-        .await;
+        let tx = invoke_contract(l2_bridge_address, "set_l1_bridge", vec![l1_bridge], account).await;
 
         log::info!("🎡 setup_l2_bridge : l1 bridge set //");
         wait_for_transaction(rpc_provider, tx.transaction_hash, "setup_l2_bridge : set_l1_bridge").await.unwrap();
@@ -0,0 +1,176 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::eyre;
+use ethers::abi::AbiEncode;
+use ethers::prelude::abigen;
+use ethers::types::{Address, Bytes};
+use serde::{Deserialize, Serialize};
+use zaun_utils::LocalWalletSignerMiddleware;
+
+abigen!(
+    StarkwareGovernance,
+    r"[
+        function starknetIsGovernor(address user) external view returns (bool)
+        function starknetAcceptGovernance()
+        function starknetRemoveGovernor(address governorForRemoval)
+        function proxyIsGovernor(address user) external view returns (bool)
+        function proxyAcceptGovernance()
+        function proxyRemoveGovernor(address governorForRemoval)
+    ]",
+);
+
+/// StarkWare contracts have two independent governance sets : the one of the implementation
+/// (`starknet*` functions of the core contract) and the one of the proxy (`proxy*` functions).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GovernanceKind {
+    Starknet,
+    Proxy,
+}
+
+/// A `nominateNewGovernor` sent by the bootstrapper. Nomination is only the first step, the
+/// nominee becomes governor once it calls `acceptGovernance`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GovernanceNomination {
+    pub contract_address: Address,
+    pub kind: GovernanceKind,
+    pub nominee: Address,
+    pub accepted: bool,
+}
+
+impl GovernanceNomination {
+    pub fn new(contract_address: Address, kind: GovernanceKind, nominee: Address) -> Self {
+        Self { contract_address, kind, nominee, accepted: false }
+    }
+
+    /// Calldata of the `acceptGovernance` call which has to be sent by the nominee.
+    pub fn accept_governance_calldata(&self) -> Bytes {
+        match self.kind {
+            GovernanceKind::Starknet => StarknetAcceptGovernanceCall.encode().into(),
+            GovernanceKind::Proxy => ProxyAcceptGovernanceCall.encode().into(),
+        }
+    }
+
+    /// Calldata of the `removeGovernor` call. Governors can't remove themselves so this has to be
+    /// sent by another governor (usually the nominee once it accepted).
+    pub fn remove_governor_calldata(&self, governor: Address) -> Bytes {
+        match self.kind {
+            GovernanceKind::Starknet => StarknetRemoveGovernorCall { governor_for_removal: governor }.encode().into(),
+            GovernanceKind::Proxy => ProxyRemoveGovernorCall { governor_for_removal: governor }.encode().into(),
+        }
+    }
+}
+
+/// Sends the governance calls of [`GovernanceNomination`]s and reads the governance status.
+pub struct GovernanceClient {
+    client: Arc<LocalWalletSignerMiddleware>,
+}
+
+impl GovernanceClient {
+    pub fn new(client: Arc<LocalWalletSignerMiddleware>) -> Self {
+        Self { client }
+    }
+
+    pub async fn is_governor(
+        &self,
+        contract_address: Address,
+        kind: GovernanceKind,
+        address: Address,
+    ) -> color_eyre::Result<bool> {
+        let contract = StarkwareGovernance::new(contract_address, self.client.clone());
+        Ok(match kind {
+            GovernanceKind::Starknet => contract.starknet_is_governor(address).call().await?,
+            GovernanceKind::Proxy => contract.proxy_is_governor(address).call().await?,
+        })
+    }
+
+    /// Refreshes `accepted` from the chain.
+    pub async fn refresh(&self, nomination: &mut GovernanceNomination) -> color_eyre::Result<()> {
+        nomination.accepted =
+            self.is_governor(nomination.contract_address, nomination.kind, nomination.nominee).await?;
+        Ok(())
+    }
+
+    /// Sends `acceptGovernance`. The client must be the nominee.
+    pub async fn accept_governance(&self, nomination: &mut GovernanceNomination) -> color_eyre::Result<()> {
+        let contract = StarkwareGovernance::new(nomination.contract_address, self.client.clone());
+        let call = match nomination.kind {
+            GovernanceKind::Starknet => contract.starknet_accept_governance(),
+            GovernanceKind::Proxy => contract.proxy_accept_governance(),
+        };
+        call.send().await?.await?.ok_or_else(|| eyre!("acceptGovernance transaction dropped"))?;
+
+        self.refresh(nomination).await?;
+        if !nomination.accepted {
+            return Err(eyre!(
+                "{:?} is not a {:?} governor of {:?} after acceptGovernance",
+                nomination.nominee,
+                nomination.kind,
+                nomination.contract_address
+            ));
+        }
+        log::info!(
+            "🏛️ Governance of {:?} ({:?}) accepted by {:?}",
+            nomination.contract_address,
+            nomination.kind,
+            nomination.nominee
+        );
+        Ok(())
+    }
+
+    /// Sends `removeGovernor`. The client must be a governor other than `governor`.
+    pub async fn remove_governor(
+        &self,
+        contract_address: Address,
+        kind: GovernanceKind,
+        governor: Address,
+    ) -> color_eyre::Result<()> {
+        let contract = StarkwareGovernance::new(contract_address, self.client.clone());
+        let call = match kind {
+            GovernanceKind::Starknet => contract.starknet_remove_governor(governor),
+            GovernanceKind::Proxy => contract.proxy_remove_governor(governor),
+        };
+        call.send().await?.await?.ok_or_else(|| eyre!("removeGovernor transaction dropped"))?;
+        log::info!("🏛️ {:?} removed from the {:?} governors of {:?}", governor, kind, contract_address);
+        Ok(())
+    }
+}
+
+/// Transaction batch in the format of the Safe{Wallet} transaction builder, used when the nominee
+/// is a multisig and can't be driven by the bootstrapper.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeTransactionBatch {
+    pub version: String,
+    pub chain_id: String,
+    pub meta: SafeBatchMeta,
+    pub transactions: Vec<SafeTransaction>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SafeBatchMeta {
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SafeTransaction {
+    pub to: Address,
+    pub value: String,
+    pub data: Bytes,
+}
+
+impl SafeTransactionBatch {
+    pub fn new(chain_id: u64, name: &str, description: &str) -> Self {
+        Self {
+            version: "1.0".to_string(),
+            chain_id: chain_id.to_string(),
+            meta: SafeBatchMeta { name: name.to_string(), description: description.to_string() },
+            transactions: vec![],
+        }
+    }
+
+    pub fn push(&mut self, to: Address, data: Bytes) {
+        self.transactions.push(SafeTransaction { to, value: "0".to_string(), data });
+    }
+}
@@ -1,6 +1,11 @@
 pub mod config;
 pub mod core_contract;
+pub mod declare_v0;
+pub mod deployer;
 pub mod eth_bridge;
+pub mod governance;
+pub mod piltover;
+pub mod proxy_upgrade;
 pub mod starknet_core_contract;
 pub mod starknet_dev_core_contract;
 pub mod token_bridge;
@@ -0,0 +1,78 @@
+use color_eyre::eyre::eyre;
+use starknet::accounts::ConnectedAccount;
+use starknet::core::types::{BlockId, BlockTag, Felt, FunctionCall};
+use starknet::core::utils::get_selector_from_name;
+use starknet_providers::Provider;
+
+use crate::contract_clients::utils::RpcAccount;
+use crate::utils::invoke_contract;
+
+/// Client of the Cairo appchain core contract ([Piltover](https://github.com/keep-starknet-strange/piltover))
+/// deployed on a Starknet settlement chain.
+pub struct PiltoverCoreContract<'a> {
+    account: RpcAccount<'a>,
+    address: Felt,
+}
+
+impl<'a> PiltoverCoreContract<'a> {
+    pub fn new(account: RpcAccount<'a>, address: Felt) -> Self {
+        Self { account, address }
+    }
+
+    pub fn address(&self) -> Felt {
+        self.address
+    }
+
+    /// Constructor calldata : `owner`, `state_root`, `block_number`, `block_hash`.
+    pub fn constructor_calldata(owner: Felt, state_root: Felt, block_number: Felt, block_hash: Felt) -> Vec<Felt> {
+        vec![owner, state_root, block_number, block_hash]
+    }
+
+    pub async fn set_program_info(&self, program_hash: Felt, config_hash: Felt) {
+        invoke_contract(self.address, "set_program_info", vec![program_hash, config_hash], &self.account).await;
+    }
+
+    pub async fn set_facts_registry(&self, facts_registry: Felt) {
+        invoke_contract(self.address, "set_facts_registry", vec![facts_registry], &self.account).await;
+    }
+
+    pub async fn register_operator(&self, operator: Felt) {
+        invoke_contract(self.address, "register_operator", vec![operator], &self.account).await;
+    }
+
+    /// OpenZeppelin `Ownable` : the new owner doesn't have to accept the ownership.
+    pub async fn transfer_ownership(&self, new_owner: Felt) {
+        invoke_contract(self.address, "transfer_ownership", vec![new_owner], &self.account).await;
+    }
+
+    pub async fn owner(&self) -> color_eyre::Result<Felt> {
+        self.call("owner", vec![]).await?.first().copied().ok_or_else(|| eyre!("owner returned no value"))
+    }
+
+    pub async fn is_operator(&self, operator: Felt) -> color_eyre::Result<bool> {
+        Ok(self.call("is_operator", vec![operator]).await?.first() == Some(&Felt::ONE))
+    }
+
+    /// `(state_root, block_number, block_hash)` of the last state update.
+    pub async fn get_state(&self) -> color_eyre::Result<(Felt, Felt, Felt)> {
+        match self.call("get_state", vec![]).await?.as_slice() {
+            [state_root, block_number, block_hash, ..] => Ok((*state_root, *block_number, *block_hash)),
+            state => Err(eyre!("Unexpected appchain state : {:?}", state)),
+        }
+    }
+
+    async fn call(&self, method: &str, calldata: Vec<Felt>) -> color_eyre::Result<Vec<Felt>> {
+        Ok(self
+            .account
+            .provider()
+            .call(
+                FunctionCall {
+                    contract_address: self.address,
+                    entry_point_selector: get_selector_from_name(method)?,
+                    calldata,
+                },
+                BlockId::Tag(BlockTag::Pending),
+            )
+            .await?)
+    }
+}
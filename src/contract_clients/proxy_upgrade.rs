@@ -0,0 +1,278 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::{eyre, WrapErr};
+use ethers::abi::Abi;
+use ethers::contract::ContractFactory;
+use ethers::prelude::abigen;
+use ethers::providers::Middleware;
+use ethers::types::{Address, BlockNumber, Bytes, TransactionRequest, U256};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::Instrument;
+use zaun_utils::LocalWalletSignerMiddleware;
+
+use crate::utils::artifacts::{load_json_artifact, solidity_bytecode};
+use crate::utils::logging::{record_tx_hash, sub_step};
+use crate::utils::pad_bytes;
+
+abigen!(
+    StarkgateProxy,
+    r"[
+        function addImplementation(address newImplementation, bytes data, bool finalize)
+        function upgradeTo(address newImplementation, bytes data, bool finalize)
+        function implementation() external view returns (address)
+        function getUpgradeActivationDelay() external view returns (uint256)
+    ]",
+);
+
+/// Where the new implementation (or EIC) of a proxy upgrade comes from.
+#[derive(Debug, Clone)]
+pub enum ImplementationSource {
+    /// Path to a solidity artifact (json containing `abi` and `bytecode`) which will be deployed.
+    Artifact(String),
+    /// An implementation which is already deployed on L1.
+    Deployed(Address),
+}
+
+/// Describes an upgrade of a StarkWare style proxy (StarkGate bridges, manager, registry and the
+/// core contract all sit behind the same proxy).
+#[derive(Debug, Clone)]
+pub struct ProxyUpgrade {
+    pub proxy_address: Address,
+    pub implementation: ImplementationSource,
+    pub eic: Option<ImplementationSource>,
+    /// Init data passed to the EIC (or the implementation when there is no EIC). The EIC address
+    /// word is prepended by the engine.
+    pub init_data: Vec<u8>,
+    pub finalize: bool,
+    /// Calls sent to the proxy once it points to the new implementation.
+    pub post_upgrade: Vec<PostUpgradeCall>,
+}
+
+/// Call to the upgraded proxy (role registration, limits...), kept with a pending upgrade so that
+/// `apply-pending-upgrades` sends it after `upgradeTo`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostUpgradeCall {
+    pub description: String,
+    pub calldata: Bytes,
+}
+
+/// An implementation which has been added to the proxy. Stored in the output when the proxy has
+/// an activation delay so that `upgradeTo` can be sent in a later run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreparedProxyUpgrade {
+    pub proxy_address: Address,
+    pub implementation_address: Address,
+    pub eic_address: Option<Address>,
+    pub calldata: Bytes,
+    pub finalize: bool,
+    /// Unix timestamp after which `upgradeTo` can be called.
+    pub activation_timestamp: U256,
+    pub upgraded: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_upgrade: Vec<PostUpgradeCall>,
+}
+
+/// Runs the add implementation -> (activation delay) -> upgrade to flow for StarkWare proxies.
+pub struct L1ProxyUpgrader {
+    client: Arc<LocalWalletSignerMiddleware>,
+}
+
+impl L1ProxyUpgrader {
+    pub fn new(client: Arc<LocalWalletSignerMiddleware>) -> Self {
+        Self { client }
+    }
+
+    /// Deploys a contract from a solidity artifact with an empty constructor.
+    pub async fn deploy_artifact(&self, artifact_path: &str) -> color_eyre::Result<Address> {
+        let (abi, bytecode) = read_solidity_artifact(artifact_path)?;
+        let factory = ContractFactory::new(abi, bytecode, self.client.clone());
+        let deployer = factory.deploy(())?;
+        let contract = async {
+            let (contract, receipt) =
+                deployer.send_with_receipt().await.wrap_err_with(|| format!("Failed to deploy {artifact_path}"))?;
+            record_tx_hash(receipt.transaction_hash);
+            color_eyre::Result::<_>::Ok(contract)
+        }
+        .instrument(sub_step!("proxy_upgrade.deploy", "settlement", artifact = artifact_path))
+        .await?;
+        log::debug!("Deployed {} : {:?}", artifact_path, contract.address());
+        Ok(contract.address())
+    }
+
+    pub async fn upgrade_activation_delay(&self, proxy_address: Address) -> color_eyre::Result<U256> {
+        Ok(StarkgateProxy::new(proxy_address, self.client.clone()).get_upgrade_activation_delay().call().await?)
+    }
+
+    pub async fn implementation(&self, proxy_address: Address) -> color_eyre::Result<Address> {
+        Ok(StarkgateProxy::new(proxy_address, self.client.clone()).implementation().call().await?)
+    }
+
+    /// Deploys the implementation and EIC (if needed) and calls `addImplementation` on the proxy.
+    pub async fn add_implementation(&self, upgrade: &ProxyUpgrade) -> color_eyre::Result<PreparedProxyUpgrade> {
+        let implementation_address = self.resolve(&upgrade.implementation).await?;
+        let eic_address = match &upgrade.eic {
+            Some(eic) => Some(self.resolve(eic).await?),
+            None => None,
+        };
+        let calldata = build_upgrade_calldata(eic_address, &upgrade.init_data);
+
+        let delay = self.upgrade_activation_delay(upgrade.proxy_address).await?;
+        let proxy = StarkgateProxy::new(upgrade.proxy_address, self.client.clone());
+        async {
+            let receipt = proxy
+                .add_implementation(implementation_address, calldata.clone(), upgrade.finalize)
+                .send()
+                .await?
+                .await?
+                .ok_or_else(|| eyre!("addImplementation transaction dropped"))?;
+            record_tx_hash(receipt.transaction_hash);
+            color_eyre::Result::<()>::Ok(())
+        }
+        .instrument(sub_step!("proxy_upgrade.add_implementation", "settlement", proxy = ?upgrade.proxy_address))
+        .await?;
+        log::info!(
+            "🔁 Implementation {:?} added to proxy {:?} (activation delay : {} secs)",
+            implementation_address,
+            upgrade.proxy_address,
+            delay
+        );
+
+        let now = self.latest_block_timestamp().await?;
+        Ok(PreparedProxyUpgrade {
+            proxy_address: upgrade.proxy_address,
+            implementation_address,
+            eic_address,
+            calldata,
+            finalize: upgrade.finalize,
+            activation_timestamp: now + delay,
+            upgraded: false,
+            post_upgrade: upgrade.post_upgrade.clone(),
+        })
+    }
+
+    /// Calls `upgradeTo` for an implementation previously added to the proxy, verifies that the
+    /// proxy now points to it and sends the post upgrade calls. The upgrade is only marked
+    /// `upgraded` once all of them went through.
+    pub async fn upgrade_to(&self, prepared: &mut PreparedProxyUpgrade) -> color_eyre::Result<()> {
+        let now = self.latest_block_timestamp().await?;
+        if now < prepared.activation_timestamp {
+            return Err(eyre!(
+                "Implementation {:?} for proxy {:?} is not enabled yet. It can be activated after timestamp {}",
+                prepared.implementation_address,
+                prepared.proxy_address,
+                prepared.activation_timestamp
+            ));
+        }
+
+        let proxy = StarkgateProxy::new(prepared.proxy_address, self.client.clone());
+        async {
+            let receipt = proxy
+                .upgrade_to(prepared.implementation_address, prepared.calldata.clone(), prepared.finalize)
+                .send()
+                .await?
+                .await?
+                .ok_or_else(|| eyre!("upgradeTo transaction dropped"))?;
+            record_tx_hash(receipt.transaction_hash);
+            color_eyre::Result::<()>::Ok(())
+        }
+        .instrument(sub_step!("proxy_upgrade.upgrade_to", "settlement", proxy = ?prepared.proxy_address))
+        .await?;
+
+        let implementation = self.implementation(prepared.proxy_address).await?;
+        if implementation != prepared.implementation_address {
+            return Err(eyre!(
+                "Proxy {:?} points to {:?} after upgrade, expected {:?}",
+                prepared.proxy_address,
+                implementation,
+                prepared.implementation_address
+            ));
+        }
+        log::info!("🔁 Proxy {:?} upgraded to {:?}", prepared.proxy_address, implementation);
+
+        for call in &prepared.post_upgrade {
+            let transaction = TransactionRequest::new().to(prepared.proxy_address).data(call.calldata.clone());
+            async {
+                let receipt = self
+                    .client
+                    .send_transaction(transaction, None)
+                    .await?
+                    .await?
+                    .ok_or_else(|| eyre!("{} transaction dropped", call.description))?;
+                record_tx_hash(receipt.transaction_hash);
+                color_eyre::Result::<()>::Ok(())
+            }
+            .instrument(sub_step!(
+                "proxy_upgrade.post_upgrade_call",
+                "settlement",
+                proxy = ?prepared.proxy_address,
+                call = %call.description
+            ))
+            .await?;
+            log::debug!("🔁 Proxy {:?} : {} ✅", prepared.proxy_address, call.description);
+        }
+        prepared.upgraded = true;
+        Ok(())
+    }
+
+    /// Adds the implementation and upgrades right away when the proxy has no activation delay.
+    /// Otherwise the upgrade is returned un-applied so that it can be finished later with
+    /// [`L1ProxyUpgrader::upgrade_to`].
+    pub async fn upgrade(&self, upgrade: &ProxyUpgrade) -> color_eyre::Result<PreparedProxyUpgrade> {
+        let mut prepared = self.add_implementation(upgrade).await?;
+        if self.upgrade_activation_delay(upgrade.proxy_address).await?.is_zero() {
+            self.upgrade_to(&mut prepared).await?;
+        } else {
+            log::warn!(
+                "⏳ Proxy {:?} has an activation delay, upgradeTo can be called after timestamp {}",
+                prepared.proxy_address,
+                prepared.activation_timestamp
+            );
+        }
+        Ok(prepared)
+    }
+
+    /// Points a freshly deployed proxy to its implementation. The contract is unusable until then,
+    /// so a proxy which can't be upgraded right away is an error.
+    pub async fn initialize_proxy(&self, upgrade: &ProxyUpgrade) -> color_eyre::Result<PreparedProxyUpgrade> {
+        let prepared = self.upgrade(upgrade).await?;
+        if !prepared.upgraded {
+            return Err(eyre!(
+                "Proxy {:?} has an activation delay, it can't be initialised before timestamp {}",
+                prepared.proxy_address,
+                prepared.activation_timestamp
+            ));
+        }
+        Ok(prepared)
+    }
+
+    async fn resolve(&self, source: &ImplementationSource) -> color_eyre::Result<Address> {
+        match source {
+            ImplementationSource::Artifact(path) => self.deploy_artifact(path).await,
+            ImplementationSource::Deployed(address) => Ok(*address),
+        }
+    }
+
+    async fn latest_block_timestamp(&self) -> color_eyre::Result<U256> {
+        let block = self
+            .client
+            .get_block(BlockNumber::Latest)
+            .await?
+            .ok_or_else(|| eyre!("Unable to fetch the latest L1 block"))?;
+        Ok(block.timestamp)
+    }
+}
+
+/// StarkWare proxies expect the calldata as : [eic_address (32 bytes), init_data...].
+pub fn build_upgrade_calldata(eic_address: Option<Address>, init_data: &[u8]) -> Bytes {
+    let mut calldata = pad_bytes(eic_address.unwrap_or_default());
+    calldata.extend_from_slice(init_data);
+    Bytes::from(calldata)
+}
+
+fn read_solidity_artifact(artifact_path: &str) -> color_eyre::Result<(Abi, Bytes)> {
+    let artifact: Value = load_json_artifact(artifact_path)?;
+    let abi: Abi = serde_json::from_value(artifact["abi"].clone())?;
+    let bytecode = solidity_bytecode(&artifact).wrap_err_with(|| format!("Invalid artifact {artifact_path}"))?;
+    Ok((abi, bytecode))
+}
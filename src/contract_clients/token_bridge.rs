@@ -4,7 +4,6 @@ use std::time::Duration;
 use async_trait::async_trait;
 use ethers::addressbook::Address;
 use ethers::prelude::U256;
-use ethers::types::Bytes;
 use starkgate_manager_client::clients::starkgate_manager::StarkgateManagerContractClient;
 use starkgate_manager_client::interfaces::manager::StarkgateManagerTrait;
 use starkgate_manager_client::{
@@ -14,7 +13,6 @@ use starkgate_registry_client::clients::starkgate_registry::StarkgateRegistryCon
 use starkgate_registry_client::{
     deploy_starkgate_registry_behind_safe_proxy, deploy_starkgate_registry_behind_unsafe_proxy,
 };
-use starknet::accounts::{Account, ConnectedAccount};
 use starknet_core::types::Felt;
 use starknet_erc20_client::clients::erc20::ERC20ContractClient;
 use starknet_erc20_client::deploy_dai_test_erc20_behind_unsafe_proxy;
@@ -30,11 +28,10 @@ use starknet_token_bridge_client::{
 use tokio::time::sleep;
 use zaun_utils::{LocalWalletSignerMiddleware, StarknetContractClient};
 
+use crate::contract_clients::deployer::Deployer;
 use crate::contract_clients::eth_bridge::BridgeDeployable;
-use crate::contract_clients::utils::{
-    build_single_owner_account, declare_contract, field_element_to_u256, DeclarationInput, RpcAccount,
-};
-use crate::helpers::account_actions::{get_contract_address_from_deploy_tx, AccountActions};
+use crate::contract_clients::proxy_upgrade::{build_upgrade_calldata, ImplementationSource, ProxyUpgrade};
+use crate::contract_clients::utils::{declare_contract, field_element_to_u256, DeclarationInput, RpcAccount};
 use crate::utils::constants::{TOKEN_BRIDGE_CASM_PATH, TOKEN_BRIDGE_SIERRA_PATH};
 use crate::utils::{invoke_contract, pad_bytes, save_to_json, wait_for_transaction, JsonValueType};
 
@@ -83,6 +80,23 @@ impl BridgeDeployable for StarknetTokenBridge {
 }
 
 impl StarknetTokenBridge {
+    /// Clients of already deployed proxies. The implementations are unknown, they can't be upgraded
+    /// through these clients.
+    pub fn at(
+        manager: Address,
+        registry: Address,
+        token_bridge: Address,
+        erc20: Address,
+        client: Arc<LocalWalletSignerMiddleware>,
+    ) -> Self {
+        Self {
+            manager: StarkgateManagerContractClient::new(manager, client.clone(), Address::zero()),
+            registry: StarkgateRegistryContractClient::new(registry, client.clone(), Address::zero()),
+            token_bridge: StarknetTokenBridgeContractClient::new(token_bridge, client.clone(), Address::zero()),
+            erc20: ERC20ContractClient::new(erc20, client, Address::zero()),
+        }
+    }
+
     pub fn manager_address(&self) -> Address {
         self.manager.address()
     }
@@ -109,78 +123,52 @@ impl StarknetTokenBridge {
         self.erc20.client()
     }
 
-    pub async fn deploy_l2_contracts(
-        rpc_provider_l2: &JsonRpcClient<HttpTransport>,
-        priv_key: &str,
-        l2_deployer_address: &str,
-    ) -> Felt {
-        let account = build_single_owner_account(rpc_provider_l2, priv_key, l2_deployer_address, false).await;
-
+    pub async fn deploy_l2_contracts(deployer: &Deployer<'_>) -> color_eyre::Result<Felt> {
         let token_bridge_class_hash = declare_contract(DeclarationInput::DeclarationInputs(
             String::from(TOKEN_BRIDGE_SIERRA_PATH),
             String::from(TOKEN_BRIDGE_CASM_PATH),
-            account.clone(),
+            deployer.account().clone(),
         ))
-        .await;
+        .await?;
         sleep(Duration::from_secs(10)).await;
         save_to_json("L2_token_bridge_class_hash", &JsonValueType::StringType(token_bridge_class_hash.to_string()))
             .unwrap();
 
         log::info!("🌗 token_bridge_class_hash : {:?}", token_bridge_class_hash);
 
-        let deploy_contract_implementation_token_bridge = account
-            .invoke_contract(
-                account.address(),
-                "deploy_contract",
-                vec![token_bridge_class_hash, Felt::ZERO, Felt::ZERO, Felt::ONE, Felt::ZERO],
-                None,
-            )
-            .send()
+        let address_token_bridge_impl = deployer
+            .deploy(token_bridge_class_hash, Felt::ZERO, true, &[Felt::ZERO])
             .await
             .expect("Error deploying the l2 contract proxy.");
-        wait_for_transaction(
-            account.provider(),
-            deploy_contract_implementation_token_bridge.transaction_hash,
-            "deploy_l2_contracts : deploy_contract : token bridge",
-        )
-        .await
-        .unwrap();
         sleep(Duration::from_secs(10)).await;
-        let address_token_bridge_impl =
-            get_contract_address_from_deploy_tx(account.provider(), &deploy_contract_implementation_token_bridge)
-                .await
-                .unwrap();
         log::info!("🌗 contract address (token bridge) : {:?}", address_token_bridge_impl);
 
-        address_token_bridge_impl
+        Ok(address_token_bridge_impl)
+    }
+
+    /// Init data of the manager, registry and token bridge proxies.
+    fn init_data(&self, messaging_contract: Address) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let manager_data = [pad_bytes(self.registry_address()), pad_bytes(self.bridge_address())].concat();
+        let registry_data = pad_bytes(self.manager_address());
+        let bridge_data = [pad_bytes(self.manager_address()), pad_bytes(messaging_contract)].concat();
+        (manager_data, registry_data, bridge_data)
     }
 
     /// Initialize Starknet Token Bridge.
     /// IMP : only need to be called when using unsafe proxy
     pub async fn initialize(&self, messaging_contract: Address, governor: Address) {
-        let empty_bytes = [0u8; 32];
+        let (manager_data, registry_data, bridge_data) = self.init_data(messaging_contract);
 
-        let mut manager_calldata = Vec::new();
-        manager_calldata.extend(empty_bytes);
-        manager_calldata.extend(pad_bytes(self.registry_address()));
-        manager_calldata.extend(pad_bytes(self.bridge_address()));
-
-        let mut registry_calldata = Vec::new();
-        registry_calldata.extend(empty_bytes);
-        registry_calldata.extend(pad_bytes(self.manager_address()));
-
-        let mut bridge_calldata = Vec::new();
-        bridge_calldata.extend(empty_bytes);
-        bridge_calldata.extend(pad_bytes(self.manager_address()));
-        bridge_calldata.extend(pad_bytes(messaging_contract));
-
-        self.manager.initialize(Bytes::from(manager_calldata)).await.expect("Failed to initialize starkgate manager");
+        self.manager
+            .initialize(build_upgrade_calldata(None, &manager_data))
+            .await
+            .expect("Failed to initialize starkgate manager");
         self.registry
-            .initialize(Bytes::from(registry_calldata))
+            .initialize(build_upgrade_calldata(None, &registry_data))
             .await
             .expect("Failed to initialize starkgate registry");
         self.token_bridge
-            .initialize(Bytes::from(bridge_calldata))
+            .initialize(build_upgrade_calldata(None, &bridge_data))
             .await
             .expect("Failed to initialize starknet token bridge");
 
@@ -189,74 +177,23 @@ impl StarknetTokenBridge {
         self.token_bridge.register_app_governor(governor).await.unwrap();
     }
 
-    /// Add Implementation Starknet Token Bridge
-    pub async fn add_implementation_token_bridge(&self, messaging_contract: Address) {
-        let empty_bytes = [0u8; 32];
-
-        let mut manager_calldata = Vec::new();
-        manager_calldata.extend(empty_bytes);
-        manager_calldata.extend(pad_bytes(self.registry_address()));
-        manager_calldata.extend(pad_bytes(self.bridge_address()));
-
-        let mut registry_calldata = Vec::new();
-        registry_calldata.extend(empty_bytes);
-        registry_calldata.extend(pad_bytes(self.manager_address()));
-
-        let mut bridge_calldata = Vec::new();
-        bridge_calldata.extend(empty_bytes);
-        bridge_calldata.extend(pad_bytes(self.manager_address()));
-        bridge_calldata.extend(pad_bytes(messaging_contract));
-
-        self.manager
-            .add_implementation(Bytes::from(manager_calldata.clone()), self.manager.implementation_address(), false)
-            .await
-            .expect("Failed to initialize starkgate manager");
-        log::info!("🎡 add_implementation_token_bridge : manager bytes : {:?}", Bytes::from(manager_calldata));
-        self.registry
-            .add_implementation(Bytes::from(registry_calldata.clone()), self.registry.implementation_address(), false)
-            .await
-            .expect("Failed to initialize starkgate registry");
-        log::info!("🎡 add_implementation_token_bridge : registry bytes : {:?}", Bytes::from(registry_calldata));
-        self.token_bridge
-            .add_implementation(Bytes::from(bridge_calldata.clone()), self.token_bridge.implementation_address(), false)
-            .await
-            .expect("Failed to initialize eth bridge");
-        log::info!("🎡 add_implementation_token_bridge : token_bridge bytes : {:?}", Bytes::from(bridge_calldata));
-    }
-
-    /// Upgrade To Starknet Token Bridge
-    pub async fn upgrade_to_token_bridge(&self, messaging_contract: Address) {
-        let empty_bytes = [0u8; 32];
-
-        let mut manager_calldata = Vec::new();
-        manager_calldata.extend(empty_bytes);
-        manager_calldata.extend(pad_bytes(self.registry_address()));
-        manager_calldata.extend(pad_bytes(self.bridge_address()));
-
-        let mut registry_calldata = Vec::new();
-        registry_calldata.extend(empty_bytes);
-        registry_calldata.extend(pad_bytes(self.manager_address()));
-
-        let mut bridge_calldata = Vec::new();
-        bridge_calldata.extend(empty_bytes);
-        bridge_calldata.extend(pad_bytes(self.manager_address()));
-        bridge_calldata.extend(pad_bytes(messaging_contract));
-
-        self.manager
-            .upgrade_to(Bytes::from(manager_calldata.clone()), self.manager.implementation_address(), false)
-            .await
-            .expect("Failed to initialize starkgate manager");
-        log::info!("🎡 upgrade_to_token_bridge : manager bytes : {:?}", Bytes::from(manager_calldata));
-        self.registry
-            .upgrade_to(Bytes::from(registry_calldata.clone()), self.registry.implementation_address(), false)
-            .await
-            .expect("Failed to initialize starkgate registry");
-        log::info!("🎡 upgrade_to_token_bridge : registry bytes : {:?}", Bytes::from(registry_calldata));
-        self.token_bridge
-            .upgrade_to(Bytes::from(bridge_calldata.clone()), self.token_bridge.implementation_address(), false)
-            .await
-            .expect("Failed to initialize eth bridge");
-        log::info!("🎡 upgrade_to_token_bridge : token_bridge bytes : {:?}", Bytes::from(bridge_calldata));
+    /// Points the manager, registry and token bridge safe proxies to the implementations deployed
+    /// along with them.
+    pub fn proxy_upgrades(&self, messaging_contract: Address) -> [ProxyUpgrade; 3] {
+        let (manager_data, registry_data, bridge_data) = self.init_data(messaging_contract);
+        let upgrade = |proxy_address, implementation_address, init_data| ProxyUpgrade {
+            proxy_address,
+            implementation: ImplementationSource::Deployed(implementation_address),
+            eic: None,
+            init_data,
+            finalize: false,
+            post_upgrade: vec![],
+        };
+        [
+            upgrade(self.manager_address(), self.manager.implementation_address(), manager_data),
+            upgrade(self.registry_address(), self.registry.implementation_address(), registry_data),
+            upgrade(self.bridge_address(), self.token_bridge.implementation_address(), bridge_data),
+        ]
     }
 
     /// Sets up the Token bridge with the specified data
@@ -312,11 +249,13 @@ impl StarknetTokenBridge {
         self.manager.enroll_token_bridge(self.address(), fee).await.unwrap();
     }
 
+    /// Sets up the roles of the L2 bridge and points it to `l1_bridge` (its settlement layer
+    /// counterpart).
     pub async fn setup_l2_bridge(
-        &self,
         rpc_provider_l2: &JsonRpcClient<HttpTransport>,
         l2_bridge: Felt,
         l2_address: &str,
+        l1_bridge: Felt,
         account: &RpcAccount<'_>,
         erc20_class_hash: Felt,
     ) {
@@ -378,13 +317,7 @@ impl StarknetTokenBridge {
         .unwrap();
         log::info!("🌗 setup_l2_bridge : set_erc20_class_hash //");
 
-        let tx = invoke_contract(
-            l2_bridge,
-            "set_l1_bridge",
-            vec![Felt::from_bytes_be_slice(self.token_bridge.address().as_bytes())],
-            account,
-        )
-        .await;
+        let tx = invoke_contract(l2_bridge, "set_l1_bridge", vec![l1_bridge], account).await;
         wait_for_transaction(rpc_provider_l2, tx.transaction_hash, "setup_l2_bridge : token bridge : set_l1_bridge")
             .await
             .unwrap();
@@ -1,14 +1,13 @@
 use std::sync::Arc;
 
+use color_eyre::eyre::WrapErr;
 use ethers::types::U256;
 use hex::encode;
-use serde::{Deserialize, Serialize};
-use serde_json::json;
 use starknet::accounts::{
     Account, AccountFactory, ConnectedAccount, ExecutionEncoding, OpenZeppelinAccountFactory, SingleOwnerAccount,
 };
 use starknet::core::types::contract::legacy::LegacyContractClass;
-use starknet::core::types::{BlockId, BlockTag, DeclareTransactionResult, Felt, FunctionCall};
+use starknet::core::types::{BlockId, BlockTag, Felt, FunctionCall};
 use starknet::core::utils::get_selector_from_name;
 use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use starknet::providers::Provider;
@@ -17,9 +16,11 @@ use starknet_core::types::contract::{CompiledClass, SierraClass};
 use starknet_core::types::BlockTag::Pending;
 use starknet_types_core::hash::{Pedersen, StarkHash};
 
+use crate::contract_clients::config::Clients;
+use crate::contract_clients::deployer::Deployer;
 use crate::contract_clients::legacy_class::CompressedLegacyContractClass;
 use crate::contract_clients::utils::DeclarationInput::{DeclarationInputs, LegacyDeclarationInputs};
-use crate::helpers::account_actions::{get_contract_address_from_deploy_tx, AccountActions};
+use crate::utils::artifacts::load_json_artifact;
 use crate::utils::{invoke_contract, save_to_json, wait_for_transaction, JsonValueType};
 use crate::ConfigFile;
 
@@ -102,93 +103,47 @@ pub struct BroadcastedDeclareTransactionV0 {
     pub is_query: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
-pub struct RpcResult<T> {
-    jsonrpc: String,
-    result: T,
-    id: u64,
-}
-
 pub const TEMP_ACCOUNT_PRIV_KEY: &str = "0xbeef";
 
 pub(crate) enum DeclarationInput<'a> {
     // inputs : sierra_path, casm_path
     DeclarationInputs(String, String, RpcAccount<'a>),
-    // input : artifact_path
-    LegacyDeclarationInputs(String, String, &'a JsonRpcClient<HttpTransport>),
+    // input : artifact_path, declared through the declare v0 backend of the clients
+    LegacyDeclarationInputs(String, &'a Clients),
 }
 
 #[allow(private_interfaces)]
-pub async fn declare_contract(input: DeclarationInput<'_>) -> Felt {
+pub async fn declare_contract(input: DeclarationInput<'_>) -> color_eyre::Result<Felt> {
     match input {
         DeclarationInputs(sierra_path, casm_path, account) => {
-            let contract_artifact: SierraClass =
-                serde_json::from_reader(std::fs::File::open(sierra_path).unwrap()).unwrap();
+            let contract_artifact: SierraClass = load_json_artifact(&sierra_path)?;
 
-            let contract_artifact_casm: CompiledClass =
-                serde_json::from_reader(std::fs::File::open(casm_path).unwrap()).unwrap();
-            let class_hash = contract_artifact_casm.class_hash().unwrap();
-            let sierra_class_hash = contract_artifact.class_hash().unwrap();
+            let contract_artifact_casm: CompiledClass = load_json_artifact(&casm_path)?;
+            let class_hash = contract_artifact_casm.class_hash()?;
+            let sierra_class_hash = contract_artifact.class_hash()?;
 
             if account.provider().get_class(BlockId::Tag(Pending), sierra_class_hash).await.is_ok() {
-                return sierra_class_hash;
+                return Ok(sierra_class_hash);
             }
 
-            let flattened_class = contract_artifact.flatten().unwrap();
+            let flattened_class = contract_artifact.flatten()?;
 
             account
                 .declare_v3(Arc::new(flattened_class), class_hash)
                 .gas(0)
                 .send()
                 .await
-                .expect("Error in declaring the contract using Cairo 1 declaration using the provided account");
-            sierra_class_hash
+                .wrap_err_with(|| format!("Error in declaring the contract {sierra_path} using Cairo 1 declaration"))?;
+            Ok(sierra_class_hash)
         }
-        LegacyDeclarationInputs(artifact_path, url, provider) => {
-            let contract_abi_artifact: LegacyContractClass = serde_json::from_reader(
-                std::fs::File::open(env!("CARGO_MANIFEST_DIR").to_owned() + "/" + &artifact_path).unwrap(),
-            )
-            .unwrap();
-
-            let class_hash = contract_abi_artifact.class_hash().expect("Failed to get class hash");
-            if provider.get_class(BlockId::Tag(Pending), class_hash).await.is_ok() {
-                return class_hash;
-            }
-
-            let contract_abi_artifact: CompressedLegacyContractClass =
-                contract_abi_artifact.clone().compress().expect("Error : Failed to compress the contract class").into();
-
-            let params: BroadcastedDeclareTransactionV0 = BroadcastedDeclareTransactionV0 {
-                sender_address: Felt::from_hex("0x1").unwrap(),
-                max_fee: Felt::ZERO,
-                signature: Vec::new(),
-                contract_class: Arc::new(contract_abi_artifact),
-                is_query: false,
-            };
-
-            // TODO: method can be updated based on the madara PR
-            let json_body = &json!({
-                "jsonrpc": "2.0",
-                "method": "madara_addDeclareV0Transaction",
-                "params": [params],
-                "id": 4
-            });
-
-            let req_client = reqwest::Client::new();
-            let raw_txn_rpc = req_client.post(url).json(json_body).send().await;
-            match raw_txn_rpc {
-                Ok(val) => {
-                    log::info!(
-                        "🚧 Txn Sent Successfully : {:?}",
-                        val.json::<RpcResult<DeclareTransactionResult>>().await.unwrap()
-                    );
-                }
-                Err(err) => {
-                    log::error!("Error : Error sending the transaction using RPC: {:?}", err);
-                }
-            }
+        LegacyDeclarationInputs(artifact_path, clients) => {
+            let contract_abi_artifact: LegacyContractClass = load_json_artifact(&artifact_path)?;
 
-            class_hash
+            clients
+                .declare_v0()
+                .declare(contract_abi_artifact, clients.provider_l2())
+                .await
+                .wrap_err_with(|| format!("Error in declaring the contract {artifact_path} using Cairo 0 declaration"))
         }
     }
 }
@@ -225,35 +180,11 @@ pub(crate) async fn deploy_account_using_priv_key(
     account_address
 }
 
-pub(crate) async fn deploy_proxy_contract(
-    account: &RpcAccount<'_>,
-    account_address: Felt,
-    class_hash: Felt,
-    salt: Felt,
-    deploy_from_zero: Felt,
-) -> Felt {
-    let txn = account
-        .invoke_contract(
-            account_address,
-            "deploy_contract",
-            vec![class_hash, salt, deploy_from_zero, Felt::ONE, Felt::ZERO],
-            None,
-        )
-        .send()
-        .await
-        .expect("Error deploying the contract proxy.");
-
-    log::info!("txn in proxy contract is: {:?}", txn);
-
-    wait_for_transaction(account.provider(), txn.transaction_hash, "deploy_proxy_contract : deploy_contract")
-        .await
-        .unwrap();
-
-    log::debug!("txn hash (proxy deployment) : {:?}", txn.transaction_hash);
-
-    let deployed_address = get_contract_address_from_deploy_tx(account.provider(), &txn).await.unwrap();
+/// Deploys a legacy proxy (upgrade delay 0).
+pub(crate) async fn deploy_proxy_contract(deployer: &Deployer<'_>, class_hash: Felt, salt: Felt, unique: bool) -> Felt {
+    let deployed_address =
+        deployer.deploy(class_hash, salt, unique, &[Felt::ZERO]).await.expect("Error deploying the contract proxy.");
     log::debug!("[IMP] Event : {:?}", deployed_address);
-
     deployed_address
 }
 
@@ -0,0 +1,338 @@
+//! Local devnet : anvil and Madara started from prebuilt binaries and bootstrapped together
+//! (`devnet up`).
+
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::time::Duration;
+
+use clap::Args;
+use serde::Serialize;
+use serde_json::json;
+use url::Url;
+
+use crate::export::{set_yaml_values, settlement_config};
+use crate::steps::Step;
+use crate::{Bootstrapper, BootstrapperOutput, ConfigFile};
+
+#[derive(Debug, Clone, Args)]
+pub struct DevnetOptions {
+    #[clap(long, env = "ANVIL_BIN", default_value = "anvil")]
+    pub anvil_bin: PathBuf,
+    /// Prebuilt Madara binary.
+    #[clap(long, env = "MADARA_BIN", default_value = "madara/target/release/madara")]
+    pub madara_bin: PathBuf,
+    /// Chain config Madara is started with, the core contract address is set in a copy of it.
+    #[clap(long, env = "MADARA_CHAIN_CONFIG", default_value = "madara/configs/presets/devnet.yaml")]
+    pub madara_chain_config: PathBuf,
+    /// Directory for the Madara database, the generated configs, the logs and `devnet.json`.
+    #[clap(long, default_value = "devnet")]
+    pub base_path: PathBuf,
+    #[clap(long, default_value_t = 8545)]
+    pub anvil_port: u16,
+    #[clap(long, default_value_t = 9944)]
+    pub rpc_port: u16,
+    #[clap(long, default_value_t = 9943)]
+    pub rpc_admin_port: u16,
+    #[clap(long, default_value_t = 8080)]
+    pub gateway_port: u16,
+    /// Reuse `<base_path>/madara-db`. By default it is wiped, anvil always starts from genesis so
+    /// the state of a previous devnet settles on a core contract which no longer exists.
+    #[clap(long)]
+    pub keep_state: bool,
+}
+
+/// What `devnet up` started, also written to `<base_path>/devnet.json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DevnetSummary {
+    pub anvil_url: String,
+    pub anvil_pid: u32,
+    pub madara_rpc_url: String,
+    pub madara_admin_url: String,
+    pub madara_pid: u32,
+    pub settlement_config_file: PathBuf,
+    pub chain_config_file: PathBuf,
+    pub l1_accounts: Vec<DevnetAccount>,
+    pub l2_accounts: Vec<DevnetAccount>,
+    pub bootstrap: BootstrapperOutput,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DevnetAccount {
+    pub address: String,
+    pub private_key: String,
+}
+
+/// Child process killed (by PID) on drop, unless it is detached.
+pub struct ManagedProcess {
+    name: &'static str,
+    child: Child,
+    detached: bool,
+}
+
+impl ManagedProcess {
+    pub fn new(name: &'static str, child: Child) -> Self {
+        Self { name, child, detached: false }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Leaves the process running, returns its PID.
+    pub fn detach(mut self) -> u32 {
+        self.detached = true;
+        self.child.id()
+    }
+}
+
+impl Drop for ManagedProcess {
+    fn drop(&mut self) {
+        if self.detached {
+            return;
+        }
+        if let Err(err) = self.child.kill() {
+            log::warn!("Unable to kill {} (pid {}) : {}", self.name, self.child.id(), err);
+        }
+        let _ = self.child.wait();
+    }
+}
+
+/// Ports and paths of a Madara sequencer settling on `l1_endpoint`.
+pub struct MadaraLaunch<'a> {
+    pub bin: &'a Path,
+    pub base_path: &'a Path,
+    pub chain_config: &'a Path,
+    pub rpc_port: u16,
+    pub rpc_admin_port: u16,
+    pub gateway_port: u16,
+    pub l1_endpoint: &'a Url,
+}
+
+/// Starts anvil on `port`, logging to `<log_dir>/anvil.log`.
+pub fn spawn_anvil(bin: &Path, port: u16, log_dir: &Path) -> io::Result<ManagedProcess> {
+    let child = Command::new(bin)
+        .arg("--port")
+        .arg(port.to_string())
+        .arg("--code-size-limit")
+        .arg("300000")
+        .stdout(File::create(log_dir.join("anvil.log"))?)
+        .stderr(File::create(log_dir.join("anvil.err.log"))?)
+        .spawn()?;
+    Ok(ManagedProcess::new("anvil", child))
+}
+
+/// Starts a Madara sequencer without fees and with the admin RPC (declare v0), logging to
+/// `<log_dir>/madara.log`.
+pub fn spawn_madara(launch: &MadaraLaunch<'_>, log_dir: &Path) -> io::Result<ManagedProcess> {
+    if !launch.bin.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Madara binary not found at {}", launch.bin.display()),
+        ));
+    }
+    let child = Command::new(launch.bin)
+        .env("RUST_LOG", "info")
+        .arg("--name")
+        .arg("madara")
+        .arg("--base-path")
+        .arg(launch.base_path)
+        .arg("--rpc-port")
+        .arg(launch.rpc_port.to_string())
+        .arg("--rpc-cors")
+        .arg("*")
+        .arg("--rpc-external")
+        .arg("--sequencer")
+        .arg("--chain-config-path")
+        .arg(launch.chain_config)
+        .arg("--feeder-gateway-enable")
+        .arg("--gateway-enable")
+        .arg("--gateway-external")
+        .arg("--gateway-port")
+        .arg(launch.gateway_port.to_string())
+        .arg("--gas-price")
+        .arg("0")
+        .arg("--blob-gas-price")
+        .arg("0")
+        .arg("--rpc-admin")
+        .arg("--rpc-admin-port")
+        .arg(launch.rpc_admin_port.to_string())
+        .arg("--l1-endpoint")
+        .arg(launch.l1_endpoint.as_str())
+        .stdout(File::create(log_dir.join("madara.log"))?)
+        .stderr(File::create(log_dir.join("madara.err.log"))?)
+        .spawn()?;
+    Ok(ManagedProcess::new("madara", child))
+}
+
+/// Waits for anvil to answer `eth_chainId`.
+pub async fn wait_for_anvil(url: &Url) -> color_eyre::Result<()> {
+    let request = json!({ "jsonrpc": "2.0", "method": "eth_chainId", "params": [], "id": 1 });
+    wait_until_ready("anvil", 50, Duration::from_millis(200), || async {
+        reqwest::Client::new().post(url.clone()).json(&request).send().await?.error_for_status()?;
+        Ok(())
+    })
+    .await
+}
+
+/// Waits for the Madara health endpoint.
+pub async fn wait_for_madara(rpc_url: &Url) -> color_eyre::Result<()> {
+    let endpoint = rpc_url.join("/health")?;
+    wait_until_ready("madara", 120, Duration::from_secs(1), || async {
+        reqwest::get(endpoint.clone()).await?.error_for_status()?;
+        Ok(())
+    })
+    .await
+}
+
+/// Removes the Madara database of a previous devnet, unless `keep_state`.
+pub fn reset_madara_db(path: &Path, keep_state: bool) -> io::Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    if keep_state {
+        log::warn!("⚠️ Keeping the Madara database at {}, it must settle on the new core contract", path.display());
+        return Ok(());
+    }
+    log::info!("🧹 Removing the Madara database of the previous devnet at {}", path.display());
+    fs::remove_dir_all(path)
+}
+
+async fn wait_until_ready<F, Fut>(
+    name: &str,
+    attempts: usize,
+    interval: Duration,
+    mut check: F,
+) -> color_eyre::Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = color_eyre::Result<()>>,
+{
+    let mut last_error = None;
+    for _ in 0..attempts {
+        match check().await {
+            Ok(()) => return Ok(()),
+            Err(err) => last_error = Some(err),
+        }
+        tokio::time::sleep(interval).await;
+    }
+    Err(color_eyre::eyre::eyre!("{} is not ready after {} attempts : {:?}", name, attempts, last_error))
+}
+
+/// Starts anvil, deploys the core contract, starts Madara settling on it and runs the L2 setup.
+/// The processes are left running once the setup succeeds, they are killed if it fails.
+pub async fn devnet_up(
+    mut config_file: ConfigFile,
+    options: &DevnetOptions,
+    artifacts_dir: Option<PathBuf>,
+    verify_artifacts: bool,
+) -> color_eyre::Result<DevnetSummary> {
+    fs::create_dir_all(&options.base_path)?;
+    let base_path = options.base_path.canonicalize()?;
+    let madara_db = base_path.join("madara-db");
+    reset_madara_db(&madara_db, options.keep_state)?;
+
+    let anvil_url = local_url(options.anvil_port);
+    let madara_rpc_url = local_url(options.rpc_port);
+    let madara_admin_url = local_url(options.rpc_admin_port);
+
+    let anvil = spawn_anvil(&options.anvil_bin, options.anvil_port, &base_path)?;
+    wait_for_anvil(&anvil_url).await?;
+    log::info!("⛓️ anvil running at {} [pid : {}]", anvil_url, anvil.id());
+
+    config_file.eth_rpc = anvil_url.to_string();
+    config_file.rollup_seq_url = madara_rpc_url.to_string();
+    config_file.rollup_declare_v0_seq_url = madara_admin_url.to_string();
+    let mut builder = Bootstrapper::builder(config_file).save_addresses(true).verify_artifacts(verify_artifacts);
+    if let Some(artifacts_dir) = artifacts_dir {
+        builder = builder.artifacts_dir(artifacts_dir);
+    }
+    let mut bootstrapper = builder.build().await?;
+
+    let core_output = bootstrapper.run_steps(&[Step::CoreContract]).await?;
+    let core_contract_address =
+        bootstrapper.config().core_contract_address.clone().expect("set by the core contract step");
+
+    let settlement_config_file = base_path.join("eth-config.json");
+    fs::write(
+        &settlement_config_file,
+        serde_json::to_string_pretty(&settlement_config(anvil_url.as_str(), &core_contract_address))?,
+    )?;
+    let chain_config_file = base_path.join("chain-config.yaml");
+    fs::write(
+        &chain_config_file,
+        set_yaml_values(
+            &fs::read_to_string(&options.madara_chain_config)?,
+            &[("eth_core_contract_address", core_contract_address.clone())],
+        ),
+    )?;
+    log::info!("⛓️ Madara configs written with the core contract {}", core_contract_address);
+
+    let madara = spawn_madara(
+        &MadaraLaunch {
+            bin: &options.madara_bin,
+            base_path: &madara_db,
+            chain_config: &chain_config_file,
+            rpc_port: options.rpc_port,
+            rpc_admin_port: options.rpc_admin_port,
+            gateway_port: options.gateway_port,
+            l1_endpoint: &anvil_url,
+        },
+        &base_path,
+    )?;
+    wait_for_madara(&madara_rpc_url).await?;
+    log::info!("⛓️ Madara running at {} [pid : {}]", madara_rpc_url, madara.id());
+
+    let l2_output = bootstrapper.setup_l2().await?;
+
+    let config = bootstrapper.config();
+    let summary = DevnetSummary {
+        anvil_url: anvil_url.to_string(),
+        madara_rpc_url: madara_rpc_url.to_string(),
+        madara_admin_url: madara_admin_url.to_string(),
+        settlement_config_file,
+        chain_config_file,
+        l1_accounts: vec![DevnetAccount {
+            address: config.l1_deployer_address.clone(),
+            private_key: config.eth_priv_key.clone(),
+        }],
+        l2_accounts: config
+            .l2_deployer_address
+            .iter()
+            .map(|address| DevnetAccount { address: address.clone(), private_key: config.rollup_priv_key.clone() })
+            .collect(),
+        bootstrap: core_output.merge(l2_output),
+        anvil_pid: anvil.detach(),
+        madara_pid: madara.detach(),
+    };
+    fs::write(base_path.join("devnet.json"), serde_json::to_string_pretty(&summary)?)?;
+
+    Ok(summary)
+}
+
+/// Human readable summary printed by `devnet up`.
+pub fn render_summary(summary: &DevnetSummary) -> String {
+    let mut lines = vec![
+        format!("anvil          : {} [pid {}]", summary.anvil_url, summary.anvil_pid),
+        format!("madara rpc     : {} [pid {}]", summary.madara_rpc_url, summary.madara_pid),
+        format!("madara admin   : {}", summary.madara_admin_url),
+        format!("chain config   : {}", summary.chain_config_file.display()),
+        format!("settlement     : {}", summary.settlement_config_file.display()),
+    ];
+    if let Some(ref core_contract) = summary.bootstrap.starknet_contract_address {
+        lines.push(format!("core contract  : {:?}", core_contract));
+    }
+    for account in &summary.l1_accounts {
+        lines.push(format!("L1 account     : {} (private key {})", account.address, account.private_key));
+    }
+    for account in &summary.l2_accounts {
+        lines.push(format!("L2 account     : {} (private key {})", account.address, account.private_key));
+    }
+    lines.push(format!("stop with      : kill {} {}", summary.anvil_pid, summary.madara_pid));
+    lines.join("\n")
+}
+
+fn local_url(port: u16) -> Url {
+    Url::parse(&format!("http://127.0.0.1:{port}")).unwrap()
+}
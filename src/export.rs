@@ -0,0 +1,159 @@
+//! Renders the deployment (the config with the manifest of the runs applied) into the configs of
+//! the other services : Madara chain config, settlement config, orchestrator `.env` and user
+//! templates (`export`).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+use color_eyre::eyre::{bail, eyre};
+use serde_json::json;
+
+use crate::ConfigFile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Madara chain config YAML, `--base-chain-config` is updated when given.
+    MadaraChainConfig,
+    /// Madara settlement config (`bin/eth-config.json`).
+    SettlementConfig,
+    /// Orchestrator `.env`.
+    Dotenv,
+    /// `--template` with its `{{name}}` placeholders replaced.
+    Template,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ExportOptions {
+    #[clap(long, value_enum)]
+    pub format: ExportFormat,
+    /// Template for the `template` format, `{{name}}` placeholders take the names of `dotenv` in
+    /// lower case (e.g. `{{l1_core_contract_address}}`).
+    #[clap(long, required_if_eq("format", "template"))]
+    pub template: Option<PathBuf>,
+    /// Chain config to update with the deployment, other keys are kept.
+    #[clap(long)]
+    pub base_chain_config: Option<PathBuf>,
+    /// Written to stdout without it.
+    #[clap(long)]
+    pub out: Option<PathBuf>,
+}
+
+/// Values of the deployment, by export name. Only the values known from the config and the
+/// manifest are present.
+pub fn export_values(config: &ConfigFile) -> BTreeMap<&'static str, String> {
+    let values: [(&'static str, Option<&String>); 20] = [
+        ("app_chain_id", Some(&config.app_chain_id)),
+        ("settlement_rpc_url", Some(config.settlement_rpc.as_ref().unwrap_or(&config.eth_rpc))),
+        ("madara_rpc_url", Some(&config.rollup_seq_url)),
+        ("l1_core_contract_address", config.core_contract_address.as_ref()),
+        ("l1_core_contract_implementation_address", config.core_contract_implementation_address.as_ref()),
+        ("gps_verifier_contract_address", Some(&config.verifier_address)),
+        ("starknet_operator_address", Some(&config.operator_address)),
+        ("sn_os_program_hash", Some(&config.sn_os_program_hash)),
+        ("sn_os_config_hash_version", Some(&config.config_hash_version)),
+        ("eth_fee_token_address", Some(&config.fee_token_address)),
+        ("strk_fee_token_address", Some(&config.native_fee_token_address)),
+        ("l1_eth_bridge_address", config.l1_eth_bridge_address.as_ref()),
+        ("l2_eth_token_address", config.l2_eth_token_proxy_address.as_ref()),
+        ("l2_eth_bridge_address", config.l2_eth_bridge_proxy_address.as_ref()),
+        ("l1_token_bridge_address", config.l1_token_bridge_address.as_ref()),
+        ("l1_manager_address", config.l1_manager_address.as_ref()),
+        ("l1_registry_address", config.l1_registry_address.as_ref()),
+        ("l2_token_bridge_address", config.l2_token_bridge_address.as_ref()),
+        ("udc_address", config.udc_address.as_ref()),
+        ("l2_deployer_address", config.l2_deployer_address.as_ref()),
+    ];
+    values.into_iter().filter_map(|(name, value)| value.map(|value| (name, value.clone()))).collect()
+}
+
+/// Renders the deployment in `options.format`.
+pub fn export(config: &ConfigFile, options: &ExportOptions) -> color_eyre::Result<String> {
+    let values = export_values(config);
+    match options.format {
+        ExportFormat::MadaraChainConfig => {
+            let base = match options.base_chain_config {
+                Some(ref path) => fs::read_to_string(path)?,
+                None => String::new(),
+            };
+            Ok(set_yaml_values(&base, &madara_chain_config_values(&values)))
+        }
+        ExportFormat::SettlementConfig => {
+            let core_contract = required(&values, "l1_core_contract_address")?;
+            Ok(serde_json::to_string_pretty(&settlement_config(&values["settlement_rpc_url"], core_contract))?)
+        }
+        ExportFormat::Dotenv => {
+            Ok(values.iter().map(|(name, value)| format!("{}={}\n", name.to_uppercase(), value)).collect::<String>())
+        }
+        ExportFormat::Template => {
+            let path = options.template.as_ref().ok_or_else(|| eyre!("`--template` is required"))?;
+            render_template(&fs::read_to_string(path)?, &values)
+        }
+    }
+}
+
+/// Settlement config of Madara (`--settlement-conf`, see `bin/eth-config.json`).
+pub fn settlement_config(rpc_endpoint: &str, core_contract_address: &str) -> serde_json::Value {
+    json!({
+        "provider": {
+            "rpc_endpoint": rpc_endpoint,
+            "gas_price_poll_ms": 10000
+        },
+        "contracts": {
+            "core_contract": core_contract_address
+        }
+    })
+}
+
+/// Copy of the top level `key: value` YAML with `values` set, the missing keys are appended.
+pub fn set_yaml_values(yaml: &str, values: &[(&str, String)]) -> String {
+    let mut missing: Vec<&(&str, String)> = values.iter().collect();
+    let mut lines: Vec<String> = yaml
+        .lines()
+        .map(|line| {
+            let Some(position) = missing.iter().position(|(key, _)| line.starts_with(&format!("{key}:"))) else {
+                return line.to_string();
+            };
+            let (key, value) = missing.remove(position);
+            format!("{key}: \"{value}\"")
+        })
+        .collect();
+    lines.extend(missing.into_iter().map(|(key, value)| format!("{key}: \"{value}\"")));
+    lines.join("\n") + "\n"
+}
+
+/// Replaces the `{{name}}` placeholders, unknown names are an error.
+pub fn render_template(template: &str, values: &BTreeMap<&'static str, String>) -> color_eyre::Result<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            bail!("Unclosed `{{{{` in the template");
+        };
+        let name = rest[start + 2..start + end].trim();
+        let value = values.get(name).ok_or_else(|| eyre!("No value for `{{{{{}}}}}` in the deployment", name))?;
+        rendered.push_str(&rest[..start]);
+        rendered.push_str(value);
+        rest = &rest[start + end + 2..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+fn madara_chain_config_values(values: &BTreeMap<&'static str, String>) -> Vec<(&'static str, String)> {
+    [
+        ("chain_id", "app_chain_id"),
+        ("native_fee_token_address", "strk_fee_token_address"),
+        ("parent_fee_token_address", "eth_fee_token_address"),
+        ("eth_core_contract_address", "l1_core_contract_address"),
+        ("eth_gps_statement_verifier", "gps_verifier_contract_address"),
+    ]
+    .into_iter()
+    .filter_map(|(key, name)| values.get(name).map(|value| (key, value.clone())))
+    .collect()
+}
+
+fn required<'a>(values: &'a BTreeMap<&'static str, String>, name: &str) -> color_eyre::Result<&'a String> {
+    values.get(name).ok_or_else(|| eyre!("`{}` is not in the config nor in the manifest", name))
+}
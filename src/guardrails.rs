@@ -0,0 +1,167 @@
+//! Guardrails of the production chains : the dev options deploy unsafe proxies and initialise
+//! the bridges without governance, and the governance must end up with a multisig.
+
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::str::FromStr;
+
+use ethers::providers::Middleware;
+use ethers::types::{Address, U256};
+use starknet::core::types::{BlockId, BlockTag, Felt};
+use starknet::core::utils::parse_cairo_short_string;
+use starknet::providers::Provider;
+
+use crate::contract_clients::config::Clients;
+use crate::ledger::format_u256_units;
+use crate::{ConfigFile, CoreContractMode, SettlementLayerKind};
+
+/// Ethereum mainnet.
+pub const PRODUCTION_ETH_CHAIN_IDS: [u64; 1] = [1];
+/// Starknet mainnet, as settlement layer.
+pub const PRODUCTION_STARKNET_CHAIN_IDS: [&str; 1] = ["SN_MAIN"];
+
+/// Whether the settlement layer is a production chain. On Ethereum, the chain ID of the RPC is used
+/// when it answers, `eth_chain_id` otherwise. A Starknet settlement chain which doesn't answer is
+/// taken as a production one.
+pub async fn is_production_chain(config: &ConfigFile, clients: &Clients) -> bool {
+    match config.settlement_layer() {
+        SettlementLayerKind::Ethereum => {
+            let chain_id = match clients.eth_client().signer().get_chainid().await {
+                Ok(chain_id) => chain_id.low_u64(),
+                Err(_) => config.eth_chain_id,
+            };
+            PRODUCTION_ETH_CHAIN_IDS.contains(&chain_id) || PRODUCTION_ETH_CHAIN_IDS.contains(&config.eth_chain_id)
+        }
+        SettlementLayerKind::Starknet => {
+            let Some(provider) = clients.provider_settlement() else {
+                return false;
+            };
+            match provider.chain_id().await {
+                Ok(chain_id) => parse_cairo_short_string(&chain_id)
+                    .is_ok_and(|chain_id| PRODUCTION_STARKNET_CHAIN_IDS.contains(&chain_id.as_str())),
+                Err(err) => {
+                    log::warn!("⚠️ Unable to get the settlement chain id, taken as a production chain : {}", err);
+                    true
+                }
+            }
+        }
+    }
+}
+
+/// Options of the config refused on a production chain, without querying the chains.
+pub fn config_violations(config: &ConfigFile) -> Vec<String> {
+    let mut violations = vec![];
+    if config.dev {
+        violations
+            .push("`dev` deploys the bridges without proxy governance and with `initialize` shortcuts".to_string());
+    }
+    if matches!(config.core_contract_mode, CoreContractMode::Dev) {
+        violations.push("`core_contract_mode` is `Dev`".to_string());
+    }
+    if config.settlement_layer() == SettlementLayerKind::Ethereum {
+        match Address::from_str(&config.l1_multisig_address) {
+            Ok(address) if address.is_zero() => violations.push("`l1_multisig_address` is unset".to_string()),
+            Ok(address) if Address::from_str(&config.l1_deployer_address).ok() == Some(address) => {
+                violations.push("`l1_multisig_address` is the L1 deployer".to_string())
+            }
+            Ok(_) => {}
+            Err(_) => violations.push("`l1_multisig_address` is unset or invalid".to_string()),
+        }
+    }
+    if config.settlement_layer() == SettlementLayerKind::Starknet {
+        let settlement_account = config.settlement_account_address.as_deref().and_then(|a| Felt::from_hex(a).ok());
+        match Felt::from_hex(config.settlement_multisig_address.as_deref().unwrap_or_default()) {
+            Ok(address) if address == Felt::ZERO => {
+                violations.push("`settlement_multisig_address` is unset".to_string())
+            }
+            Ok(address) if settlement_account == Some(address) => {
+                violations.push("`settlement_multisig_address` is the settlement account".to_string())
+            }
+            Ok(_) => {}
+            Err(_) => violations.push("`settlement_multisig_address` is unset or invalid".to_string()),
+        }
+    }
+    match Felt::from_hex(&config.l2_multisig_address) {
+        Ok(address) if address == Felt::ZERO => violations.push("`l2_multisig_address` is unset".to_string()),
+        Ok(address) if config.l2_deployer_address.as_deref().and_then(|a| Felt::from_hex(a).ok()) == Some(address) => {
+            violations.push("`l2_multisig_address` is the L2 deployer".to_string())
+        }
+        Ok(_) => {}
+        Err(_) => violations.push("`l2_multisig_address` is unset or invalid".to_string()),
+    }
+    violations
+}
+
+/// [`config_violations`] and the multisig of the settlement layer being an EOA (Ethereum) or not
+/// deployed (Starknet).
+pub async fn production_violations(config: &ConfigFile, clients: &Clients) -> Vec<String> {
+    let mut violations = config_violations(config);
+    if config.settlement_layer() == SettlementLayerKind::Ethereum {
+        if let Ok(address) = Address::from_str(&config.l1_multisig_address) {
+            match clients.eth_client().signer().get_code(address, None).await {
+                Ok(code) if code.is_empty() => {
+                    violations.push(format!("`l1_multisig_address` {:?} is an EOA, not a multisig", address))
+                }
+                Ok(_) => {}
+                Err(err) => violations.push(format!("Unable to check `l1_multisig_address` : {}", err)),
+            }
+        }
+    }
+    if config.settlement_layer() == SettlementLayerKind::Starknet {
+        let multisig = config.settlement_multisig_address.as_deref().and_then(|a| Felt::from_hex(a).ok());
+        if let (Some(provider), Some(address)) = (clients.provider_settlement(), multisig) {
+            if let Err(err) = provider.get_class_hash_at(BlockId::Tag(BlockTag::Latest), address).await {
+                violations.push(format!(
+                    "`settlement_multisig_address` {} is not deployed on the settlement chain : {}",
+                    address.to_hex_string(),
+                    err
+                ))
+            }
+        }
+    }
+    violations
+}
+
+/// What a production run is about to do, shown before the confirmation.
+pub fn plan_summary(config: &ConfigFile, plan: &str, l1_cost: Option<(u64, U256)>) -> String {
+    let cost = match l1_cost {
+        Some((gas, gas_price)) => format!(
+            "about {} ETH ({} gas at {} gwei)",
+            format_u256_units(gas_price * U256::from(gas)),
+            gas,
+            gas_price / U256::exp10(9)
+        ),
+        None => "unknown".to_string(),
+    };
+    let (settlement, settlement_multisig) = match config.settlement_layer() {
+        SettlementLayerKind::Ethereum => (
+            format!("Ethereum, chain id {} ({})", config.eth_chain_id, config.eth_rpc),
+            format!("L1 multisig        : {}", config.l1_multisig_address),
+        ),
+        SettlementLayerKind::Starknet => (
+            format!("Starknet ({})", config.settlement_rpc.as_deref().unwrap_or_default()),
+            format!("Settlement owner   : {}", config.settlement_multisig_address.as_deref().unwrap_or("unset")),
+        ),
+    };
+    [
+        format!("Settlement layer   : {}", settlement),
+        format!("Appchain           : {} ({})", config.app_chain_id, config.rollup_seq_url),
+        format!("Plan               : {}", plan),
+        settlement_multisig,
+        format!("L2 multisig        : {}", config.l2_multisig_address),
+        format!("Estimated L1 cost  : {}", cost),
+    ]
+    .join("\n")
+}
+
+/// Asks to type `mainnet` to go on, refuses when stdin is not a terminal.
+pub fn confirm_production_run(summary: &str) -> io::Result<bool> {
+    if !io::stdin().is_terminal() {
+        return Ok(false);
+    }
+    println!("🚨 The settlement layer is a production chain :\n{}", summary);
+    print!("Type `mainnet` to continue : ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(answer.trim() == "mainnet")
+}
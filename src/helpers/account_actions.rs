@@ -1,20 +1,19 @@
 use std::future::Future;
 
-use assert_matches::assert_matches;
 use async_trait::async_trait;
 use starknet::accounts::{Account, Call, ExecutionV1, SingleOwnerAccount};
 use starknet::core::types::contract::legacy::LegacyContractClass;
-use starknet::core::types::{Felt, FlattenedSierraClass, TransactionReceipt};
+use starknet::core::types::{Felt, FlattenedSierraClass};
 use starknet::core::utils::get_selector_from_name;
 use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use starknet::providers::Provider;
 use starknet::signers::LocalWallet;
 use starknet_core::types::contract::{CompiledClass, SierraClass};
-use starknet_core::types::{InvokeTransactionResult, TransactionReceiptWithBlockInfo};
+use starknet_core::types::TransactionReceiptWithBlockInfo;
 use starknet_providers::ProviderError;
 
 use crate::contract_clients::utils::RpcAccount;
-use crate::utils::wait_for_transaction;
+use crate::utils::artifacts::load_json_artifact;
 
 pub type TransactionExecution<'a> = ExecutionV1<'a, RpcAccount<'a>>;
 
@@ -51,26 +50,17 @@ impl AccountActions for SingleOwnerAccount<&JsonRpcClient<HttpTransport>, LocalW
     }
 
     fn declare_contract_params_sierra(&self, path_to_sierra: &str, path_to_casm: &str) -> (Felt, FlattenedSierraClass) {
-        let sierra: SierraClass = serde_json::from_reader(
-            std::fs::File::open(env!("CARGO_MANIFEST_DIR").to_owned() + "/" + path_to_sierra).unwrap(),
-        )
-        .unwrap();
+        let sierra: SierraClass = load_json_artifact(path_to_sierra).unwrap();
 
         let flattened_class = sierra.flatten().unwrap();
 
-        let casm: CompiledClass = serde_json::from_reader(
-            std::fs::File::open(env!("CARGO_MANIFEST_DIR").to_owned() + "/" + path_to_casm).unwrap(),
-        )
-        .unwrap();
+        let casm: CompiledClass = load_json_artifact(path_to_casm).unwrap();
 
         (casm.class_hash().unwrap(), flattened_class)
     }
 
     fn declare_contract_params_legacy(&self, path_to_compiled_contract: &str) -> LegacyContractClass {
-        let contract_artifact: LegacyContractClass = serde_json::from_reader(
-            std::fs::File::open(env!("CARGO_MANIFEST_DIR").to_owned() + "/" + path_to_compiled_contract).unwrap(),
-        )
-        .unwrap();
+        let contract_artifact: LegacyContractClass = load_json_artifact(path_to_compiled_contract).unwrap();
 
         contract_artifact
     }
@@ -104,22 +94,3 @@ pub async fn get_transaction_receipt(
 
     rpc.get_transaction_receipt(transaction_hash).await
 }
-
-pub async fn get_contract_address_from_deploy_tx(
-    rpc: &JsonRpcClient<HttpTransport>,
-    tx: &InvokeTransactionResult,
-) -> Result<Felt, ProviderError> {
-    let deploy_tx_hash = tx.transaction_hash;
-
-    wait_for_transaction(rpc, deploy_tx_hash, "get_contract_address_from_deploy_tx").await.unwrap();
-
-    let deploy_tx_receipt = get_transaction_receipt(rpc, deploy_tx_hash).await?;
-
-    let contract_address = assert_matches!(
-        deploy_tx_receipt,
-        TransactionReceiptWithBlockInfo { receipt: TransactionReceipt::Invoke(receipt), .. } => {
-            receipt.events.iter().find(|e| e.keys[0] == get_selector_from_name("ContractDeployed").unwrap()).unwrap().data[0]
-        }
-    );
-    Ok(contract_address)
-}
@@ -0,0 +1,445 @@
+//! Ledger of the transactions sent by a run of the steps, with the fees they cost.
+//!
+//! The transactions are not captured when they are sent (most of the L1 ones are sent by the
+//! zaun clients) but read back from the chains : after every step, the blocks produced since the
+//! previous one are scanned for the transactions of the bootstrapper accounts.
+
+use std::fmt;
+
+use ethers::providers::Middleware;
+use ethers::types::{Address, U256};
+use serde::Serialize;
+use starknet::core::types::contract::legacy::LegacyContractClass;
+use starknet::core::types::{
+    BlockId, BlockTag, DeclareTransaction, Felt, InvokeTransaction, MaybePendingBlockWithTxs, PriceUnit, ReceiptBlock,
+    Transaction, TransactionReceipt,
+};
+use starknet::core::utils::get_contract_address;
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+use starknet::providers::Provider;
+use starknet::signers::SigningKey;
+
+use crate::contract_clients::config::Clients;
+use crate::contract_clients::utils::TEMP_ACCOUNT_PRIV_KEY;
+use crate::utils::artifacts::load_json_artifact;
+use crate::utils::constants::OZ_ACCOUNT_PATH;
+use crate::{ConfigFile, SettlementLayerKind};
+
+/// Sender of the `declare_v0` transactions of the legacy classes.
+const LEGACY_DECLARE_SENDER: Felt = Felt::ONE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerChain {
+    Settlement,
+    L2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeUnit {
+    Wei,
+    Fri,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LedgerEntry {
+    pub step: String,
+    pub chain: LedgerChain,
+    pub tx_hash: String,
+    /// `None` while the transaction is in the pending block.
+    pub block_number: Option<u64>,
+    /// Called contract (the first call of the L2 multicalls), deployed contract or declared class.
+    pub target: Option<String>,
+    /// 4 bytes selector on Ethereum, entry point selector on Starknet, `create`, `declare` or
+    /// `deploy_account` otherwise.
+    pub function: Option<String>,
+    pub calldata: Vec<String>,
+    /// Ethereum only.
+    pub gas_used: Option<u128>,
+    /// Ethereum only.
+    pub effective_gas_price: Option<u128>,
+    /// Gas used times effective gas price on Ethereum, actual fee on Starknet.
+    pub fee: u128,
+    pub fee_unit: FeeUnit,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Fees {
+    pub wei: u128,
+    pub fri: u128,
+}
+
+impl Fees {
+    fn add(&mut self, unit: FeeUnit, fee: u128) {
+        match unit {
+            FeeUnit::Wei => self.wei += fee,
+            FeeUnit::Fri => self.fri += fee,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentCost {
+    pub step: String,
+    pub transactions: usize,
+    pub settlement: Fees,
+    pub l2: Fees,
+}
+
+/// Fees per step, in the order the steps ran, and in total.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CostReport {
+    pub components: Vec<ComponentCost>,
+    pub settlement: Fees,
+    pub l2: Fees,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Ledger {
+    pub transactions: Vec<LedgerEntry>,
+    pub cost_report: CostReport,
+}
+
+impl Ledger {
+    pub fn new(transactions: Vec<LedgerEntry>) -> Self {
+        let cost_report = CostReport::new(&transactions);
+        Self { transactions, cost_report }
+    }
+
+    pub fn merge(self, other: Ledger) -> Ledger {
+        let mut transactions = self.transactions;
+        for entry in other.transactions {
+            push_entry(&mut transactions, entry);
+        }
+        Ledger::new(transactions)
+    }
+}
+
+/// Adds `entry` unless it is already in the ledger, returns whether it was added. A transaction
+/// seen in the pending block is seen again in its block by the next scan, which only sets its
+/// block number.
+fn push_entry(transactions: &mut Vec<LedgerEntry>, entry: LedgerEntry) -> bool {
+    match transactions.iter_mut().find(|known| known.chain == entry.chain && known.tx_hash == entry.tx_hash) {
+        Some(known) => {
+            known.block_number = known.block_number.or(entry.block_number);
+            false
+        }
+        None => {
+            transactions.push(entry);
+            true
+        }
+    }
+}
+
+impl CostReport {
+    pub fn new(transactions: &[LedgerEntry]) -> Self {
+        let mut report = CostReport::default();
+        for entry in transactions {
+            let position = match report.components.iter().position(|component| component.step == entry.step) {
+                Some(position) => position,
+                None => {
+                    report.components.push(ComponentCost {
+                        step: entry.step.clone(),
+                        transactions: 0,
+                        settlement: Fees::default(),
+                        l2: Fees::default(),
+                    });
+                    report.components.len() - 1
+                }
+            };
+            let component = &mut report.components[position];
+            component.transactions += 1;
+            match entry.chain {
+                LedgerChain::Settlement => {
+                    component.settlement.add(entry.fee_unit, entry.fee);
+                    report.settlement.add(entry.fee_unit, entry.fee);
+                }
+                LedgerChain::L2 => {
+                    component.l2.add(entry.fee_unit, entry.fee);
+                    report.l2.add(entry.fee_unit, entry.fee);
+                }
+            }
+        }
+        report
+    }
+}
+
+impl fmt::Display for CostReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let row = |f: &mut fmt::Formatter<'_>, step: &str, transactions: &str, columns: [&str; 4]| {
+            writeln!(
+                f,
+                "{:<24} {:>4} {:>22} {:>22} {:>22} {:>22}",
+                step, transactions, columns[0], columns[1], columns[2], columns[3]
+            )
+        };
+        let fees = |settlement: &Fees, l2: &Fees| [settlement.wei, settlement.fri, l2.wei, l2.fri].map(format_units);
+        row(f, "step", "txs", ["settlement (ETH)", "settlement (STRK)", "l2 (ETH)", "l2 (STRK)"])?;
+        for component in &self.components {
+            let columns = fees(&component.settlement, &component.l2);
+            row(f, &component.step, &component.transactions.to_string(), columns.each_ref().map(String::as_str))?;
+        }
+        let transactions: usize = self.components.iter().map(|component| component.transactions).sum();
+        let columns = fees(&self.settlement, &self.l2);
+        row(f, "total", &transactions.to_string(), columns.each_ref().map(String::as_str))
+    }
+}
+
+/// Amount with 18 decimals (wei in ETH, fri in STRK).
+pub fn format_units(amount: u128) -> String {
+    let decimals = format!("{:018}", amount % 10u128.pow(18));
+    let decimals = decimals.trim_end_matches('0');
+    if decimals.is_empty() {
+        format!("{}", amount / 10u128.pow(18))
+    } else {
+        format!("{}.{}", amount / 10u128.pow(18), decimals)
+    }
+}
+
+/// [`format_units`] of an amount which may not fit in a `u128` (balances, cost estimates).
+pub fn format_u256_units(amount: U256) -> String {
+    let unit = U256::exp10(18);
+    // The decimals are below 10^18, they fit in a u128.
+    let decimals = format_units((amount % unit).as_u128());
+    format!("{}{}", amount / unit, decimals.trim_start_matches('0'))
+}
+
+/// Scans the settlement layer and the L2 for the transactions of the bootstrapper accounts, from
+/// the heads at [`LedgerRecorder::start`].
+pub struct LedgerRecorder {
+    settlement: SettlementLayerKind,
+    settlement_account: Option<Felt>,
+    l2_senders: Vec<Felt>,
+    settlement_block: u64,
+    l2_block: u64,
+    transactions: Vec<LedgerEntry>,
+}
+
+impl LedgerRecorder {
+    pub async fn start(clients: &Clients, config: &ConfigFile) -> color_eyre::Result<LedgerRecorder> {
+        let settlement = config.settlement_layer();
+        let settlement_account = config.settlement_account_address.as_deref().map(Felt::from_hex).transpose()?;
+        let settlement_block = match settlement {
+            SettlementLayerKind::Ethereum => clients.eth_client().signer().get_block_number().await?.as_u64(),
+            SettlementLayerKind::Starknet => match clients.provider_settlement() {
+                Some(provider) => provider.block_number().await?,
+                None => 0,
+            },
+        };
+        let mut l2_senders = vec![LEGACY_DECLARE_SENDER];
+        match config.l2_deployer_address {
+            Some(ref address) => l2_senders.push(Felt::from_hex(address)?),
+            // The deployer account is then deployed by the bootstrapper, its class is declared from
+            // the temp account unless it is in the genesis state (`cairo1_only`).
+            None if !config.cairo1_only() => l2_senders.push(temp_account_address()?),
+            None => {}
+        }
+        Ok(LedgerRecorder {
+            settlement,
+            settlement_account,
+            l2_senders,
+            settlement_block,
+            l2_block: clients.provider_l2().block_number().await?,
+            transactions: vec![],
+        })
+    }
+
+    /// The L2 transactions sent by `address` are recorded too.
+    pub fn add_l2_sender(&mut self, address: Felt) {
+        if !self.l2_senders.contains(&address) {
+            self.l2_senders.push(address);
+        }
+    }
+
+    /// Records the transactions sent since the previous call as the ones of `step`. Returns the
+    /// hashes of the new settlement transactions.
+    pub async fn record(&mut self, clients: &Clients, step: &str) -> color_eyre::Result<Vec<String>> {
+        let mut entries = vec![];
+        match self.settlement {
+            SettlementLayerKind::Ethereum => {
+                self.settlement_block = self.scan_ethereum(clients, step, &mut entries).await?;
+            }
+            SettlementLayerKind::Starknet => {
+                if let (Some(provider), Some(account)) = (clients.provider_settlement(), self.settlement_account) {
+                    self.settlement_block = scan_starknet(
+                        provider,
+                        self.settlement_block,
+                        &[account],
+                        step,
+                        LedgerChain::Settlement,
+                        &mut entries,
+                    )
+                    .await?;
+                }
+            }
+        }
+        self.l2_block =
+            scan_starknet(clients.provider_l2(), self.l2_block, &self.l2_senders, step, LedgerChain::L2, &mut entries)
+                .await?;
+        let mut settlement_hashes = vec![];
+        for entry in entries {
+            log::debug!("🧾 {} : {:?} transaction {}", entry.step, entry.chain, entry.tx_hash);
+            let (chain, tx_hash) = (entry.chain, entry.tx_hash.clone());
+            if push_entry(&mut self.transactions, entry) && chain == LedgerChain::Settlement {
+                settlement_hashes.push(tx_hash);
+            }
+        }
+        Ok(settlement_hashes)
+    }
+
+    pub fn finish(self) -> Ledger {
+        Ledger::new(self.transactions)
+    }
+
+    async fn scan_ethereum(
+        &self,
+        clients: &Clients,
+        step: &str,
+        entries: &mut Vec<LedgerEntry>,
+    ) -> color_eyre::Result<u64> {
+        let client = clients.eth_client().signer().clone();
+        let sender: Address = client.address();
+        let head = client.get_block_number().await?.as_u64();
+        for number in self.settlement_block + 1..=head {
+            let Some(block) = client.get_block_with_txs(number).await? else {
+                continue;
+            };
+            for tx in block.transactions.into_iter().filter(|tx| tx.from == sender) {
+                let Some(receipt) = client.get_transaction_receipt(tx.hash).await? else {
+                    continue;
+                };
+                let gas_used = receipt.gas_used.unwrap_or_default().as_u128();
+                let effective_gas_price = receipt.effective_gas_price.or(tx.gas_price).unwrap_or_default().as_u128();
+                let (target, function, calldata) = match tx.to {
+                    Some(to) => (
+                        Some(format!("{:?}", to)),
+                        tx.input.get(..4).map(|selector| format!("0x{}", hex::encode(selector))),
+                        tx.input.get(4..).map(hex::encode).unwrap_or_default(),
+                    ),
+                    None => (
+                        receipt.contract_address.map(|address| format!("{:?}", address)),
+                        Some("create".to_string()),
+                        hex::encode(&tx.input),
+                    ),
+                };
+                entries.push(LedgerEntry {
+                    step: step.to_string(),
+                    chain: LedgerChain::Settlement,
+                    tx_hash: format!("{:?}", tx.hash),
+                    block_number: Some(number),
+                    target,
+                    function,
+                    calldata: vec![format!("0x{}", calldata)],
+                    gas_used: Some(gas_used),
+                    effective_gas_price: Some(effective_gas_price),
+                    fee: gas_used * effective_gas_price,
+                    fee_unit: FeeUnit::Wei,
+                });
+            }
+        }
+        Ok(head)
+    }
+}
+
+/// Records the transactions of `senders` in the blocks after `from_block` and in the pending block,
+/// returns the new head.
+async fn scan_starknet(
+    provider: &JsonRpcClient<HttpTransport>,
+    from_block: u64,
+    senders: &[Felt],
+    step: &str,
+    chain: LedgerChain,
+    entries: &mut Vec<LedgerEntry>,
+) -> color_eyre::Result<u64> {
+    let head = provider.block_number().await?;
+    let mut blocks: Vec<BlockId> = (from_block + 1..=head).map(BlockId::Number).collect();
+    blocks.push(BlockId::Tag(BlockTag::Pending));
+
+    for block_id in blocks {
+        let transactions = match provider.get_block_with_txs(block_id).await? {
+            MaybePendingBlockWithTxs::Block(block) => block.transactions,
+            MaybePendingBlockWithTxs::PendingBlock(block) => block.transactions,
+        };
+        for tx in transactions {
+            let tx_hash = *tx.transaction_hash();
+            let Some((sender, target, function, calldata)) = describe_starknet_transaction(&tx) else {
+                continue;
+            };
+            if sender.is_some_and(|sender| !senders.contains(&sender)) {
+                continue;
+            }
+            let receipt = provider.get_transaction_receipt(tx_hash).await?;
+            let (fee, target) = match receipt.receipt {
+                TransactionReceipt::Invoke(ref receipt) => (&receipt.actual_fee, target),
+                TransactionReceipt::Declare(ref receipt) => (&receipt.actual_fee, target),
+                // The sender of a deploy account is the deployed account.
+                TransactionReceipt::DeployAccount(ref receipt) => {
+                    if !senders.contains(&receipt.contract_address) {
+                        continue;
+                    }
+                    (&receipt.actual_fee, Some(receipt.contract_address))
+                }
+                TransactionReceipt::L1Handler(_) | TransactionReceipt::Deploy(_) => continue,
+            };
+            entries.push(LedgerEntry {
+                step: step.to_string(),
+                chain,
+                tx_hash: tx_hash.to_hex_string(),
+                block_number: match receipt.block {
+                    ReceiptBlock::Block { block_number, .. } => Some(block_number),
+                    ReceiptBlock::Pending => None,
+                },
+                target: target.map(|target| target.to_hex_string()),
+                function: Some(function),
+                calldata: calldata.iter().map(Felt::to_hex_string).collect(),
+                gas_used: None,
+                effective_gas_price: None,
+                fee: fee.amount.to_string().parse()?,
+                fee_unit: match fee.unit {
+                    PriceUnit::Wei => FeeUnit::Wei,
+                    PriceUnit::Fri => FeeUnit::Fri,
+                },
+            });
+        }
+    }
+    Ok(head)
+}
+
+type StarknetTransactionDescription = (Option<Felt>, Option<Felt>, String, Vec<Felt>);
+
+/// Sender (`None` for the deploy account transactions), target, function and calldata of the
+/// transactions sent by accounts.
+fn describe_starknet_transaction(tx: &Transaction) -> Option<StarknetTransactionDescription> {
+    // `__execute__` calldata of the accounts : number of calls, then the target and the selector of
+    // the first call.
+    let multicall = |sender: Felt, calldata: &Vec<Felt>| {
+        let target = calldata.get(1).copied();
+        let function = calldata.get(2).map(Felt::to_hex_string).unwrap_or_else(|| "__execute__".to_string());
+        (Some(sender), target, function, calldata.clone())
+    };
+    let declare = |sender: Felt, class_hash: Felt| (Some(sender), Some(class_hash), "declare".to_string(), vec![]);
+    match tx {
+        Transaction::Invoke(InvokeTransaction::V0(tx)) => Some((
+            Some(tx.contract_address),
+            Some(tx.contract_address),
+            tx.entry_point_selector.to_hex_string(),
+            tx.calldata.clone(),
+        )),
+        Transaction::Invoke(InvokeTransaction::V1(tx)) => Some(multicall(tx.sender_address, &tx.calldata)),
+        Transaction::Invoke(InvokeTransaction::V3(tx)) => Some(multicall(tx.sender_address, &tx.calldata)),
+        Transaction::Declare(DeclareTransaction::V0(tx)) => Some(declare(tx.sender_address, tx.class_hash)),
+        Transaction::Declare(DeclareTransaction::V1(tx)) => Some(declare(tx.sender_address, tx.class_hash)),
+        Transaction::Declare(DeclareTransaction::V2(tx)) => Some(declare(tx.sender_address, tx.class_hash)),
+        Transaction::Declare(DeclareTransaction::V3(tx)) => Some(declare(tx.sender_address, tx.class_hash)),
+        Transaction::DeployAccount(_) => Some((None, None, "deploy_account".to_string(), vec![])),
+        Transaction::L1Handler(_) | Transaction::Deploy(_) => None,
+    }
+}
+
+/// Account deployed from [`TEMP_ACCOUNT_PRIV_KEY`] to declare the Cairo 1 OZ account class.
+fn temp_account_address() -> color_eyre::Result<Felt> {
+    let oz_account: LegacyContractClass = load_json_artifact(OZ_ACCOUNT_PATH)?;
+    let public_key = SigningKey::from_secret_scalar(Felt::from_hex(TEMP_ACCOUNT_PRIV_KEY)?).verifying_key().scalar();
+    Ok(get_contract_address(Felt::ZERO, oz_account.class_hash()?, &[public_key], Felt::ZERO))
+}
@@ -0,0 +1,724 @@
+mod bootstrapper;
+pub mod contract_clients;
+pub mod devnet;
+pub mod export;
+pub mod guardrails;
+pub mod helpers;
+pub mod ledger;
+pub mod preflight;
+pub mod settlement;
+pub mod setup_scripts;
+pub mod steps;
+#[cfg(test)]
+pub mod tests;
+pub mod utils;
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub use bootstrapper::{Bootstrapper, BootstrapperBuilder, BootstrapperError, BootstrapperResult};
+use contract_clients::utils::RpcAccount;
+use ethers::abi::Address;
+use serde::{Deserialize, Serialize};
+use setup_scripts::accept_governance::GovernanceAcceptanceOutput;
+use setup_scripts::argent::ArgentSetupOutput;
+use setup_scripts::braavos::BraavosSetupOutput;
+use setup_scripts::core_contract::CoreContractStarknetL1Output;
+use setup_scripts::custom_contracts::{CustomContract, CustomContractOutput, CustomContractsSetup};
+use setup_scripts::erc20_bridge::Erc20BridgeSetupOutput;
+use setup_scripts::eth_bridge::EthBridgeSetupOutput;
+use setup_scripts::genesis::{GenesisBalance, GenesisSetupOutput};
+use setup_scripts::operators::OperatorsOutput;
+use setup_scripts::piltover::PiltoverSetupOutput;
+use setup_scripts::udc::UdcSetupOutput;
+use setup_scripts::update_core_contract::CoreContractUpdateOutput;
+use setup_scripts::upgrade_token_bridge::TokenBridgeUpgradeOutput;
+use starknet::accounts::Account;
+use starknet_core_contract_client::clients::StarknetCoreContractClient;
+use starknet_types_core::felt::Felt;
+use tracing::Instrument;
+
+use crate::contract_clients::config::Clients;
+use crate::contract_clients::declare_v0::DeclareV0Backend;
+use crate::contract_clients::deployer::{Deployer, DeploymentMethod, L2DeployerAccount};
+use crate::contract_clients::governance::GovernanceNomination;
+use crate::contract_clients::proxy_upgrade::{L1ProxyUpgrader, PreparedProxyUpgrade};
+use crate::contract_clients::starknet_core_contract::StarknetCoreContract;
+use crate::ledger::Ledger;
+use crate::settlement::SettlementLayer;
+use crate::setup_scripts::account_setup::account_init;
+use crate::setup_scripts::argent::ArgentSetup;
+use crate::setup_scripts::braavos::BraavosSetup;
+use crate::setup_scripts::core_contract::CoreContractStarknetL1;
+use crate::setup_scripts::erc20_bridge::Erc20Bridge;
+use crate::setup_scripts::eth_bridge::EthBridge;
+use crate::setup_scripts::udc::UdcSetup;
+use crate::setup_scripts::upgrade_eth_token::upgrade_eth_token_to_cairo_1;
+use crate::setup_scripts::upgrade_l1_bridge::upgrade_l1_bridge;
+use crate::setup_scripts::upgrade_l2_bridge::upgrade_eth_bridge_to_cairo_1;
+use crate::setup_scripts::upgrade_token_bridge::upgrade_token_bridge;
+use crate::utils::logging::sub_step;
+use crate::utils::{save_to_json, JsonValueType};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub enum CoreContractMode {
+    Production,
+    Dev,
+}
+
+/// Chain the appchain settles on.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SettlementLayerKind {
+    /// Solidity core contract and StarkGate bridges on `eth_rpc`.
+    #[default]
+    Ethereum,
+    /// Piltover core contract and Cairo token bridge on `settlement_rpc` (L3 appchains).
+    Starknet,
+}
+
+// TODO :                 There is a lot of optional stuff in the config which is needed if we run
+// TODO : (continued.)    individual commands. We need to think of a better design.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ConfigFile {
+    pub eth_rpc: String,
+    pub eth_priv_key: String,
+    pub rollup_seq_url: String,
+    pub rollup_declare_v0_seq_url: String,
+    pub rollup_priv_key: String,
+    pub eth_chain_id: u64,
+    pub l1_deployer_address: String,
+    pub l1_wait_time: String,
+    pub sn_os_program_hash: String,
+    pub config_hash_version: String,
+    pub app_chain_id: String,
+    pub fee_token_address: String,
+    pub native_fee_token_address: String,
+    pub cross_chain_wait_time: u64,
+    pub l1_multisig_address: String,
+    pub l2_multisig_address: String,
+    pub verifier_address: String,
+    pub operator_address: String,
+    pub dev: bool,
+    pub core_contract_mode: CoreContractMode,
+    pub l2_deployer_address: Option<String>,
+    /// Account at `l2_deployer_address`, defaults to `bootstrapper`. The other ones must be
+    /// deployed already.
+    pub l2_deployer_account: Option<L2DeployerAccount>,
+    /// How the L2 contracts are deployed, defaults to `account` for the `bootstrapper` account
+    /// and to `legacy_udc` for the other ones.
+    pub l2_deployment_method: Option<DeploymentMethod>,
+    pub core_contract_address: Option<String>,
+    pub core_contract_implementation_address: Option<String>,
+    /// L1 block the core contract was deployed at, the operator events are queried from it.
+    pub core_contract_deployment_block: Option<u64>,
+    /// Implementation to upgrade the core contract to in `update-core-contract` mode.
+    pub core_contract_new_implementation_address: Option<String>,
+    /// EIC rotating the core contract verifier to `verifier_address` in `update-core-contract`
+    /// mode.
+    pub core_contract_verifier_eic_address: Option<String>,
+    /// State the core contract is initialised with (defaults to zeros).
+    pub initial_block_number: Option<u64>,
+    pub initial_state_root: Option<String>,
+    pub initial_block_hash: Option<String>,
+    pub udc_address: Option<String>,
+    pub l1_eth_bridge_address: Option<String>,
+    pub l2_eth_token_proxy_address: Option<String>,
+    pub l2_eth_bridge_proxy_address: Option<String>,
+    pub l1_token_bridge_address: Option<String>,
+    pub l1_manager_address: Option<String>,
+    pub l1_registry_address: Option<String>,
+    pub l2_token_bridge_address: Option<String>,
+    /// L1 tokens enrolled in the token bridge, checked after a token bridge upgrade.
+    pub l1_enrolled_token_addresses: Option<Vec<String>>,
+    /// Upgrades added to L1 proxies with an activation delay which still need `upgradeTo`.
+    pub pending_l1_upgrades: Option<Vec<PreparedProxyUpgrade>>,
+    /// Operators to add to / remove from the core contract in `manage-operators` mode.
+    pub register_operator_addresses: Option<Vec<String>>,
+    pub unregister_operator_addresses: Option<Vec<String>>,
+    /// Nominations (from a previous output) to complete in `accept-governance` mode.
+    pub governance_nominations: Option<Vec<GovernanceNomination>>,
+    /// Remove `l1_deployer_address` from the governors once the nominee accepted.
+    pub remove_deployer_governor: Option<bool>,
+    /// Where the Safe transaction batch is written when the nominee is a multisig.
+    pub governance_safe_batch_file: Option<String>,
+    /// Extra contracts declared and deployed at the end of `setup-l2` or with `custom-contracts`.
+    pub custom_contracts: Option<Vec<CustomContract>>,
+    /// Defaults to `ethereum`.
+    pub settlement_layer: Option<SettlementLayerKind>,
+    /// Starknet settlement chain RPC and the account deploying the contracts on it.
+    pub settlement_rpc: Option<String>,
+    pub settlement_account_address: Option<String>,
+    pub settlement_priv_key: Option<String>,
+    /// UDC of the settlement chain, defaults to the Starknet one.
+    pub settlement_udc_address: Option<String>,
+    /// Multisig the ownership of the Piltover core contract is transferred to once it is set up,
+    /// the `settlement_account_address` account stays the owner when unset.
+    pub settlement_multisig_address: Option<String>,
+    /// Facts registry set on the Piltover core contract, left unset when absent.
+    pub facts_registry_address: Option<String>,
+    /// Directory the `genesis` mode writes the bundle to, defaults to `genesis`.
+    pub genesis_dir: Option<String>,
+    /// ETH balances of the genesis state, the deployer account gets 1000 ETH unless it is listed.
+    pub genesis_balances: Option<Vec<GenesisBalance>>,
+    /// Madara chain config (e.g. `madara/configs/presets/devnet.yaml`) the `genesis` mode writes
+    /// its fee tokens, chain id and core contract into, required by the `genesis` mode.
+    pub genesis_base_chain_config: Option<String>,
+    /// How the legacy classes are declared on `rollup_declare_v0_seq_url`, defaults to `auto`.
+    pub declare_v0_backend: Option<DeclareV0Backend>,
+    /// Deploys the ETH token and bridge directly as Cairo 1 classes and never declares a legacy
+    /// class, defaults to false. The Cairo 1 OZ account class must already be declared and the UDC
+    /// deployed (at `udc_address`, defaults to the Starknet one), as in the genesis state.
+    pub cairo1_only: Option<bool>,
+}
+
+impl ConfigFile {
+    pub fn settlement_layer(&self) -> SettlementLayerKind {
+        self.settlement_layer.unwrap_or_default()
+    }
+
+    pub fn cairo1_only(&self) -> bool {
+        self.cairo1_only.unwrap_or_default()
+    }
+
+    pub fn l2_deployer_account(&self) -> L2DeployerAccount {
+        self.l2_deployer_account.unwrap_or_default()
+    }
+
+    pub fn l2_deployment_method(&self) -> DeploymentMethod {
+        self.l2_deployment_method.unwrap_or(match self.l2_deployer_account() {
+            L2DeployerAccount::Bootstrapper => DeploymentMethod::Account,
+            L2DeployerAccount::Oz | L2DeployerAccount::Argent | L2DeployerAccount::Braavos => {
+                DeploymentMethod::LegacyUdc
+            }
+        })
+    }
+}
+
+impl Default for ConfigFile {
+    fn default() -> Self {
+        Self {
+            eth_rpc: "http://127.0.0.1:8545".to_string(),
+            eth_priv_key: "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string(),
+            rollup_seq_url: "http://127.0.0.1:19944".to_string(),
+            rollup_declare_v0_seq_url: "http://127.0.0.1:19943".to_string(),
+            rollup_priv_key: "0xabcd".to_string(),
+            eth_chain_id: 31337,
+            l1_deployer_address: "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_string(),
+            l1_wait_time: "15".to_string(),
+            sn_os_program_hash: "0x1e324682835e60c4779a683b32713504aed894fd73842f7d05b18e7bd29cd70".to_string(),
+            config_hash_version: "StarknetOsConfig2".to_string(),
+            app_chain_id: "MADARA_DEVNET".to_string(),
+            fee_token_address: "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7".to_string(),
+            native_fee_token_address: "0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d".to_string(),
+            cross_chain_wait_time: 20,
+            l1_multisig_address: "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".to_string(),
+            l2_multisig_address: "0x556455b8ac8bc00e0ad061d7df5458fa3c372304877663fa21d492a8d5e9435".to_string(),
+            verifier_address: "0x000000000000000000000000000000000000abcd".to_string(),
+            operator_address: "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_string(),
+            dev: false,
+            core_contract_mode: CoreContractMode::Dev,
+            core_contract_address: Some("0xe7f1725e7734ce288f8367e1bb143e90bb3f0512".to_string()),
+            core_contract_implementation_address: Some("0x5fbdb2315678afecb367f032d93f642f64180aa3".to_string()),
+            core_contract_deployment_block: None,
+            core_contract_new_implementation_address: None,
+            core_contract_verifier_eic_address: None,
+            initial_block_number: None,
+            initial_state_root: None,
+            initial_block_hash: None,
+            l2_deployer_address: None,
+            l2_deployer_account: None,
+            l2_deployment_method: None,
+            udc_address: None,
+            l1_eth_bridge_address: None,
+            l2_eth_token_proxy_address: None,
+            l2_eth_bridge_proxy_address: None,
+            l1_token_bridge_address: None,
+            l1_manager_address: None,
+            l1_registry_address: None,
+            l2_token_bridge_address: None,
+            l1_enrolled_token_addresses: None,
+            pending_l1_upgrades: None,
+            register_operator_addresses: None,
+            unregister_operator_addresses: None,
+            governance_nominations: None,
+            remove_deployer_governor: None,
+            governance_safe_batch_file: None,
+            custom_contracts: None,
+            settlement_layer: None,
+            settlement_rpc: None,
+            settlement_account_address: None,
+            settlement_priv_key: None,
+            settlement_udc_address: None,
+            settlement_multisig_address: None,
+            facts_registry_address: None,
+            genesis_dir: None,
+            genesis_balances: None,
+            genesis_base_chain_config: None,
+            declare_v0_backend: None,
+            cairo1_only: None,
+        }
+    }
+}
+
+fn get_core_contract_client(
+    config_file: &ConfigFile,
+    clients: &Clients,
+) -> BootstrapperResult<CoreContractStarknetL1Output> {
+    let core_contract_address = config_address(&config_file.core_contract_address, "core_contract_address")?;
+    let core_contract_implementation_address =
+        config_address(&config_file.core_contract_implementation_address, "core_contract_implementation_address")?;
+    let core_contract_client = StarknetCoreContractClient::new(
+        core_contract_address,
+        clients.eth_client().signer().clone(),
+        core_contract_implementation_address,
+    );
+    Ok(CoreContractStarknetL1Output {
+        core_contract_client: Box::new(StarknetCoreContract { core_contract_client }),
+        deployment_block: config_file.core_contract_deployment_block.unwrap_or_default(),
+        governance_nominations: vec![],
+    })
+}
+
+fn config_address(value: &Option<String>, field: &'static str) -> BootstrapperResult<Address> {
+    let value = value.as_ref().ok_or(BootstrapperError::MissingConfig(field))?;
+    Address::from_str(value).map_err(|_| BootstrapperError::InvalidConfig { field, value: value.clone() })
+}
+
+async fn get_account<'a>(clients: &'a Clients, config_file: &'a ConfigFile) -> color_eyre::Result<RpcAccount<'a>> {
+    log::info!("⏳ L2 State and Initialisation Started");
+    let account = account_init(clients, config_file).await?;
+    log::info!("🔐 Account with given  private key deployed on L2. [Account Address : {:?}]", account.address());
+    Ok(account)
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct BootstrapperOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starknet_contract_address: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starknet_contract_implementation_address: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starknet_contract_deployment_block: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eth_bridge_setup_outputs: Option<EthBridgeSetupOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub erc20_bridge_setup_outputs: Option<Erc20BridgeSetupOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub udc_setup_outputs: Option<UdcSetupOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub argent_setup_outputs: Option<ArgentSetupOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub braavos_setup_outputs: Option<BraavosSetupOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub core_contract_update_outputs: Option<CoreContractUpdateOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_bridge_upgrade_outputs: Option<TokenBridgeUpgradeOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_l1_upgrades: Option<Vec<PreparedProxyUpgrade>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operators_outputs: Option<OperatorsOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub governance_nominations: Option<Vec<GovernanceNomination>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub governance_safe_batch_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifacts_manifest_file: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_contracts_outputs: Option<Vec<CustomContractOutput>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub piltover_setup_outputs: Option<PiltoverSetupOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genesis_outputs: Option<GenesisSetupOutput>,
+    /// Account deployed by the run, reused by the next runs through `--manifest`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub l2_deployer_address: Option<String>,
+    /// Transactions sent by the steps and their cost.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger: Option<Ledger>,
+}
+
+impl BootstrapperOutput {
+    /// Combines the outputs of two steps. The pending upgrades, nominations and ledgers are
+    /// concatenated.
+    pub fn merge(self, other: BootstrapperOutput) -> BootstrapperOutput {
+        fn concat<T>(a: Option<Vec<T>>, b: Option<Vec<T>>) -> Option<Vec<T>> {
+            match (a, b) {
+                (Some(mut a), Some(b)) => {
+                    a.extend(b);
+                    Some(a)
+                }
+                (a, b) => a.or(b),
+            }
+        }
+
+        BootstrapperOutput {
+            starknet_contract_address: other.starknet_contract_address.or(self.starknet_contract_address),
+            starknet_contract_implementation_address: other
+                .starknet_contract_implementation_address
+                .or(self.starknet_contract_implementation_address),
+            starknet_contract_deployment_block: other
+                .starknet_contract_deployment_block
+                .or(self.starknet_contract_deployment_block),
+            eth_bridge_setup_outputs: other.eth_bridge_setup_outputs.or(self.eth_bridge_setup_outputs),
+            erc20_bridge_setup_outputs: other.erc20_bridge_setup_outputs.or(self.erc20_bridge_setup_outputs),
+            udc_setup_outputs: other.udc_setup_outputs.or(self.udc_setup_outputs),
+            argent_setup_outputs: other.argent_setup_outputs.or(self.argent_setup_outputs),
+            braavos_setup_outputs: other.braavos_setup_outputs.or(self.braavos_setup_outputs),
+            core_contract_update_outputs: other.core_contract_update_outputs.or(self.core_contract_update_outputs),
+            token_bridge_upgrade_outputs: other.token_bridge_upgrade_outputs.or(self.token_bridge_upgrade_outputs),
+            pending_l1_upgrades: concat(self.pending_l1_upgrades, other.pending_l1_upgrades),
+            operators_outputs: other.operators_outputs.or(self.operators_outputs),
+            governance_nominations: concat(self.governance_nominations, other.governance_nominations),
+            governance_safe_batch_file: other.governance_safe_batch_file.or(self.governance_safe_batch_file),
+            artifacts_manifest_file: other.artifacts_manifest_file.or(self.artifacts_manifest_file),
+            custom_contracts_outputs: other.custom_contracts_outputs.or(self.custom_contracts_outputs),
+            piltover_setup_outputs: other.piltover_setup_outputs.or(self.piltover_setup_outputs),
+            genesis_outputs: other.genesis_outputs.or(self.genesis_outputs),
+            l2_deployer_address: other.l2_deployer_address.or(self.l2_deployer_address),
+            ledger: match (self.ledger, other.ledger) {
+                (Some(a), Some(b)) => Some(a.merge(b)),
+                (a, b) => a.or(b),
+            },
+        }
+    }
+}
+
+impl From<CoreContractStarknetL1Output> for BootstrapperOutput {
+    fn from(output: CoreContractStarknetL1Output) -> Self {
+        BootstrapperOutput {
+            starknet_contract_address: Some(output.core_contract_client.address()),
+            starknet_contract_implementation_address: Some(output.core_contract_client.implementation_address()),
+            starknet_contract_deployment_block: Some(output.deployment_block),
+            governance_nominations: pending_nominations(output.governance_nominations),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<EthBridgeSetupOutput> for BootstrapperOutput {
+    fn from(output: EthBridgeSetupOutput) -> Self {
+        BootstrapperOutput {
+            governance_nominations: pending_nominations(output.governance_nominations.clone()),
+            eth_bridge_setup_outputs: Some(output),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<Erc20BridgeSetupOutput> for BootstrapperOutput {
+    fn from(output: Erc20BridgeSetupOutput) -> Self {
+        BootstrapperOutput { erc20_bridge_setup_outputs: Some(output), ..Default::default() }
+    }
+}
+
+impl From<UdcSetupOutput> for BootstrapperOutput {
+    fn from(output: UdcSetupOutput) -> Self {
+        BootstrapperOutput { udc_setup_outputs: Some(output), ..Default::default() }
+    }
+}
+
+impl From<ArgentSetupOutput> for BootstrapperOutput {
+    fn from(output: ArgentSetupOutput) -> Self {
+        BootstrapperOutput { argent_setup_outputs: Some(output), ..Default::default() }
+    }
+}
+
+impl From<BraavosSetupOutput> for BootstrapperOutput {
+    fn from(output: BraavosSetupOutput) -> Self {
+        BootstrapperOutput { braavos_setup_outputs: Some(output), ..Default::default() }
+    }
+}
+
+impl From<Vec<CustomContractOutput>> for BootstrapperOutput {
+    fn from(output: Vec<CustomContractOutput>) -> Self {
+        BootstrapperOutput { custom_contracts_outputs: Some(output), ..Default::default() }
+    }
+}
+
+impl From<Vec<PreparedProxyUpgrade>> for BootstrapperOutput {
+    fn from(output: Vec<PreparedProxyUpgrade>) -> Self {
+        BootstrapperOutput { pending_l1_upgrades: pending_upgrades(output), ..Default::default() }
+    }
+}
+
+impl From<TokenBridgeUpgradeOutput> for BootstrapperOutput {
+    fn from(output: TokenBridgeUpgradeOutput) -> Self {
+        BootstrapperOutput {
+            pending_l1_upgrades: pending_upgrades(vec![
+                output.l1_manager_upgrade.clone(),
+                output.l1_registry_upgrade.clone(),
+                output.l1_token_bridge_upgrade.clone(),
+            ]),
+            token_bridge_upgrade_outputs: Some(output),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<CoreContractUpdateOutput> for BootstrapperOutput {
+    fn from(output: CoreContractUpdateOutput) -> Self {
+        BootstrapperOutput {
+            pending_l1_upgrades: pending_upgrades(output.implementation_upgrade.clone().into_iter().collect()),
+            core_contract_update_outputs: Some(output),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<PiltoverSetupOutput> for BootstrapperOutput {
+    fn from(output: PiltoverSetupOutput) -> Self {
+        BootstrapperOutput { piltover_setup_outputs: Some(output), ..Default::default() }
+    }
+}
+
+impl From<GenesisSetupOutput> for BootstrapperOutput {
+    fn from(output: GenesisSetupOutput) -> Self {
+        BootstrapperOutput {
+            governance_nominations: pending_nominations(output.governance_nominations.clone()),
+            l2_deployer_address: Some(output.l2_deployer_address.to_hex_string()),
+            genesis_outputs: Some(output),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<OperatorsOutput> for BootstrapperOutput {
+    fn from(output: OperatorsOutput) -> Self {
+        BootstrapperOutput { operators_outputs: Some(output), ..Default::default() }
+    }
+}
+
+impl From<GovernanceAcceptanceOutput> for BootstrapperOutput {
+    fn from(output: GovernanceAcceptanceOutput) -> Self {
+        BootstrapperOutput {
+            governance_nominations: Some(output.governance_nominations),
+            governance_safe_batch_file: output.safe_batch_file,
+            ..Default::default()
+        }
+    }
+}
+
+async fn setup_core_contract(
+    config_file: &ConfigFile,
+    clients: &Clients,
+) -> color_eyre::Result<CoreContractStarknetL1Output> {
+    let core_contract = CoreContractStarknetL1::new(config_file, clients);
+    let core_contract_client = core_contract.setup().await?;
+    log::info!("📦 Core address : {:?}", core_contract_client.core_contract_client.address());
+    log::info!(
+        "📦 Core implementation address : {:?}",
+        core_contract_client.core_contract_client.implementation_address()
+    );
+    save_to_json(
+        "l1_core_contract_address",
+        &JsonValueType::EthAddress(core_contract_client.core_contract_client.address()),
+    )?;
+    log::info!("✅ Core setup init for L1 successful.");
+    Ok(core_contract_client)
+}
+
+async fn setup_eth_bridge<'a>(
+    account: Option<RpcAccount<'a>>,
+    settlement: &dyn SettlementLayer,
+    config_file: &ConfigFile,
+    clients: &Clients,
+) -> color_eyre::Result<EthBridgeSetupOutput> {
+    let account = match account {
+        Some(account) => account,
+        None => get_account(clients, config_file).await?,
+    };
+    log::info!("⏳ Starting ETH bridge deployment");
+    let eth_bridge = EthBridge::new(account.clone(), account.address(), config_file, clients, settlement);
+    let eth_bridge_setup_outputs = eth_bridge.setup().await?;
+    log::info!("✅ ETH bridge deployment complete.");
+    Ok(eth_bridge_setup_outputs)
+}
+
+async fn upgrade_eth_bridge<'a>(
+    account: Option<RpcAccount<'a>>,
+    config_file: &ConfigFile,
+    clients: &Clients,
+) -> color_eyre::Result<PreparedProxyUpgrade> {
+    // The L2 contracts are already deployed as Cairo 1 classes, only the L1 bridge is upgraded.
+    if config_file.cairo1_only() {
+        log::info!("ℹ️ The L2 ETH token and bridge are deployed as Cairo 1 classes. Skipping their upgrade....");
+    } else {
+        let account = match account {
+            Some(account) => account,
+            None => get_account(clients, config_file).await?,
+        };
+        let deployer = Deployer::from_config(account, config_file)?;
+        upgrade_eth_token_to_cairo_1(
+            &deployer,
+            clients.provider_l2(),
+            config_felt(&config_file.l2_eth_token_proxy_address, "l2_eth_token_proxy_address")?,
+        )
+        .instrument(sub_step!("upgrade_eth_bridge.upgrade_l2_eth_token", "l2"))
+        .await?;
+        upgrade_eth_bridge_to_cairo_1(
+            &deployer,
+            clients.provider_l2(),
+            config_felt(&config_file.l2_eth_bridge_proxy_address, "l2_eth_bridge_proxy_address")?,
+            config_felt(&config_file.l2_eth_token_proxy_address, "l2_eth_token_proxy_address")?,
+        )
+        .instrument(sub_step!("upgrade_eth_bridge.upgrade_l2_eth_bridge", "l2"))
+        .await?;
+    }
+    let l1_upgrade = upgrade_l1_bridge(
+        config_address(&config_file.l1_eth_bridge_address, "l1_eth_bridge_address")?,
+        config_file,
+        clients,
+    )
+    .await?;
+
+    Ok(l1_upgrade)
+}
+
+async fn upgrade_erc20_bridge<'a>(
+    account: Option<RpcAccount<'a>>,
+    config_file: &ConfigFile,
+    clients: &Clients,
+) -> color_eyre::Result<TokenBridgeUpgradeOutput> {
+    let account = match account {
+        Some(account) => account,
+        None => get_account(clients, config_file).await?,
+    };
+    log::info!("⏳ Starting ERC20 token bridge upgrade");
+    upgrade_token_bridge(&account, config_file, clients).await
+}
+
+/// Calls `upgradeTo` for the upgrades which were added in a previous run but were waiting for
+/// the proxy activation delay. Returns all the upgrades with their updated status.
+async fn apply_pending_upgrades(
+    config_file: &ConfigFile,
+    clients: &Clients,
+) -> color_eyre::Result<Vec<PreparedProxyUpgrade>> {
+    let mut pending_upgrades = config_file.pending_l1_upgrades.clone().unwrap_or_default();
+    let upgrader = L1ProxyUpgrader::new(clients.eth_client().signer().clone());
+    for upgrade in pending_upgrades.iter_mut().filter(|upgrade| !upgrade.upgraded) {
+        upgrader.upgrade_to(upgrade).await?;
+    }
+    Ok(pending_upgrades)
+}
+
+fn config_felt(value: &Option<String>, field: &'static str) -> BootstrapperResult<Felt> {
+    let value = value.as_ref().ok_or(BootstrapperError::MissingConfig(field))?;
+    Felt::from_str(value).map_err(|_| BootstrapperError::InvalidConfig { field, value: value.clone() })
+}
+
+fn pending_upgrades(upgrades: Vec<PreparedProxyUpgrade>) -> Option<Vec<PreparedProxyUpgrade>> {
+    let pending: Vec<PreparedProxyUpgrade> = upgrades.into_iter().filter(|upgrade| !upgrade.upgraded).collect();
+    if pending.is_empty() { None } else { Some(pending) }
+}
+
+fn pending_nominations(nominations: Vec<GovernanceNomination>) -> Option<Vec<GovernanceNomination>> {
+    let pending: Vec<GovernanceNomination> =
+        nominations.into_iter().filter(|nomination| !nomination.accepted).collect();
+    if pending.is_empty() { None } else { Some(pending) }
+}
+
+async fn setup_erc20_bridge<'a>(
+    account: Option<RpcAccount<'a>>,
+    settlement: &dyn SettlementLayer,
+    config_file: &ConfigFile,
+    clients: &Clients,
+) -> color_eyre::Result<Erc20BridgeSetupOutput> {
+    let account = match account {
+        Some(account) => account,
+        None => get_account(clients, config_file).await?,
+    };
+    log::info!("⏳ Starting ERC20 token bridge deployment");
+    let erc20_bridge = Erc20Bridge::new(account.clone(), account.address(), config_file, clients, settlement);
+    let erc20_bridge_setup_outputs = erc20_bridge.setup().await?;
+    log::info!("✅ ERC20 token bridge deployment complete.");
+    Ok(erc20_bridge_setup_outputs)
+}
+
+async fn setup_udc<'a>(
+    account: Option<RpcAccount<'a>>,
+    config_file: &ConfigFile,
+    clients: &Clients,
+) -> color_eyre::Result<UdcSetupOutput> {
+    let account = match account {
+        Some(account) => account,
+        None => get_account(clients, config_file).await?,
+    };
+    log::info!("⏳ Starting UDC (Universal Deployer Contract) deployment");
+    let deployer = Deployer::from_config(account, config_file)?;
+    // Deploying through a UDC needs it to be deployed already.
+    let predeployed = config_file.cairo1_only() || deployer.method() != DeploymentMethod::Account;
+    let udc_address = deployer.udc_address();
+    let udc = UdcSetup::new(deployer, clients);
+    let udc_setup_outputs = match predeployed {
+        true => udc.predeployed(udc_address).await?,
+        false => udc.setup().await?,
+    };
+    log::info!(
+        "*️⃣ UDC setup completed. [UDC Address : {:?}, UDC class hash : {:?}]",
+        udc_setup_outputs.udc_address,
+        udc_setup_outputs.udc_class_hash
+    );
+    log::info!("✅ UDC (Universal Deployer Contract) deployment complete.");
+    Ok(udc_setup_outputs)
+}
+
+async fn setup_custom_contracts<'a>(
+    account: Option<RpcAccount<'a>>,
+    config_file: &ConfigFile,
+    clients: &Clients,
+) -> color_eyre::Result<Vec<CustomContractOutput>> {
+    let account = match account {
+        Some(account) => account,
+        None => get_account(clients, config_file).await?,
+    };
+    log::info!("⏳ Starting custom contracts deployment");
+    let custom_contracts = CustomContractsSetup::new(account, config_file, clients);
+    let custom_contracts_outputs = custom_contracts.setup().await?;
+    log::info!("✅ Custom contracts deployment complete.");
+    Ok(custom_contracts_outputs)
+}
+
+async fn setup_argent<'a>(
+    account: Option<RpcAccount<'a>>,
+    config_file: &ConfigFile,
+    clients: &Clients,
+) -> color_eyre::Result<ArgentSetupOutput> {
+    let account = match account {
+        Some(account) => account,
+        None => get_account(clients, config_file).await?,
+    };
+    log::info!("⏳ Starting Argent Account deployment");
+    let argent = ArgentSetup::new(account.clone());
+    let argent_setup_outputs = argent.setup().await?;
+    log::info!("*️⃣ Argent setup completed. [Argent account class hash : {:?}]", argent_setup_outputs.argent_class_hash);
+    log::info!("✅ Argent Account deployment complete.");
+    Ok(argent_setup_outputs)
+}
+
+async fn setup_braavos<'a>(
+    account: Option<RpcAccount<'a>>,
+    config_file: &ConfigFile,
+    clients: &Clients,
+    udc_address: Felt,
+) -> color_eyre::Result<BraavosSetupOutput> {
+    let account = match account {
+        Some(account) => account,
+        None => get_account(clients, config_file).await?,
+    };
+    log::info!("⏳ Starting Braavos Account deployment");
+    // Through the UDC of the `udc` step, the legacy one unless deploying through the Cairo 1 one.
+    let method = match config_file.l2_deployment_method() {
+        DeploymentMethod::Udc => DeploymentMethod::Udc,
+        DeploymentMethod::Account | DeploymentMethod::LegacyUdc => DeploymentMethod::LegacyUdc,
+    };
+    let deployer = Deployer::new(account, method, udc_address);
+    let braavos = BraavosSetup::new(deployer, clients, config_file.cairo1_only());
+    let braavos_setup_outputs = braavos.setup().await?;
+    log::info!(
+        "*️⃣ Braavos setup completed. [Braavos account class hash : {:?}]",
+        braavos_setup_outputs.braavos_class_hash
+    );
+    log::info!("✅ Braavos Account deployment complete.");
+    Ok(braavos_setup_outputs)
+}
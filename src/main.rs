@@ -1,45 +1,16 @@
-pub mod contract_clients;
-pub mod helpers;
-mod setup_scripts;
-#[cfg(test)]
-pub mod tests;
-pub mod utils;
-
 use std::fs::File;
-use std::path::PathBuf;
-use std::str::FromStr;
+use std::path::{Path, PathBuf};
 
-use clap::{Parser, ValueEnum};
-use contract_clients::utils::RpcAccount;
+use clap::{Parser, Subcommand, ValueEnum};
 use dotenv::dotenv;
-use ethers::abi::{AbiEncode, Address};
 use inline_colorization::*;
-use serde::{Deserialize, Serialize};
-use setup_scripts::argent::ArgentSetupOutput;
-use setup_scripts::braavos::BraavosSetupOutput;
-use setup_scripts::core_contract::CoreContractStarknetL1Output;
-use setup_scripts::erc20_bridge::Erc20BridgeSetupOutput;
-use setup_scripts::eth_bridge::EthBridgeSetupOutput;
-use setup_scripts::udc::UdcSetupOutput;
-use starknet::accounts::Account;
-use starknet_core_contract_client::clients::StarknetCoreContractClient;
-use starknet_types_core::felt::Felt;
-
-use crate::contract_clients::config::Clients;
-use crate::contract_clients::starknet_core_contract::StarknetCoreContract;
-use crate::contract_clients::utils::build_single_owner_account;
-use crate::setup_scripts::account_setup::account_init;
-use crate::setup_scripts::argent::ArgentSetup;
-use crate::setup_scripts::braavos::BraavosSetup;
-use crate::setup_scripts::core_contract::CoreContractStarknetL1;
-use crate::setup_scripts::erc20_bridge::Erc20Bridge;
-use crate::setup_scripts::eth_bridge::EthBridge;
-use crate::setup_scripts::udc::UdcSetup;
-use crate::setup_scripts::upgrade_eth_token::upgrade_eth_token_to_cairo_1;
-use crate::setup_scripts::upgrade_l1_bridge::upgrade_l1_bridge;
-use crate::setup_scripts::upgrade_l2_bridge::upgrade_eth_bridge_to_cairo_1;
-use crate::utils::banner::BANNER;
-use crate::utils::{save_to_json, JsonValueType};
+use madara_bootstrapper::devnet::{devnet_up, render_summary, DevnetOptions};
+use madara_bootstrapper::export::{export, ExportOptions};
+use madara_bootstrapper::guardrails::confirm_production_run;
+use madara_bootstrapper::steps::{apply_manifest, render_graph, Step, StepSelection};
+use madara_bootstrapper::utils::banner::BANNER;
+use madara_bootstrapper::utils::logging::{init_logging, LogFormat};
+use madara_bootstrapper::{Bootstrapper, BootstrapperError, BootstrapperOutput, BootstrapperResult, ConfigFile};
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 enum BootstrapMode {
@@ -52,165 +23,183 @@ enum BootstrapMode {
     Argent,
     Braavos,
     UpgradeEthBridge,
+    UpgradeTokenBridge,
+    UpdateCoreContract,
+    ApplyPendingUpgrades,
+    ManageOperators,
+    AcceptGovernance,
+    PinArtifacts,
+    CustomContracts,
+    Genesis,
 }
 
-#[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
-pub struct CliArgs {
-    #[clap(long)]
-    config: Option<PathBuf>,
-    #[clap(long, env, value_enum)]
-    mode: BootstrapMode,
-    #[clap(long, env)]
-    output_file: Option<String>,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub enum CoreContractMode {
-    Production,
-    Dev,
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Local anvil + Madara devnet.
+    #[command(subcommand)]
+    Devnet(DevnetCommand),
+    /// Render the deployment (config and `--manifest`) as the config of another service.
+    Export(ExportOptions),
+    /// Check the config, the chains and the artifacts for the selected steps (`--mode`, `--only`,
+    /// `--skip`, `--from`) without sending any transaction.
+    Preflight,
 }
 
-// TODO :                 There is a lot of optional stuff in the config which is needed if we run
-// TODO : (continued.)    individual commands. We need to think of a better design.
-#[derive(Serialize, Deserialize, Clone)]
-pub struct ConfigFile {
-    pub eth_rpc: String,
-    pub eth_priv_key: String,
-    pub rollup_seq_url: String,
-    pub rollup_declare_v0_seq_url: String,
-    pub rollup_priv_key: String,
-    pub eth_chain_id: u64,
-    pub l1_deployer_address: String,
-    pub l1_wait_time: String,
-    pub sn_os_program_hash: String,
-    pub config_hash_version: String,
-    pub app_chain_id: String,
-    pub fee_token_address: String,
-    pub native_fee_token_address: String,
-    pub cross_chain_wait_time: u64,
-    pub l1_multisig_address: String,
-    pub l2_multisig_address: String,
-    pub verifier_address: String,
-    pub operator_address: String,
-    pub dev: bool,
-    pub core_contract_mode: CoreContractMode,
-    pub l2_deployer_address: Option<String>,
-    pub core_contract_address: Option<String>,
-    pub core_contract_implementation_address: Option<String>,
-    pub udc_address: Option<String>,
-    pub l1_eth_bridge_address: Option<String>,
-    pub l2_eth_token_proxy_address: Option<String>,
-    pub l2_eth_bridge_proxy_address: Option<String>,
+#[derive(Subcommand, Debug)]
+enum DevnetCommand {
+    /// Start anvil and Madara settling on it, bootstrap them and leave them running.
+    Up(DevnetOptions),
 }
 
-impl Default for ConfigFile {
-    fn default() -> Self {
-        Self {
-            eth_rpc: "http://127.0.0.1:8545".to_string(),
-            eth_priv_key: "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string(),
-            rollup_seq_url: "http://127.0.0.1:19944".to_string(),
-            rollup_declare_v0_seq_url: "http://127.0.0.1:19943".to_string(),
-            rollup_priv_key: "0xabcd".to_string(),
-            eth_chain_id: 31337,
-            l1_deployer_address: "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_string(),
-            l1_wait_time: "15".to_string(),
-            sn_os_program_hash: "0x1e324682835e60c4779a683b32713504aed894fd73842f7d05b18e7bd29cd70".to_string(),
-            config_hash_version: "StarknetOsConfig2".to_string(),
-            app_chain_id: "MADARA_DEVNET".to_string(),
-            fee_token_address: "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7".to_string(),
-            native_fee_token_address: "0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d".to_string(),
-            cross_chain_wait_time: 20,
-            l1_multisig_address: "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".to_string(),
-            l2_multisig_address: "0x556455b8ac8bc00e0ad061d7df5458fa3c372304877663fa21d492a8d5e9435".to_string(),
-            verifier_address: "0x000000000000000000000000000000000000abcd".to_string(),
-            operator_address: "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_string(),
-            dev: false,
-            core_contract_mode: CoreContractMode::Dev,
-            core_contract_address: Some("0xe7f1725e7734ce288f8367e1bb143e90bb3f0512".to_string()),
-            core_contract_implementation_address: Some("0x5fbdb2315678afecb367f032d93f642f64180aa3".to_string()),
-            l2_deployer_address: None,
-            udc_address: None,
-            l1_eth_bridge_address: None,
-            l2_eth_token_proxy_address: None,
-            l2_eth_bridge_proxy_address: None,
-        }
-    }
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None, subcommand_negates_reqs = true)]
+pub struct CliArgs {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[clap(long, global = true)]
+    config: Option<PathBuf>,
+    /// Mode to run. Without it, the bootstrap steps selected by `--only`, `--skip` and `--from` are
+    /// run.
+    #[clap(long, env, value_enum, required_unless_present_any = ["only", "skip", "from", "print_graph"])]
+    mode: Option<BootstrapMode>,
+    /// Only run these steps, their prerequisites must be in the config or in `--manifest`.
+    #[clap(long, value_enum, value_delimiter = ',', conflicts_with = "mode")]
+    only: Vec<Step>,
+    #[clap(long, value_enum, value_delimiter = ',', conflicts_with = "mode")]
+    skip: Vec<Step>,
+    /// Run the steps starting from this one.
+    #[clap(long, value_enum, conflicts_with = "mode")]
+    from: Option<Step>,
+    /// Output (`--output-file`) of a previous run to take the addresses missing from the config
+    /// from.
+    #[clap(long, env, global = true)]
+    manifest: Option<PathBuf>,
+    /// Print the step graph (with the selected steps marked) and exit.
+    #[clap(long)]
+    print_graph: bool,
+    #[clap(long, env, global = true)]
+    output_file: Option<String>,
+    /// Initialise the core contract with the latest L2 block instead of the genesis state.
+    #[clap(long)]
+    from_l2_head: bool,
+    /// Directory to load the contract artifacts from instead of the embedded / local ones.
+    #[clap(long, env, global = true)]
+    artifacts_dir: Option<PathBuf>,
+    /// Use the artifacts without checking them against the pinned manifest.
+    #[clap(long, env, global = true)]
+    skip_artifacts_verification: bool,
+    #[clap(long, env, value_enum, global = true, default_value_t = LogFormat::Pretty)]
+    log_format: LogFormat,
+    /// Start sending transactions without running the preflight checks first.
+    #[clap(long)]
+    skip_preflight: bool,
+    /// Run against a production settlement layer without the interactive confirmation.
+    #[clap(long)]
+    i_understand_mainnet: bool,
 }
 
 #[tokio::main]
 pub async fn main() {
-    env_logger::init();
     dotenv().ok();
 
     let args = CliArgs::parse();
+    init_logging(args.log_format).expect("Failed to set up logging");
 
-    println!("{color_red}{}{color_reset}", BANNER);
+    let selection = StepSelection { only: args.only.clone(), skip: args.skip.clone(), from: args.from };
+    if args.print_graph {
+        println!("{}", render_graph(&selection.plan()));
+        return;
+    }
+
+    // The exported config can be written to stdout.
+    if !matches!(args.command, Some(Command::Export(_))) {
+        println!("{color_red}{}{color_reset}", BANNER);
+    }
 
     // Load config from file or use defaults
     let mut config_file = match args.config {
-        Some(path) => {
+        Some(ref path) => {
             let file = File::open(path).expect("Failed to open config file");
             serde_json::from_reader(file).expect("Failed to parse config file")
         }
         None => ConfigFile::default(),
     };
 
-    let clients = Clients::init_from_config(&config_file).await;
-
-    let account = match config_file.l2_deployer_address {
-        Some(ref addr) => Some(
-            build_single_owner_account(clients.provider_l2(), &config_file.rollup_priv_key, &addr.to_string(), false)
-                .await,
-        ),
-        None => None,
-    };
-
-    let output = match args.mode {
-        BootstrapMode::Core | BootstrapMode::SetupL1 => {
-            let output = setup_core_contract(&config_file, &clients).await;
+    if let Some(ref path) = args.manifest {
+        let file = File::open(path).expect("Failed to open manifest file");
+        let manifest = serde_json::from_reader(file).expect("Failed to parse manifest file");
+        apply_manifest(&mut config_file, &manifest);
+    }
 
-            BootstrapperOutput {
-                starknet_contract_address: Some(output.core_contract_client.address()),
-                starknet_contract_implementation_address: Some(output.core_contract_client.implementation_address()),
-                ..Default::default()
+    if let Some(Command::Export(ref options)) = args.command {
+        let rendered = match export(&config_file, options) {
+            Ok(rendered) => rendered,
+            Err(err) => {
+                log::error!("❌ Export failed : {:?}", err);
+                std::process::exit(1);
             }
+        };
+        match options.out {
+            Some(ref out) => std::fs::write(out, rendered).expect("Failed to write the export"),
+            None => print!("{}", rendered),
         }
-        BootstrapMode::SetupL2 => setup_l2(&mut config_file, &clients).await,
-        BootstrapMode::EthBridge => {
-            let core_contract_client = get_core_contract_client(&config_file, &clients);
-            let output = setup_eth_bridge(account, &core_contract_client, &config_file, &clients).await;
-            BootstrapperOutput { eth_bridge_setup_outputs: Some(output), ..Default::default() }
-        }
-        BootstrapMode::Erc20Bridge => {
-            let core_contract_client = get_core_contract_client(&config_file, &clients);
-            let output = setup_erc20_bridge(account, &core_contract_client, &config_file, &clients).await;
-            BootstrapperOutput { erc20_bridge_setup_outputs: Some(output), ..Default::default() }
-        }
-        BootstrapMode::Udc => {
-            let output = setup_udc(account, &config_file, &clients).await;
-            BootstrapperOutput { udc_setup_outputs: Some(output), ..Default::default() }
-        }
-        BootstrapMode::Argent => {
-            let output = setup_argent(account, &config_file, &clients).await;
-            BootstrapperOutput { argent_setup_outputs: Some(output), ..Default::default() }
+        return;
+    }
+
+    if let Some(Command::Devnet(DevnetCommand::Up(ref options))) = args.command {
+        let summary = match devnet_up(
+            config_file,
+            options,
+            args.artifacts_dir.clone(),
+            !args.skip_artifacts_verification,
+        )
+        .await
+        {
+            Ok(summary) => summary,
+            Err(err) => {
+                log::error!("❌ Devnet failed to start : {:?}", err);
+                std::process::exit(1);
+            }
+        };
+        println!("✅ Devnet ready :");
+        println!("{}", render_summary(&summary));
+        if let Some(output_file) = args.output_file {
+            let file = File::create(&output_file).unwrap();
+            serde_json::to_writer_pretty(file, &summary.bootstrap).unwrap();
+            println!("✅ Bootstrap output saved to {}", output_file);
         }
-        BootstrapMode::Braavos => {
-            let output = setup_braavos(
-                account,
-                &config_file,
-                &clients,
-                Felt::from_str(
-                    &config_file.udc_address.clone().expect("UDC Address not available in config. Run with mode UDC"),
-                )
-                .expect("Unable to get UDC address"),
-            )
-            .await;
-            BootstrapperOutput { braavos_setup_outputs: Some(output), ..Default::default() }
+        return;
+    }
+
+    if let Some(Command::Preflight) = args.command {
+        let report = match build_bootstrapper(&args, &selection, config_file).await {
+            Ok(bootstrapper) => bootstrapper.preflight(&planned_steps(args.mode, &selection)).await,
+            Err(err) => {
+                log::error!("❌ Preflight failed : {}", err);
+                std::process::exit(1);
+            }
+        };
+        println!("{}", report);
+        if !report.passed() {
+            std::process::exit(1);
         }
-        BootstrapMode::UpgradeEthBridge => {
-            upgrade_eth_bridge(account, &config_file, &clients).await.expect("Unable to upgrade Eth bridge");
-            BootstrapperOutput { ..Default::default() }
+        return;
+    }
+
+    let output = match run(&args, &selection, config_file).await {
+        Ok(output) => output,
+        Err(err) => {
+            log::error!("❌ Bootstrap failed : {}", err);
+            // The transactions sent before the failure, next to the output the run would have written.
+            if let (BootstrapperError::StepFailed { ledger: Some(ledger), .. }, Some(output_file)) =
+                (&err, &args.output_file)
+            {
+                let ledger_file = Path::new(output_file).with_extension("ledger.json");
+                let file = File::create(&ledger_file).unwrap();
+                serde_json::to_writer_pretty(file, ledger).unwrap();
+                println!("🧾 Ledger of the failed run saved to {}", ledger_file.display());
+            }
+            std::process::exit(1);
         }
     };
 
@@ -228,259 +217,108 @@ pub async fn main() {
     }
 }
 
-fn get_core_contract_client(config_file: &ConfigFile, clients: &Clients) -> CoreContractStarknetL1Output {
-    let Some(core_contract_address) = config_file.core_contract_address.clone() else {
-        panic!("Core contract address is required for ETH bridge setup");
-    };
-    let Some(core_contract_implementation_address) = config_file.core_contract_implementation_address.clone() else {
-        panic!("Core contract implementation address is required for ETH bridge setup");
-    };
-    let core_contract_client = StarknetCoreContractClient::new(
-        Address::from_str(&core_contract_address).unwrap(),
-        clients.eth_client().signer().clone(),
-        Address::from_str(&core_contract_implementation_address).unwrap(),
-    );
-    CoreContractStarknetL1Output { core_contract_client: Box::new(StarknetCoreContract { core_contract_client }) }
-}
-
-async fn get_account<'a>(clients: &'a Clients, config_file: &'a ConfigFile) -> RpcAccount<'a> {
-    log::info!("⏳ L2 State and Initialisation Started");
-    let account = account_init(clients, config_file).await;
-    log::info!("🔐 Account with given  private key deployed on L2. [Account Address : {:?}]", account.address());
-    account
-}
-
-#[derive(Serialize, Clone, Default)]
-pub struct BootstrapperOutput {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub starknet_contract_address: Option<Address>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub starknet_contract_implementation_address: Option<Address>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub eth_bridge_setup_outputs: Option<EthBridgeSetupOutput>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub erc20_bridge_setup_outputs: Option<Erc20BridgeSetupOutput>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub udc_setup_outputs: Option<UdcSetupOutput>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub argent_setup_outputs: Option<ArgentSetupOutput>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub braavos_setup_outputs: Option<BraavosSetupOutput>,
-}
-
-pub async fn bootstrap(config_file: &mut ConfigFile, clients: &Clients) -> BootstrapperOutput {
-    // setup core contract (L1)
-    let core_contract_client = setup_core_contract(config_file, clients).await;
-
-    // setup L2
-    let l2_output = setup_l2(config_file, clients).await;
-
-    BootstrapperOutput {
-        starknet_contract_address: Some(core_contract_client.core_contract_client.address()),
-        starknet_contract_implementation_address: Some(
-            core_contract_client.core_contract_client.implementation_address(),
-        ),
-        ..l2_output
+async fn run(
+    args: &CliArgs,
+    selection: &StepSelection,
+    config_file: ConfigFile,
+) -> BootstrapperResult<BootstrapperOutput> {
+    let mut bootstrapper = build_bootstrapper(args, selection, config_file).await?;
+
+    if !args.skip_preflight && !matches!(args.mode, Some(BootstrapMode::PinArtifacts | BootstrapMode::Genesis)) {
+        let report = bootstrapper.preflight(&planned_steps(args.mode, selection)).await;
+        if !report.passed() {
+            return Err(BootstrapperError::Preflight(report));
+        }
+        log::info!("🛫 Preflight checks passed :\n{}", report);
     }
-}
-
-async fn setup_core_contract(config_file: &ConfigFile, clients: &Clients) -> CoreContractStarknetL1Output {
-    let core_contract = CoreContractStarknetL1::new(config_file, clients);
-    let core_contract_client = core_contract.setup().await;
-    log::info!("📦 Core address : {:?}", core_contract_client.core_contract_client.address());
-    log::info!(
-        "📦 Core implementation address : {:?}",
-        core_contract_client.core_contract_client.implementation_address()
-    );
-    save_to_json(
-        "l1_core_contract_address",
-        &JsonValueType::EthAddress(core_contract_client.core_contract_client.address()),
-    )
-    .unwrap();
-    log::info!("✅ Core setup init for L1 successful.");
-    core_contract_client
-}
-
-async fn setup_eth_bridge<'a>(
-    account: Option<RpcAccount<'a>>,
-    core_contract_client: &CoreContractStarknetL1Output,
-    config_file: &ConfigFile,
-    clients: &Clients,
-) -> EthBridgeSetupOutput {
-    let account = match account {
-        Some(account) => account,
-        None => get_account(clients, config_file).await,
-    };
-    log::info!("⏳ Starting ETH bridge deployment");
-    let eth_bridge = EthBridge::new(
-        account.clone(),
-        account.address(),
-        config_file,
-        clients,
-        core_contract_client.core_contract_client.as_ref(),
-    );
-    let eth_bridge_setup_outputs = eth_bridge.setup().await;
-    log::info!("✅ ETH bridge deployment complete.");
-    eth_bridge_setup_outputs
-}
-
-async fn upgrade_eth_bridge<'a>(
-    account: Option<RpcAccount<'a>>,
-    config_file: &ConfigFile,
-    clients: &Clients,
-) -> color_eyre::Result<()> {
-    let account = match account {
-        Some(account) => account,
-        None => get_account(clients, config_file).await,
-    };
-    upgrade_eth_token_to_cairo_1(
-        &account,
-        clients.provider_l2(),
-        Felt::from_str(
-            &config_file.l2_eth_token_proxy_address.clone().expect("l2_eth_token_proxy_address not in config."),
-        )?,
-    )
-    .await;
-    upgrade_eth_bridge_to_cairo_1(
-        &account,
-        clients.provider_l2(),
-        Felt::from_str(
-            &config_file.l2_eth_bridge_proxy_address.clone().expect("l2_eth_bridge_proxy_address not in config."),
-        )?,
-        Felt::from_str(
-            &config_file.l2_eth_token_proxy_address.clone().expect("l2_eth_token_proxy_address not in config."),
-        )?,
-    )
-    .await;
-    upgrade_l1_bridge(
-        Address::from_str(&config_file.l1_eth_bridge_address.clone().expect("l1_eth_bridge_address not in config."))?,
-        config_file,
-    )
-    .await?;
 
-    Ok(())
-}
+    if args.from_l2_head {
+        bootstrapper.use_l2_head_as_initial_state().await?;
+    }
 
-async fn setup_erc20_bridge<'a>(
-    account: Option<RpcAccount<'a>>,
-    core_contract_client: &CoreContractStarknetL1Output,
-    config_file: &ConfigFile,
-    clients: &Clients,
-) -> Erc20BridgeSetupOutput {
-    let account = match account {
-        Some(account) => account,
-        None => get_account(clients, config_file).await,
+    let Some(mode) = args.mode else {
+        return bootstrapper.run_steps(&selection.plan()).await;
     };
-    log::info!("⏳ Starting ERC20 token bridge deployment");
-    let erc20_bridge = Erc20Bridge::new(
-        account.clone(),
-        account.address(),
-        config_file,
-        clients,
-        core_contract_client.core_contract_client.as_ref(),
-    );
-    let erc20_bridge_setup_outputs = erc20_bridge.setup().await;
-    log::info!("✅ ERC20 token bridge deployment complete.");
-    erc20_bridge_setup_outputs
-}
 
-async fn setup_udc<'a>(account: Option<RpcAccount<'a>>, config_file: &ConfigFile, clients: &Clients) -> UdcSetupOutput {
-    let account = match account {
-        Some(account) => account,
-        None => get_account(clients, config_file).await,
+    let output = match mode {
+        BootstrapMode::Core | BootstrapMode::SetupL1 => bootstrapper.run_steps(&[Step::CoreContract]).await?,
+        BootstrapMode::SetupL2 => bootstrapper.setup_l2().await?,
+        BootstrapMode::EthBridge => bootstrapper.setup_eth_bridge().await?.into(),
+        BootstrapMode::Erc20Bridge => bootstrapper.setup_erc20_bridge().await?.into(),
+        BootstrapMode::Udc => bootstrapper.setup_udc().await?.into(),
+        BootstrapMode::Argent => bootstrapper.setup_argent().await?.into(),
+        BootstrapMode::Braavos => bootstrapper.setup_braavos().await?.into(),
+        BootstrapMode::UpgradeEthBridge => vec![bootstrapper.upgrade_eth_bridge().await?].into(),
+        BootstrapMode::UpgradeTokenBridge => bootstrapper.upgrade_token_bridge().await?.into(),
+        BootstrapMode::UpdateCoreContract => bootstrapper.update_core_contract().await?.into(),
+        BootstrapMode::ApplyPendingUpgrades => bootstrapper.apply_pending_upgrades().await?.into(),
+        BootstrapMode::ManageOperators => bootstrapper.manage_operators().await?.into(),
+        BootstrapMode::AcceptGovernance => bootstrapper.accept_governance().await?.into(),
+        BootstrapMode::PinArtifacts => BootstrapperOutput {
+            artifacts_manifest_file: Some(bootstrapper.pin_artifacts(&args.artifacts_dir)?),
+            ..Default::default()
+        },
+        BootstrapMode::CustomContracts => bootstrapper.setup_custom_contracts().await?.into(),
+        BootstrapMode::Genesis => bootstrapper.genesis().await?.into(),
     };
-    log::info!("⏳ Starting UDC (Universal Deployer Contract) deployment");
-    let udc = UdcSetup::new(account.clone(), account.address(), config_file, clients);
-    let udc_setup_outputs = udc.setup().await;
-    log::info!(
-        "*️⃣ UDC setup completed. [UDC Address : {:?}, UDC class hash : {:?}]",
-        udc_setup_outputs.udc_address,
-        udc_setup_outputs.udc_class_hash
-    );
-    log::info!("✅ UDC (Universal Deployer Contract) deployment complete.");
-    udc_setup_outputs
-}
 
-async fn setup_argent<'a>(
-    account: Option<RpcAccount<'a>>,
-    config_file: &ConfigFile,
-    clients: &Clients,
-) -> ArgentSetupOutput {
-    let account = match account {
-        Some(account) => account,
-        None => get_account(clients, config_file).await,
-    };
-    log::info!("⏳ Starting Argent Account deployment");
-    let argent = ArgentSetup::new(account.clone());
-    let argent_setup_outputs = argent.setup().await;
-    log::info!("*️⃣ Argent setup completed. [Argent account class hash : {:?}]", argent_setup_outputs.argent_class_hash);
-    log::info!("✅ Argent Account deployment complete.");
-    argent_setup_outputs
+    Ok(output)
 }
 
-async fn setup_braavos<'a>(
-    account: Option<RpcAccount<'a>>,
-    config_file: &ConfigFile,
-    clients: &Clients,
-    udc_address: Felt,
-) -> BraavosSetupOutput {
-    let account = match account {
-        Some(account) => account,
-        None => get_account(clients, config_file).await,
+async fn build_bootstrapper(
+    args: &CliArgs,
+    selection: &StepSelection,
+    config_file: ConfigFile,
+) -> BootstrapperResult<Bootstrapper> {
+    // The manifest is what `pin-artifacts` writes, the artifacts can't be checked against it.
+    let verify_artifacts = !args.skip_artifacts_verification && !matches!(args.mode, Some(BootstrapMode::PinArtifacts));
+    let steps = planned_steps(args.mode, selection);
+    let description = match args.mode {
+        Some(mode) if steps.is_empty() => Some(format!("{:?}", mode)),
+        _ => None,
     };
-    log::info!("⏳ Starting Braavos Account deployment");
-    let braavos = BraavosSetup::new(account.clone(), config_file, clients, udc_address);
-    let braavos_setup_outputs = braavos.setup().await;
-    log::info!(
-        "*️⃣ Braavos setup completed. [Braavos account class hash : {:?}]",
-        braavos_setup_outputs.braavos_class_hash
-    );
-    log::info!("✅ Braavos Account deployment complete.");
-    braavos_setup_outputs
+    // The preflight and `pin-artifacts` send no transaction, only the config guardrails apply.
+    let confirmed = args.i_understand_mainnet
+        || matches!(args.command, Some(Command::Preflight))
+        || matches!(args.mode, Some(BootstrapMode::PinArtifacts));
+    let mut builder = Bootstrapper::builder(config_file)
+        .save_addresses(true)
+        .verify_artifacts(verify_artifacts)
+        .plan(steps, description)
+        .confirm_production(move |summary| {
+            if confirmed {
+                log::info!("🚨 The settlement layer is a production chain :\n{}", summary);
+                return Ok(true);
+            }
+            confirm_production_run(summary)
+        });
+    if let Some(ref artifacts_dir) = args.artifacts_dir {
+        builder = builder.artifacts_dir(artifacts_dir.clone());
+    }
+    builder.build().await
 }
 
-pub async fn setup_l2(config_file: &mut ConfigFile, clients: &Clients) -> BootstrapperOutput {
-    // Had to create a temporary clone otherwise the `ConfigFile`
-    // will be dropped after passing into `get_account` function.
-    let config_file_clone = &config_file.clone();
-    let account = get_account(clients, config_file_clone).await;
-
-    let core_contract_client = get_core_contract_client(config_file, clients);
-
-    // setup eth bridge
-    let eth_bridge_setup_outputs =
-        setup_eth_bridge(Some(account.clone()), &core_contract_client, config_file, clients).await;
-
-    // setup erc20 bridge
-    let erc20_bridge_setup_outputs =
-        setup_erc20_bridge(Some(account.clone()), &core_contract_client, config_file, clients).await;
-
-    // setup udc
-    let udc_setup_outputs = setup_udc(Some(account.clone()), config_file, clients).await;
-
-    // setup argent account
-    let argent_setup_outputs = setup_argent(Some(account.clone()), config_file, clients).await;
-
-    // setup braavos account
-    let braavos_setup_outputs =
-        setup_braavos(Some(account.clone()), config_file, clients, udc_setup_outputs.udc_address).await;
-
-    // upgrading the eth bridge
-    config_file.l1_eth_bridge_address = Some(format!(
-        "0x{}",
-        eth_bridge_setup_outputs.l1_bridge_address.encode_hex().trim_start_matches("0x").trim_start_matches('0')
-    ));
-    config_file.l2_eth_token_proxy_address = Some(eth_bridge_setup_outputs.l2_eth_proxy_address.to_hex_string());
-    config_file.l2_eth_bridge_proxy_address =
-        Some(eth_bridge_setup_outputs.l2_eth_bridge_proxy_address.to_hex_string());
-    upgrade_eth_bridge(Some(account), config_file, clients).await.expect("Unable to upgrade ETH bridge.");
-
-    BootstrapperOutput {
-        eth_bridge_setup_outputs: Some(eth_bridge_setup_outputs),
-        erc20_bridge_setup_outputs: Some(erc20_bridge_setup_outputs),
-        udc_setup_outputs: Some(udc_setup_outputs),
-        argent_setup_outputs: Some(argent_setup_outputs),
-        braavos_setup_outputs: Some(braavos_setup_outputs),
-        ..Default::default()
+/// Steps run by `mode`, the maintenance modes only get the chains checked by the preflight.
+fn planned_steps(mode: Option<BootstrapMode>, selection: &StepSelection) -> Vec<Step> {
+    match mode {
+        None => selection.plan(),
+        Some(BootstrapMode::Core | BootstrapMode::SetupL1) => vec![Step::CoreContract],
+        Some(BootstrapMode::SetupL2) => Step::ALL.into_iter().filter(|step| *step != Step::CoreContract).collect(),
+        Some(BootstrapMode::EthBridge) => vec![Step::EthBridge],
+        Some(BootstrapMode::Erc20Bridge) => vec![Step::Erc20Bridge],
+        Some(BootstrapMode::Udc) => vec![Step::Udc],
+        Some(BootstrapMode::Argent) => vec![Step::Argent],
+        Some(BootstrapMode::Braavos) => vec![Step::Braavos],
+        Some(BootstrapMode::CustomContracts) => vec![Step::CustomContracts],
+        Some(BootstrapMode::UpgradeEthBridge) => vec![Step::UpgradeEthBridge],
+        Some(
+            BootstrapMode::UpgradeTokenBridge
+            | BootstrapMode::UpdateCoreContract
+            | BootstrapMode::ApplyPendingUpgrades
+            | BootstrapMode::ManageOperators
+            | BootstrapMode::AcceptGovernance
+            | BootstrapMode::PinArtifacts
+            | BootstrapMode::Genesis,
+        ) => vec![],
     }
 }
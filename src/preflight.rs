@@ -0,0 +1,367 @@
+//! Preflight : checks run before the first transaction of a run (and by the `preflight`
+//! command) so that a misconfiguration fails the run before any state change instead of in the
+//! middle of a step.
+
+use std::fmt;
+use std::str::FromStr;
+
+use color_eyre::eyre::{ensure, eyre};
+use ethers::providers::Middleware;
+use ethers::types::{Address, U256};
+use serde::Serialize;
+use starknet::core::types::contract::legacy::LegacyContractClass;
+use starknet::core::types::{BlockId, BlockTag, Felt};
+use starknet::core::utils::parse_cairo_short_string;
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+use starknet::providers::Provider;
+use starknet_core::types::contract::SierraClass;
+
+use crate::contract_clients::config::Clients;
+use crate::contract_clients::declare_v0::DeclareV0Backend;
+use crate::contract_clients::deployer::DeploymentMethod;
+use crate::ledger::format_u256_units;
+use crate::steps::Step;
+use crate::utils::artifacts::{load_artifact, load_json_artifact, ArtifactKind, KNOWN_ARTIFACTS};
+use crate::utils::constants::{OZ_ACCOUNT_CASM_PATH, OZ_ACCOUNT_PATH, OZ_ACCOUNT_SIERRA_PATH, STARKNET_UDC_ADDRESS};
+use crate::{ConfigFile, CoreContractMode, SettlementLayerKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Passed,
+    /// Reported, doesn't fail the preflight.
+    Warning,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreflightCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PreflightReport {
+    pub checks: Vec<PreflightCheck>,
+}
+
+impl PreflightReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.status != CheckStatus::Failed)
+    }
+
+    fn push(&mut self, name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) {
+        self.checks.push(PreflightCheck { name: name.into(), status, detail: detail.into() });
+    }
+
+    fn push_result(&mut self, name: impl Into<String>, result: color_eyre::Result<String>) {
+        match result {
+            Ok(detail) => self.push(name, CheckStatus::Passed, detail),
+            Err(err) => self.push(name, CheckStatus::Failed, format!("{:#}", err)),
+        }
+    }
+}
+
+impl fmt::Display for PreflightReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let marker = match check.status {
+                CheckStatus::Passed => "✅",
+                CheckStatus::Warning => "⚠️",
+                CheckStatus::Failed => "❌",
+            };
+            writeln!(f, "{} {} : {}", marker, check.name, check.detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs every check for `steps`. The checks never fail, their failures are in the report.
+pub async fn preflight(config: &ConfigFile, clients: &Clients, steps: &[Step]) -> PreflightReport {
+    let mut report = PreflightReport::default();
+    let settlement = config.settlement_layer();
+    // The core contract and the modes without steps (update-core-contract, manage-operators...)
+    // don't reach the L2.
+    let l2_steps = steps.iter().any(|step| *step != Step::CoreContract);
+
+    if l2_steps {
+        report.push_result("l2_rpc", check_l2_chain_id(config, clients).await);
+    }
+    match settlement {
+        SettlementLayerKind::Ethereum => {
+            report.push_result("settlement_rpc", check_ethereum_chain_id(config, clients).await);
+            report.push_result("l1_deployer_balance", check_l1_balance(clients, steps).await);
+        }
+        SettlementLayerKind::Starknet => {
+            report.push_result("settlement_rpc", check_starknet_settlement(clients).await);
+        }
+    }
+
+    let artifacts = required_artifacts(config, steps);
+    report.push_result("artifacts", check_artifacts(&artifacts));
+    report.push_result("declare_v0", check_declare_v0(clients, &artifacts).await);
+    if config.cairo1_only() {
+        report.push_result("cairo1_only", check_cairo1_only(config, clients, steps).await);
+    }
+    if l2_steps && config.l2_deployment_method() != DeploymentMethod::Account {
+        report.push_result("l2_deployer", check_deployment_udc(config, clients).await);
+    }
+    check_contracts(config, clients, steps, &mut report).await;
+
+    report
+}
+
+async fn check_l2_chain_id(config: &ConfigFile, clients: &Clients) -> color_eyre::Result<String> {
+    let chain_id = clients.provider_l2().chain_id().await?;
+    let expected = Felt::from_hex(&hex::encode(config.app_chain_id.as_str()))?;
+    let chain_id_name = parse_cairo_short_string(&chain_id).unwrap_or_else(|_| chain_id.to_hex_string());
+    ensure!(
+        chain_id == expected,
+        "{} answers with chain id {}, `app_chain_id` is {}",
+        config.rollup_seq_url,
+        chain_id_name,
+        config.app_chain_id
+    );
+    Ok(format!("{} (chain id {})", config.rollup_seq_url, chain_id_name))
+}
+
+async fn check_ethereum_chain_id(config: &ConfigFile, clients: &Clients) -> color_eyre::Result<String> {
+    let chain_id = clients.eth_client().signer().get_chainid().await?;
+    ensure!(
+        chain_id == U256::from(config.eth_chain_id),
+        "{} answers with chain id {}, `eth_chain_id` is {}",
+        config.eth_rpc,
+        chain_id,
+        config.eth_chain_id
+    );
+    Ok(format!("{} (chain id {})", config.eth_rpc, chain_id))
+}
+
+async fn check_starknet_settlement(clients: &Clients) -> color_eyre::Result<String> {
+    let provider = clients.provider_settlement().ok_or_else(|| eyre!("`settlement_rpc` is not set"))?;
+    let chain_id = provider.chain_id().await?;
+    Ok(format!("chain id {}", parse_cairo_short_string(&chain_id).unwrap_or_else(|_| chain_id.to_hex_string())))
+}
+
+/// Rough upper bound of the gas used by the L1 transactions of a step.
+fn estimated_l1_gas(step: Step) -> u64 {
+    match step {
+        Step::CoreContract => 8_000_000,
+        Step::EthBridge => 5_000_000,
+        Step::Erc20Bridge => 15_000_000,
+        Step::UpgradeEthBridge => 6_000_000,
+        Step::Udc | Step::Argent | Step::Braavos | Step::CustomContracts => 0,
+    }
+}
+
+/// Estimated L1 gas of `steps` and the current gas price, which is not fetched (zero) when the
+/// steps send no L1 transaction.
+pub async fn estimate_l1_cost(clients: &Clients, steps: &[Step]) -> color_eyre::Result<(u64, U256)> {
+    let gas: u64 = steps.iter().map(|step| estimated_l1_gas(*step)).sum();
+    if gas == 0 {
+        return Ok((0, U256::zero()));
+    }
+    Ok((gas, clients.eth_client().signer().get_gas_price().await?))
+}
+
+async fn check_l1_balance(clients: &Clients, steps: &[Step]) -> color_eyre::Result<String> {
+    let (gas, gas_price) = estimate_l1_cost(clients, steps).await?;
+    if gas == 0 {
+        return Ok("no L1 transaction planned".to_string());
+    }
+    let client = clients.eth_client().signer();
+    let balance = client.get_balance(client.address(), None).await?;
+    let budget = gas_price * U256::from(gas);
+    ensure!(
+        balance >= budget,
+        "{:?} has {} ETH, the steps need about {} ETH ({} gas at {} wei)",
+        client.address(),
+        format_u256_units(balance),
+        format_u256_units(budget),
+        gas,
+        gas_price
+    );
+    Ok(format!("{} ETH, about {} ETH needed", format_u256_units(balance), format_u256_units(budget)))
+}
+
+/// Artifacts of the steps and of the L2 deployer account when it has to be deployed.
+fn required_artifacts(config: &ConfigFile, steps: &[Step]) -> Vec<&'static str> {
+    let mut artifacts = vec![];
+    if config.l2_deployer_address.is_none() && steps.iter().any(|step| *step != Step::CoreContract) {
+        if !config.cairo1_only() {
+            artifacts.push(OZ_ACCOUNT_PATH);
+        }
+        artifacts.extend([OZ_ACCOUNT_SIERRA_PATH, OZ_ACCOUNT_CASM_PATH]);
+    }
+    for path in steps.iter().flat_map(|step| step.artifacts(config)) {
+        if !artifacts.contains(path) {
+            artifacts.push(path);
+        }
+    }
+    artifacts
+}
+
+fn check_artifacts(artifacts: &[&str]) -> color_eyre::Result<String> {
+    let failures: Vec<String> =
+        artifacts.iter().filter_map(|path| load_artifact(path).err().map(|err| format!("{:#}", err))).collect();
+    ensure!(failures.is_empty(), "{}", failures.join(", "));
+    Ok(format!("{} artifacts found and verified", artifacts.len()))
+}
+
+/// The legacy classes still to declare need a declare v0 backend answering on
+/// `rollup_declare_v0_seq_url`, the `predeclared` backend can't declare any.
+async fn check_declare_v0(clients: &Clients, artifacts: &[&str]) -> color_eyre::Result<String> {
+    let mut undeclared = 0;
+    for path in artifacts {
+        if !KNOWN_ARTIFACTS.contains(&(*path, ArtifactKind::Legacy)) {
+            continue;
+        }
+        // Reported by the artifacts check.
+        let Ok(class) = load_json_artifact::<LegacyContractClass>(path) else {
+            continue;
+        };
+        if clients.provider_l2().get_class(BlockId::Tag(BlockTag::Pending), class.class_hash()?).await.is_err() {
+            undeclared += 1;
+        }
+    }
+    if undeclared == 0 {
+        return Ok("no legacy class to declare".to_string());
+    }
+
+    let declare_v0 = clients.declare_v0();
+    let backend = declare_v0.backend().await;
+    ensure!(
+        backend != DeclareV0Backend::Predeclared,
+        "{} legacy classes are not declared and the `predeclared` backend can't declare them (no declare v0 backend \
+         on {})",
+        undeclared,
+        declare_v0.url()
+    );
+    ensure!(
+        declare_v0.is_served(backend).await?,
+        "{} doesn't serve the `{}` declare v0 backend, {} legacy classes need it",
+        declare_v0.url(),
+        backend,
+        undeclared
+    );
+    Ok(format!("{} legacy classes to declare through {} ({})", undeclared, declare_v0.url(), backend))
+}
+
+/// `cairo1_only` can't declare the OZ account class of the deployer nor deploy the UDC, they must
+/// be in the genesis state.
+async fn check_cairo1_only(config: &ConfigFile, clients: &Clients, steps: &[Step]) -> color_eyre::Result<String> {
+    let mut found = vec![];
+    if config.l2_deployer_address.is_none() && steps.iter().any(|step| *step != Step::CoreContract) {
+        let class_hash = load_json_artifact::<SierraClass>(OZ_ACCOUNT_SIERRA_PATH)?.class_hash()?;
+        ensure!(
+            clients.provider_l2().get_class(BlockId::Tag(BlockTag::Pending), class_hash).await.is_ok(),
+            "the OZ account class {} of the deployer is not declared",
+            class_hash.to_hex_string()
+        );
+        found.push(format!("OZ account class {}", class_hash.to_hex_string()));
+    }
+    if steps.contains(&Step::Udc) {
+        let udc_address = Felt::from_str(config.udc_address.as_deref().unwrap_or(STARKNET_UDC_ADDRESS))?;
+        ensure!(
+            clients.provider_l2().get_class_hash_at(BlockId::Tag(BlockTag::Pending), udc_address).await.is_ok(),
+            "no UDC deployed at {}",
+            udc_address.to_hex_string()
+        );
+        found.push(format!("UDC {}", udc_address.to_hex_string()));
+    }
+    if found.is_empty() {
+        return Ok("nothing to check".to_string());
+    }
+    Ok(format!("{} found", found.join(", ")))
+}
+
+/// An existing deployer account deploys through a UDC, which the `udc` step can't deploy.
+async fn check_deployment_udc(config: &ConfigFile, clients: &Clients) -> color_eyre::Result<String> {
+    let method = config.l2_deployment_method();
+    let udc_address = match config.udc_address {
+        Some(ref address) => Felt::from_str(address)?,
+        None => method.default_udc_address(),
+    };
+    ensure!(
+        clients.provider_l2().get_class_hash_at(BlockId::Tag(BlockTag::Pending), udc_address).await.is_ok(),
+        "no UDC deployed at {} for the `{}` deployment method",
+        udc_address.to_hex_string(),
+        method
+    );
+    Ok(format!("{} through the UDC {}", method, udc_address.to_hex_string()))
+}
+
+/// The addresses of the config used by the steps must be deployed contracts.
+async fn check_contracts(config: &ConfigFile, clients: &Clients, steps: &[Step], report: &mut PreflightReport) {
+    let starknet_settlement = config.settlement_layer() == SettlementLayerKind::Starknet;
+    let core_contract_planned = steps.contains(&Step::CoreContract);
+
+    let mut settlement_addresses: Vec<(&str, Option<&String>)> = vec![];
+    if !core_contract_planned {
+        settlement_addresses.push(("core_contract_address", config.core_contract_address.as_ref()));
+    }
+    if !starknet_settlement {
+        if !core_contract_planned {
+            settlement_addresses
+                .push(("core_contract_implementation_address", config.core_contract_implementation_address.as_ref()));
+        }
+        settlement_addresses.extend([
+            ("l1_eth_bridge_address", config.l1_eth_bridge_address.as_ref()),
+            ("l1_token_bridge_address", config.l1_token_bridge_address.as_ref()),
+            ("l1_manager_address", config.l1_manager_address.as_ref()),
+            ("l1_registry_address", config.l1_registry_address.as_ref()),
+        ]);
+    }
+    for (field, address) in settlement_addresses {
+        let Some(address) = address else {
+            continue;
+        };
+        let result = match clients.provider_settlement() {
+            Some(provider) if starknet_settlement => check_starknet_contract(provider, address).await,
+            _ => check_ethereum_contract(clients, address).await,
+        };
+        report.push_result(format!("contract.{}", field), result);
+    }
+
+    // Only called by the production core contract.
+    if core_contract_planned && !starknet_settlement {
+        let name = "contract.verifier_address";
+        match check_ethereum_contract(clients, &config.verifier_address).await {
+            Ok(detail) => report.push(name, CheckStatus::Passed, detail),
+            Err(err) if config.dev || matches!(config.core_contract_mode, CoreContractMode::Dev) => {
+                report.push(name, CheckStatus::Warning, format!("{:#} (unused by the dev core contract)", err))
+            }
+            Err(err) => report.push(name, CheckStatus::Failed, format!("{:#}", err)),
+        }
+    }
+
+    for (field, address) in [
+        ("l2_deployer_address", &config.l2_deployer_address),
+        ("udc_address", &config.udc_address),
+        ("l2_eth_token_proxy_address", &config.l2_eth_token_proxy_address),
+        ("l2_eth_bridge_proxy_address", &config.l2_eth_bridge_proxy_address),
+        ("l2_token_bridge_address", &config.l2_token_bridge_address),
+    ] {
+        if let Some(address) = address {
+            report.push_result(
+                format!("contract.{}", field),
+                check_starknet_contract(clients.provider_l2(), address).await,
+            );
+        }
+    }
+}
+
+async fn check_ethereum_contract(clients: &Clients, address: &str) -> color_eyre::Result<String> {
+    let code = clients.eth_client().signer().get_code(Address::from_str(address)?, None).await?;
+    ensure!(!code.is_empty(), "no contract at {}", address);
+    Ok(format!("{} ({} bytes of code)", address, code.len()))
+}
+
+async fn check_starknet_contract(provider: &JsonRpcClient<HttpTransport>, address: &str) -> color_eyre::Result<String> {
+    let class_hash = provider
+        .get_class_hash_at(BlockId::Tag(BlockTag::Pending), Felt::from_hex(address)?)
+        .await
+        .map_err(|err| eyre!("no contract at {} ({})", address, err))?;
+    Ok(format!("{} (class {})", address, class_hash.to_hex_string()))
+}
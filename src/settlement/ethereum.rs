@@ -0,0 +1,175 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ethers::abi::Address;
+use ethers::types::U256;
+use starknet::core::types::Felt;
+use tracing::Instrument;
+use zaun_utils::LocalWalletSignerMiddleware;
+
+use crate::contract_clients::config::Clients;
+use crate::contract_clients::eth_bridge::{BridgeDeployable, StarknetLegacyEthBridge};
+use crate::contract_clients::governance::{GovernanceKind, GovernanceNomination};
+use crate::contract_clients::proxy_upgrade::L1ProxyUpgrader;
+use crate::contract_clients::token_bridge::StarknetTokenBridge;
+use crate::settlement::{
+    address_from_felt, felt_from_address, SettlementCoreContract, SettlementEthBridge, SettlementLayer,
+    SettlementTokenBridge,
+};
+use crate::utils::logging::sub_step;
+use crate::{get_core_contract_client, setup_core_contract, ConfigFile, SettlementLayerKind};
+
+/// Ethereum settlement : Solidity core contract and StarkGate bridges, deployed with
+/// `eth_priv_key`. The bridges are initialised with the core contract of the config.
+pub struct EthereumSettlement<'a> {
+    config: &'a ConfigFile,
+    clients: &'a Clients,
+}
+
+impl<'a> EthereumSettlement<'a> {
+    pub fn new(config: &'a ConfigFile, clients: &'a Clients) -> Self {
+        Self { config, clients }
+    }
+
+    fn client(&self) -> Arc<LocalWalletSignerMiddleware> {
+        self.clients.eth_client().signer().clone()
+    }
+
+    fn core_contract_address(&self) -> color_eyre::Result<Address> {
+        Ok(get_core_contract_client(self.config, self.clients)?.core_contract_client.address())
+    }
+
+    fn l1_multisig_address(&self) -> color_eyre::Result<Address> {
+        Ok(Address::from_str(&self.config.l1_multisig_address)?)
+    }
+}
+
+#[async_trait(?Send)]
+impl SettlementLayer for EthereumSettlement<'_> {
+    fn kind(&self) -> SettlementLayerKind {
+        SettlementLayerKind::Ethereum
+    }
+
+    fn config_address(&self, address: Felt) -> String {
+        format!("{:?}", address_from_felt(address))
+    }
+
+    async fn deploy_core_contract(&self) -> color_eyre::Result<SettlementCoreContract> {
+        let core_contract = setup_core_contract(self.config, self.clients).await?;
+        Ok(SettlementCoreContract {
+            address: felt_from_address(core_contract.core_contract_client.address()),
+            implementation_address: Some(felt_from_address(
+                core_contract.core_contract_client.implementation_address(),
+            )),
+            output: core_contract.into(),
+        })
+    }
+
+    async fn deploy_eth_bridge(&self) -> color_eyre::Result<Box<dyn SettlementEthBridge>> {
+        let core_contract = self.core_contract_address()?;
+        let bridge = StarknetLegacyEthBridge::deploy(self.client(), self.config.dev).await;
+        log::info!("✴️ ETH Bridge L1 deployment completed [Eth Bridge Address (L1) : {:?}]", bridge.address());
+
+        if self.config.dev {
+            bridge.initialize(core_contract).await;
+        } else {
+            L1ProxyUpgrader::new(self.client())
+                .initialize_proxy(&bridge.proxy_upgrade(core_contract))
+                .instrument(sub_step!("eth_bridge.initialize_proxy", "settlement"))
+                .await?;
+        }
+        log::info!("✴️ ETH Bridge initialization on L1 completed");
+
+        Ok(Box::new(EthereumEthBridge {
+            bridge,
+            l1_multisig_address: self.l1_multisig_address()?,
+            dev: self.config.dev,
+        }))
+    }
+
+    async fn deploy_token_bridge(&self) -> color_eyre::Result<Box<dyn SettlementTokenBridge>> {
+        let core_contract = self.core_contract_address()?;
+        let bridge = StarknetTokenBridge::deploy(self.client(), self.config.dev).await;
+        log::info!(
+            "❇️ ERC20 Token Bridge L1 deployment completed [ERC20 Token Bridge Address (L1) : {:?}]",
+            bridge.bridge_address()
+        );
+
+        let l1_deployer_address = Address::from_str(&self.config.l1_deployer_address)?;
+        if self.config.dev {
+            bridge.initialize(core_contract, l1_deployer_address).await;
+        } else {
+            bridge.setup_permissions_with_bridge_l1(l1_deployer_address, self.l1_multisig_address()?).await;
+            let upgrader = L1ProxyUpgrader::new(self.client());
+            for upgrade in bridge.proxy_upgrades(core_contract) {
+                let span = sub_step!("token_bridge.initialize_proxy", "settlement", proxy = ?upgrade.proxy_address);
+                upgrader.initialize_proxy(&upgrade).instrument(span).await?;
+            }
+        }
+
+        Ok(Box::new(EthereumTokenBridge { bridge }))
+    }
+}
+
+pub struct EthereumEthBridge {
+    bridge: StarknetLegacyEthBridge,
+    l1_multisig_address: Address,
+    dev: bool,
+}
+
+#[async_trait(?Send)]
+impl SettlementEthBridge for EthereumEthBridge {
+    fn address(&self) -> Felt {
+        felt_from_address(self.bridge.address())
+    }
+
+    async fn link_l2_bridge(&self, l2_bridge: Felt) -> color_eyre::Result<()> {
+        self.bridge
+            .setup_l1_bridge(
+                "10000000000000000000000000000000000000000",
+                "10000000000000000000000000000000000000000",
+                l2_bridge,
+                self.l1_multisig_address,
+                self.dev,
+            )
+            .await;
+        Ok(())
+    }
+
+    fn governance_nominations(&self) -> Vec<GovernanceNomination> {
+        if self.dev {
+            vec![]
+        } else {
+            vec![GovernanceNomination::new(self.bridge.address(), GovernanceKind::Proxy, self.l1_multisig_address)]
+        }
+    }
+}
+
+pub struct EthereumTokenBridge {
+    bridge: StarknetTokenBridge,
+}
+
+#[async_trait(?Send)]
+impl SettlementTokenBridge for EthereumTokenBridge {
+    fn address(&self) -> Felt {
+        felt_from_address(self.bridge.bridge_address())
+    }
+
+    fn manager_address(&self) -> Felt {
+        felt_from_address(self.bridge.manager_address())
+    }
+
+    fn registry_address(&self) -> Felt {
+        felt_from_address(self.bridge.registry_address())
+    }
+
+    fn test_token_address(&self) -> Felt {
+        felt_from_address(self.bridge.address())
+    }
+
+    async fn link_l2_bridge(&self, l2_bridge: Felt) -> color_eyre::Result<()> {
+        self.bridge.setup_l1_bridge(U256::from_dec_str("100000000000000").unwrap(), l2_bridge).await;
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+use ethers::abi::Address;
+use starknet::core::types::Felt;
+
+use crate::contract_clients::governance::GovernanceNomination;
+use crate::steps::Step;
+use crate::{BootstrapperOutput, SettlementLayerKind};
+
+pub mod ethereum;
+pub mod piltover;
+
+/// Chain the appchain settles on. The setup scripts only go through this trait for the settlement
+/// side so that other settlement targets (or a mock) can be used without changing them.
+///
+/// Addresses on the settlement layer are felts (Ethereum addresses fit in one).
+#[async_trait(?Send)]
+pub trait SettlementLayer {
+    fn kind(&self) -> SettlementLayerKind;
+
+    /// Whether `step` applies to this settlement layer, the other steps are skipped.
+    fn supports(&self, _step: Step) -> bool {
+        true
+    }
+
+    /// `address` as written in the config file.
+    fn config_address(&self, address: Felt) -> String;
+
+    /// Deploys and initialises the appchain core contract.
+    async fn deploy_core_contract(&self) -> color_eyre::Result<SettlementCoreContract>;
+
+    /// Deploys and initialises the settlement side of the ETH bridge.
+    async fn deploy_eth_bridge(&self) -> color_eyre::Result<Box<dyn SettlementEthBridge>>;
+
+    /// Deploys and initialises the settlement side of the ERC20 token bridge.
+    async fn deploy_token_bridge(&self) -> color_eyre::Result<Box<dyn SettlementTokenBridge>>;
+}
+
+/// Core contract deployed by [`SettlementLayer::deploy_core_contract`].
+#[derive(Clone)]
+pub struct SettlementCoreContract {
+    pub address: Felt,
+    /// Implementation behind the proxy, if the core contract is proxied.
+    pub implementation_address: Option<Felt>,
+    /// Output of the `core-contract` step.
+    pub output: BootstrapperOutput,
+}
+
+/// Settlement side of the ETH bridge.
+#[async_trait(?Send)]
+pub trait SettlementEthBridge {
+    fn address(&self) -> Felt;
+
+    /// Points the bridge to its appchain counterpart and sets the deposit limits.
+    async fn link_l2_bridge(&self, l2_bridge: Felt) -> color_eyre::Result<()>;
+
+    /// Governance nominations sent while setting up the bridge.
+    fn governance_nominations(&self) -> Vec<GovernanceNomination>;
+}
+
+/// Settlement side of the ERC20 token bridge (manager, registry and bridge) with a test token.
+#[async_trait(?Send)]
+pub trait SettlementTokenBridge {
+    fn address(&self) -> Felt;
+
+    fn manager_address(&self) -> Felt;
+
+    fn registry_address(&self) -> Felt;
+
+    /// Token enrolled by [`SettlementTokenBridge::link_l2_bridge`] to check the bridge.
+    fn test_token_address(&self) -> Felt;
+
+    /// Points the bridge to its appchain counterpart and enrolls the test token.
+    async fn link_l2_bridge(&self, l2_bridge: Felt) -> color_eyre::Result<()>;
+}
+
+/// Settlement address of an Ethereum contract.
+pub fn felt_from_address(address: Address) -> Felt {
+    Felt::from_bytes_be_slice(address.as_bytes())
+}
+
+/// Ethereum address of a settlement address, the upper bytes are dropped.
+pub fn address_from_felt(felt: Felt) -> Address {
+    Address::from_slice(&felt.to_bytes_be()[12..])
+}
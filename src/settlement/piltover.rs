@@ -0,0 +1,67 @@
+use async_trait::async_trait;
+use color_eyre::eyre::eyre;
+use starknet::core::types::Felt;
+
+use crate::contract_clients::config::Clients;
+use crate::contract_clients::utils::{build_single_owner_account, RpcAccount};
+use crate::settlement::{SettlementCoreContract, SettlementEthBridge, SettlementLayer, SettlementTokenBridge};
+use crate::setup_scripts::piltover::PiltoverSetup;
+use crate::steps::Step;
+use crate::{BootstrapperError, ConfigFile, SettlementLayerKind};
+
+/// Starknet settlement : Piltover core contract, deployed with the `settlement_account_address`
+/// account. There is no token bridge on this path, the bridge steps are skipped.
+pub struct PiltoverSettlement<'a> {
+    config: &'a ConfigFile,
+    clients: &'a Clients,
+}
+
+impl<'a> PiltoverSettlement<'a> {
+    pub fn new(config: &'a ConfigFile, clients: &'a Clients) -> Self {
+        Self { config, clients }
+    }
+
+    pub async fn account(&self) -> color_eyre::Result<RpcAccount<'a>> {
+        let provider = self.clients.provider_settlement().ok_or(BootstrapperError::MissingConfig("settlement_rpc"))?;
+        let address = self
+            .config
+            .settlement_account_address
+            .as_ref()
+            .ok_or(BootstrapperError::MissingConfig("settlement_account_address"))?;
+        let priv_key =
+            self.config.settlement_priv_key.as_ref().ok_or(BootstrapperError::MissingConfig("settlement_priv_key"))?;
+        Ok(build_single_owner_account(provider, priv_key, address, false).await)
+    }
+}
+
+#[async_trait(?Send)]
+impl SettlementLayer for PiltoverSettlement<'_> {
+    fn kind(&self) -> SettlementLayerKind {
+        SettlementLayerKind::Starknet
+    }
+
+    fn supports(&self, step: Step) -> bool {
+        !matches!(step, Step::EthBridge | Step::Erc20Bridge | Step::UpgradeEthBridge)
+    }
+
+    fn config_address(&self, address: Felt) -> String {
+        address.to_hex_string()
+    }
+
+    async fn deploy_core_contract(&self) -> color_eyre::Result<SettlementCoreContract> {
+        let piltover = PiltoverSetup::new(self.account().await?, self.config).setup().await?;
+        Ok(SettlementCoreContract {
+            address: piltover.core_contract_address,
+            implementation_address: None,
+            output: piltover.into(),
+        })
+    }
+
+    async fn deploy_eth_bridge(&self) -> color_eyre::Result<Box<dyn SettlementEthBridge>> {
+        Err(eyre!("The Starknet settlement layer has no ETH bridge"))
+    }
+
+    async fn deploy_token_bridge(&self) -> color_eyre::Result<Box<dyn SettlementTokenBridge>> {
+        Err(eyre!("The Starknet settlement layer has no token bridge"))
+    }
+}
@@ -0,0 +1,96 @@
+use std::fs::File;
+use std::path::Path;
+use std::str::FromStr;
+
+use color_eyre::eyre::eyre;
+use ethers::abi::Address;
+use ethers::providers::Middleware;
+use serde::Serialize;
+
+use crate::contract_clients::config::Clients;
+use crate::contract_clients::governance::{GovernanceClient, GovernanceNomination, SafeTransactionBatch};
+use crate::ConfigFile;
+
+const DEFAULT_SAFE_BATCH_FILE: &str = "./data/accept_governance_safe_batch.json";
+
+#[derive(Serialize, Clone)]
+pub struct GovernanceAcceptanceOutput {
+    pub governance_nominations: Vec<GovernanceNomination>,
+    /// Safe transaction builder batch to be executed by the nominee (when it is a multisig).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safe_batch_file: Option<String>,
+}
+
+/// Completes the two-step governance transfer of the nominations in `governance_nominations`.
+///
+/// - If `eth_priv_key` is the nominee, `acceptGovernance` is sent directly.
+/// - Otherwise (e.g. the nominee is a Safe multisig) the calls are exported as a Safe transaction
+///   builder batch to `governance_safe_batch_file`.
+///
+/// With `remove_deployer_governor`, `removeGovernor(l1_deployer_address)` is sent (or added to the
+/// batch, after the acceptance) so that the deployer doesn't keep governance rights.
+pub async fn accept_governance(
+    config_file: &ConfigFile,
+    clients: &Clients,
+) -> color_eyre::Result<GovernanceAcceptanceOutput> {
+    let mut nominations =
+        config_file.governance_nominations.clone().ok_or_else(|| eyre!("governance_nominations not in config."))?;
+    let caller = clients.eth_client().signer().address();
+    let deployer = Address::from_str(&config_file.l1_deployer_address)?;
+    let remove_deployer = config_file.remove_deployer_governor.unwrap_or(false);
+
+    let governance = GovernanceClient::new(clients.eth_client().signer().clone());
+    let mut batch = SafeTransactionBatch::new(
+        config_file.eth_chain_id,
+        "Accept governance",
+        "Accepts the governance nominated by the Madara bootstrapper",
+    );
+
+    for nomination in nominations.iter_mut() {
+        governance.refresh(nomination).await?;
+        let sent_by_nominee = caller == nomination.nominee;
+
+        if nomination.accepted {
+            log::info!(
+                "ℹ️ {:?} is already a {:?} governor of {:?}. Skipping....",
+                nomination.nominee,
+                nomination.kind,
+                nomination.contract_address
+            );
+        } else if sent_by_nominee {
+            governance.accept_governance(nomination).await?;
+        } else {
+            batch.push(nomination.contract_address, nomination.accept_governance_calldata());
+        }
+
+        if !remove_deployer
+            || deployer == nomination.nominee
+            || !governance.is_governor(nomination.contract_address, nomination.kind, deployer).await?
+        {
+            continue;
+        }
+        if sent_by_nominee {
+            governance.remove_governor(nomination.contract_address, nomination.kind, deployer).await?;
+        } else {
+            batch.push(nomination.contract_address, nomination.remove_governor_calldata(deployer));
+        }
+    }
+
+    let safe_batch_file = if batch.transactions.is_empty() {
+        None
+    } else {
+        let path = config_file.governance_safe_batch_file.clone().unwrap_or(DEFAULT_SAFE_BATCH_FILE.to_string());
+        if let Some(dir_path) = Path::new(&path).parent() {
+            std::fs::create_dir_all(dir_path)?;
+        }
+        serde_json::to_writer_pretty(File::create(&path)?, &batch)?;
+        log::info!(
+            "🏛️ {} governance transactions to be executed by the nominee saved to {}",
+            batch.transactions.len(),
+            path
+        );
+        Some(path)
+    };
+
+    Ok(GovernanceAcceptanceOutput { governance_nominations: nominations, safe_batch_file })
+}
@@ -1,35 +1,88 @@
 use std::time::Duration;
 
+use color_eyre::eyre::{eyre, WrapErr};
+use starknet::core::types::{BlockId, BlockTag, Felt};
+use starknet::providers::Provider;
+use starknet_core::types::contract::SierraClass;
 use tokio::time::sleep;
+use tracing::Instrument;
 
 use crate::contract_clients::config::Clients;
 use crate::contract_clients::utils::{
     build_single_owner_account, declare_contract, deploy_account_using_priv_key, DeclarationInput, RpcAccount,
     TEMP_ACCOUNT_PRIV_KEY,
 };
+use crate::utils::artifacts::load_json_artifact;
 use crate::utils::constants::{OZ_ACCOUNT_CASM_PATH, OZ_ACCOUNT_PATH, OZ_ACCOUNT_SIERRA_PATH};
+use crate::utils::logging::{sub_step, traced};
 use crate::utils::{convert_to_hex, save_to_json, JsonValueType};
 use crate::ConfigFile;
 
-pub async fn account_init<'a>(clients: &'a Clients, arg_config: &'a ConfigFile) -> RpcAccount<'a> {
+pub async fn account_init<'a>(clients: &'a Clients, arg_config: &'a ConfigFile) -> color_eyre::Result<RpcAccount<'a>> {
+    let oz_account_caio_1_class_hash = match arg_config.cairo1_only() {
+        true => {
+            predeclared_oz_account_class_hash(clients)
+                .instrument(sub_step!("account_init.check_oz_account", "l2"))
+                .await?
+        }
+        false => declare_oz_account_through_temp_account(clients).await?,
+    };
+
+    // Using Account Cairo 1 contract
     // >>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>
-    // Making temp account for declaration of OZ account Cairo 1 contract
-    let oz_account_class_hash = declare_contract(DeclarationInput::LegacyDeclarationInputs(
-        String::from(OZ_ACCOUNT_PATH),
-        arg_config.rollup_declare_v0_seq_url.clone(),
-        clients.provider_l2(),
-    ))
+    let account_address = traced(
+        sub_step!("account_init.deploy_deployer_account", "l2"),
+        deploy_account_using_priv_key(
+            arg_config.rollup_priv_key.clone(),
+            clients.provider_l2(),
+            oz_account_caio_1_class_hash,
+        ),
+    )
     .await;
-    log::info!("OZ Account Class Hash Declared");
-    save_to_json("oz_account_class_hash", &JsonValueType::StringType(oz_account_class_hash.to_string())).unwrap();
-    sleep(Duration::from_secs(10)).await;
+    save_to_json("account_address", &JsonValueType::StringType(account_address.to_string()))?;
+    Ok(build_single_owner_account(
+        clients.provider_l2(),
+        &arg_config.rollup_priv_key,
+        &convert_to_hex(&account_address.to_string()),
+        false,
+    )
+    .await)
+    // >>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>
+}
 
-    log::info!("Waiting for block to be mined [/]");
-    sleep(Duration::from_secs(10)).await;
+/// In `cairo1_only` mode there is no account to declare the Cairo 1 OZ account class from, it
+/// must already be declared (genesis state).
+async fn predeclared_oz_account_class_hash(clients: &Clients) -> color_eyre::Result<Felt> {
+    let oz_account_class: SierraClass = load_json_artifact(OZ_ACCOUNT_SIERRA_PATH)?;
+    let oz_account_class_hash = oz_account_class.class_hash().wrap_err("Invalid OZ account class")?;
+    if clients.provider_l2().get_class(BlockId::Tag(BlockTag::Pending), oz_account_class_hash).await.is_err() {
+        return Err(eyre!(
+            "The OZ account class {:?} is not declared, `cairo1_only` needs it in the genesis state or an existing \
+             `l2_deployer_address`",
+            oz_account_class_hash
+        ));
+    }
+    log::info!("ℹ️ Using the declared OZ account class {:?}", oz_account_class_hash);
+    save_to_json("oz_account_caio_1_class_hash", &JsonValueType::StringType(oz_account_class_hash.to_string()))?;
+    Ok(oz_account_class_hash)
+}
 
-    let account_address_temp =
-        deploy_account_using_priv_key(TEMP_ACCOUNT_PRIV_KEY.to_string(), clients.provider_l2(), oz_account_class_hash)
-            .await;
+async fn declare_oz_account_through_temp_account(clients: &Clients) -> color_eyre::Result<Felt> {
+    // >>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>
+    // Making temp account for declaration of OZ account Cairo 1 contract
+    let oz_account_class_hash = traced(
+        sub_step!("account_init.declare_legacy_oz_account", "l2"),
+        declare_contract(DeclarationInput::LegacyDeclarationInputs(String::from(OZ_ACCOUNT_PATH), clients)),
+    )
+    .await?;
+    log::info!("OZ Account Class Hash Declared");
+    save_to_json("oz_account_class_hash", &JsonValueType::StringType(oz_account_class_hash.to_string()))?;
+
+    let account_address_temp = traced(
+        sub_step!("account_init.deploy_temp_account", "l2"),
+        deploy_account_using_priv_key(TEMP_ACCOUNT_PRIV_KEY.to_string(), clients.provider_l2(), oz_account_class_hash),
+    )
+    .await;
     sleep(Duration::from_secs(10)).await;
 
     let user_account_temp = build_single_owner_account(
@@ -39,32 +92,17 @@ pub async fn account_init<'a>(clients: &'a Clients, arg_config: &'a ConfigFile)
         false,
     )
     .await;
-    let oz_account_caio_1_class_hash = declare_contract(DeclarationInput::DeclarationInputs(
-        String::from(OZ_ACCOUNT_SIERRA_PATH),
-        String::from(OZ_ACCOUNT_CASM_PATH),
-        user_account_temp.clone(),
-    ))
-    .await;
-    save_to_json("oz_account_caio_1_class_hash", &JsonValueType::StringType(oz_account_caio_1_class_hash.to_string()))
-        .unwrap();
-    sleep(Duration::from_secs(10)).await;
-    // >>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>
-
-    // Using Account Cairo 1 contract
-    // >>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>
-    let account_address = deploy_account_using_priv_key(
-        arg_config.rollup_priv_key.clone(),
-        clients.provider_l2(),
-        oz_account_caio_1_class_hash,
-    )
-    .await;
-    save_to_json("account_address", &JsonValueType::StringType(account_address.to_string())).unwrap();
-    build_single_owner_account(
-        clients.provider_l2(),
-        &arg_config.rollup_priv_key,
-        &convert_to_hex(&account_address.to_string()),
-        false,
+    let oz_account_caio_1_class_hash = traced(
+        sub_step!("account_init.declare_oz_account", "l2"),
+        declare_contract(DeclarationInput::DeclarationInputs(
+            String::from(OZ_ACCOUNT_SIERRA_PATH),
+            String::from(OZ_ACCOUNT_CASM_PATH),
+            user_account_temp.clone(),
+        )),
     )
-    .await
+    .await?;
+    save_to_json("oz_account_caio_1_class_hash", &JsonValueType::StringType(oz_account_caio_1_class_hash.to_string()))?;
+    sleep(Duration::from_secs(10)).await;
     // >>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>
+    Ok(oz_account_caio_1_class_hash)
 }
@@ -6,6 +6,7 @@ use tokio::time::sleep;
 
 use crate::contract_clients::utils::{declare_contract, DeclarationInput, RpcAccount};
 use crate::utils::constants::{ARGENT_ACCOUNT_CASM_PATH, ARGENT_ACCOUNT_SIERRA_PATH};
+use crate::utils::logging::{sub_step, traced};
 use crate::utils::{save_to_json, JsonValueType};
 
 pub struct ArgentSetup<'a> {
@@ -22,17 +23,20 @@ impl<'a> ArgentSetup<'a> {
         Self { account }
     }
 
-    pub async fn setup(&self) -> ArgentSetupOutput {
-        let argent_class_hash = declare_contract(DeclarationInput::DeclarationInputs(
-            String::from(ARGENT_ACCOUNT_SIERRA_PATH),
-            String::from(ARGENT_ACCOUNT_CASM_PATH),
-            self.account.clone(),
-        ))
-        .await;
+    pub async fn setup(&self) -> color_eyre::Result<ArgentSetupOutput> {
+        let argent_class_hash = traced(
+            sub_step!("argent.declare_argent_account", "l2"),
+            declare_contract(DeclarationInput::DeclarationInputs(
+                String::from(ARGENT_ACCOUNT_SIERRA_PATH),
+                String::from(ARGENT_ACCOUNT_CASM_PATH),
+                self.account.clone(),
+            )),
+        )
+        .await?;
         log::info!("📣 Argent Hash Declared");
-        save_to_json("argent_class_hash", &JsonValueType::StringType(argent_class_hash.to_string())).unwrap();
+        save_to_json("argent_class_hash", &JsonValueType::StringType(argent_class_hash.to_string()))?;
         sleep(Duration::from_secs(10)).await;
 
-        ArgentSetupOutput { argent_class_hash }
+        Ok(ArgentSetupOutput { argent_class_hash })
     }
 }
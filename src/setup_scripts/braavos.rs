@@ -1,25 +1,24 @@
 use std::time::Duration;
 
+use color_eyre::eyre::WrapErr;
 use serde::Serialize;
-use starknet::accounts::ConnectedAccount;
 use starknet::core::types::Felt;
 use tokio::time::sleep;
 
 use crate::contract_clients::config::Clients;
-use crate::contract_clients::utils::{declare_contract, DeclarationInput, RpcAccount};
-use crate::helpers::account_actions::{get_contract_address_from_deploy_tx, AccountActions};
+use crate::contract_clients::deployer::Deployer;
+use crate::contract_clients::utils::{declare_contract, DeclarationInput};
 use crate::utils::constants::{
     BRAAVOS_ACCOUNT_CASM_PATH, BRAAVOS_ACCOUNT_SIERRA_PATH, BRAAVOS_AGGREGATOR_PATH, BRAAVOS_BASE_ACCOUNT_CASM_PATH,
     BRAAVOS_BASE_ACCOUNT_SIERRA_PATH,
 };
-use crate::utils::{save_to_json, wait_for_transaction, JsonValueType};
-use crate::ConfigFile;
+use crate::utils::logging::{sub_step, traced};
+use crate::utils::{save_to_json, JsonValueType};
 
 pub struct BraavosSetup<'a> {
-    account: RpcAccount<'a>,
-    arg_config: &'a ConfigFile,
+    deployer: Deployer<'a>,
     clients: &'a Clients,
-    udc_address: Felt,
+    cairo1_only: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -28,67 +27,73 @@ pub struct BraavosSetupOutput {
 }
 
 impl<'a> BraavosSetup<'a> {
-    pub fn new(account: RpcAccount<'a>, arg_config: &'a ConfigFile, clients: &'a Clients, udc_address: Felt) -> Self {
-        Self { account, arg_config, clients, udc_address }
+    pub fn new(deployer: Deployer<'a>, clients: &'a Clients, cairo1_only: bool) -> Self {
+        Self { deployer, clients, cairo1_only }
     }
 
-    pub async fn setup(&self) -> BraavosSetupOutput {
-        let braavos_class_hash = declare_contract(DeclarationInput::DeclarationInputs(
-            String::from(BRAAVOS_ACCOUNT_SIERRA_PATH),
-            String::from(BRAAVOS_ACCOUNT_CASM_PATH),
-            self.account.clone(),
-        ))
-        .await;
+    pub async fn setup(&self) -> color_eyre::Result<BraavosSetupOutput> {
+        let braavos_class_hash = traced(
+            sub_step!("braavos.declare_braavos_account", "l2"),
+            declare_contract(DeclarationInput::DeclarationInputs(
+                String::from(BRAAVOS_ACCOUNT_SIERRA_PATH),
+                String::from(BRAAVOS_ACCOUNT_CASM_PATH),
+                self.deployer.account().clone(),
+            )),
+        )
+        .await?;
         log::info!("📣 Braavos Account class hash declared.");
-        save_to_json("braavos_class_hash", &JsonValueType::StringType(braavos_class_hash.to_string())).unwrap();
+        save_to_json("braavos_class_hash", &JsonValueType::StringType(braavos_class_hash.to_string()))?;
         sleep(Duration::from_secs(10)).await;
 
-        let braavos_base_account_class_hash = declare_contract(DeclarationInput::DeclarationInputs(
-            String::from(BRAAVOS_BASE_ACCOUNT_SIERRA_PATH),
-            String::from(BRAAVOS_BASE_ACCOUNT_CASM_PATH),
-            self.account.clone(),
-        ))
-        .await;
+        let braavos_base_account_class_hash = traced(
+            sub_step!("braavos.declare_braavos_base_account", "l2"),
+            declare_contract(DeclarationInput::DeclarationInputs(
+                String::from(BRAAVOS_BASE_ACCOUNT_SIERRA_PATH),
+                String::from(BRAAVOS_BASE_ACCOUNT_CASM_PATH),
+                self.deployer.account().clone(),
+            )),
+        )
+        .await?;
         log::info!("📣 Braavos Base Account class hash declared.");
         save_to_json(
             "braavos_base_account_class_hash",
             &JsonValueType::StringType(braavos_base_account_class_hash.to_string()),
-        )
-        .unwrap();
+        )?;
         sleep(Duration::from_secs(10)).await;
 
-        let braavos_aggregator_class_hash = declare_contract(DeclarationInput::LegacyDeclarationInputs(
-            String::from(BRAAVOS_AGGREGATOR_PATH),
-            self.arg_config.rollup_declare_v0_seq_url.clone(),
-            self.clients.provider_l2(),
-        ))
-        .await;
+        // The aggregator is a legacy class.
+        if self.cairo1_only {
+            log::info!("ℹ️ Braavos Aggregator is not deployed in `cairo1_only` mode. Skipping....");
+        } else {
+            self.deploy_aggregator().await?;
+        }
+
+        Ok(BraavosSetupOutput { braavos_class_hash })
+    }
+
+    async fn deploy_aggregator(&self) -> color_eyre::Result<()> {
+        let braavos_aggregator_class_hash = traced(
+            sub_step!("braavos.declare_aggregator", "l2"),
+            declare_contract(DeclarationInput::LegacyDeclarationInputs(
+                String::from(BRAAVOS_AGGREGATOR_PATH),
+                self.clients,
+            )),
+        )
+        .await?;
         log::info!("📣 Braavos Aggregator class hash declared.");
         save_to_json(
             "braavos_aggregator_class_hash",
             &JsonValueType::StringType(braavos_aggregator_class_hash.to_string()),
-        )
-        .unwrap();
-        sleep(Duration::from_secs(10)).await;
+        )?;
 
-        let deploy_tx = self
-            .account
-            .invoke_contract(
-                self.udc_address,
-                "deployContract",
-                vec![braavos_aggregator_class_hash, Felt::ZERO, Felt::ZERO, Felt::ZERO],
-                None,
-            )
-            .send()
-            .await
-            .expect("Error deploying the contract proxy.");
-        wait_for_transaction(self.account.provider(), deploy_tx.transaction_hash, "deploy_eth_token_on_l2 : deploy")
-            .await
-            .unwrap();
-        let contract_address = get_contract_address_from_deploy_tx(self.account.provider(), &deploy_tx).await.unwrap();
+        let contract_address = traced(
+            sub_step!("braavos.deploy_aggregator", "l2"),
+            self.deployer.deploy(braavos_aggregator_class_hash, Felt::ZERO, false, &[]),
+        )
+        .await
+        .wrap_err("Error deploying the Braavos Aggregator")?;
 
         log::info!("*️⃣ Braavos Aggregator deployed. [Braavos Aggregator : {:?}]", contract_address);
-
-        BraavosSetupOutput { braavos_class_hash }
+        Ok(())
     }
 }
@@ -1,12 +1,22 @@
 use std::str::FromStr;
 
+use color_eyre::eyre::{eyre, WrapErr};
 use ethers::abi::Address;
+use ethers::providers::Middleware;
+use starknet::core::types::{BlockId, BlockTag, Felt, MaybePendingBlockWithTxHashes};
+use starknet_providers::jsonrpc::HttpTransport;
+use starknet_providers::{JsonRpcClient, Provider};
+use tracing::Instrument;
 
 use crate::contract_clients::config::Clients;
-use crate::contract_clients::core_contract::{CoreContract, CoreContractDeploy};
+use crate::contract_clients::core_contract::{get_init_data_core_contract, CoreContract, CoreContractDeploy};
+use crate::contract_clients::governance::{GovernanceKind, GovernanceNomination};
+use crate::contract_clients::proxy_upgrade::{ImplementationSource, L1ProxyUpgrader, ProxyUpgrade};
 use crate::contract_clients::starknet_core_contract::StarknetCoreContract;
 use crate::contract_clients::starknet_dev_core_contract::StarknetDevCoreContract;
 use crate::contract_clients::utils::get_bridge_init_configs;
+use crate::setup_scripts::operators::warn_if_deployer_is_operator;
+use crate::utils::logging::sub_step;
 use crate::utils::{save_to_json, JsonValueType};
 use crate::{ConfigFile, CoreContractMode};
 
@@ -17,6 +27,10 @@ pub struct CoreContractStarknetL1<'a> {
 
 pub struct CoreContractStarknetL1Output {
     pub core_contract_client: Box<dyn CoreContract>,
+    /// L1 block before the deployment, operator events are queried from it.
+    pub deployment_block: u64,
+    /// Governance nominations sent during the setup which are pending acceptance.
+    pub governance_nominations: Vec<GovernanceNomination>,
 }
 
 impl<'a> CoreContractStarknetL1<'a> {
@@ -24,76 +38,120 @@ impl<'a> CoreContractStarknetL1<'a> {
         Self { arg_config, clients }
     }
 
-    pub async fn setup(&self) -> CoreContractStarknetL1Output {
+    pub async fn setup(&self) -> color_eyre::Result<CoreContractStarknetL1Output> {
+        let deployment_block = self
+            .clients
+            .eth_client()
+            .signer()
+            .get_block_number()
+            .await
+            .wrap_err("Failed to get the L1 block number")?
+            .as_u64();
         let core_contract_client: Box<dyn CoreContract> = match self.arg_config.core_contract_mode {
-            CoreContractMode::Production => Box::new(StarknetCoreContract::deploy(self.clients).await),
-            CoreContractMode::Dev => Box::new(StarknetDevCoreContract::deploy(self.clients).await),
+            CoreContractMode::Production => Box::new(
+                StarknetCoreContract::deploy(self.clients)
+                    .instrument(sub_step!("core_contract.deploy_core_contract", "settlement"))
+                    .await,
+            ),
+            CoreContractMode::Dev => Box::new(
+                StarknetDevCoreContract::deploy(self.clients)
+                    .instrument(sub_step!("core_contract.deploy_core_contract", "settlement"))
+                    .await,
+            ),
         };
         log::info!("📦 Core address : {:?}", core_contract_client.address());
 
-        save_to_json("l1_core_contract_address", &JsonValueType::EthAddress(core_contract_client.address())).unwrap();
+        save_to_json("l1_core_contract_address", &JsonValueType::EthAddress(core_contract_client.address()))?;
         let (program_hash, config_hash) = get_bridge_init_configs(self.arg_config);
+        let (block_number, state_root, block_hash) = self.initial_state()?;
+        log::info!(
+            "📦 Core contract initial state : [block number : {:?}, state root : {:?}, block hash : {:?}]",
+            block_number,
+            state_root,
+            block_hash
+        );
 
-        // add_implementation(
-        //      call_data : [
-        //        0, : block number
-        //        0, : state root
-        //        0, : block hash
-        //        program_hash,
-        //        config_hash,
-        //        verifier_address
-        //      ],
-        //      implementation_address,
-        //      finalized_implementation_bool
-        // )
+        let verifier_address = parse_address("verifier_address", &self.arg_config.verifier_address)?;
+        // Calldata of the proxy : [
+        //      eic_address,
+        //      block_number,
+        //      state_root,
+        //      block_hash,
+        //      program_hash,
+        //      config_hash,
+        //      verifier_address
+        // ]
+        let init_data = get_init_data_core_contract(
+            block_number,
+            state_root,
+            block_hash,
+            program_hash,
+            config_hash,
+            verifier_address,
+        );
+        L1ProxyUpgrader::new(self.clients.eth_client().signer().clone())
+            .initialize_proxy(&ProxyUpgrade {
+                proxy_address: core_contract_client.address(),
+                implementation: ImplementationSource::Deployed(core_contract_client.implementation_address()),
+                eic: None,
+                init_data: init_data.into(),
+                finalize: false,
+                post_upgrade: vec![],
+            })
+            .instrument(sub_step!("core_contract.initialize_proxy", "settlement"))
+            .await?;
         core_contract_client
-            .add_implementation_core_contract(
-                0u64.into(), // block number
-                0u64.into(), // state root
-                0u64.into(), // block hash
-                program_hash,
-                config_hash,
-                core_contract_client.implementation_address(),
-                Address::from_str(&self.arg_config.verifier_address.clone()).unwrap(),
-                false,
-            )
-            .await;
-        // upgrade_to(
-        //      call_data : [
-        //        0, : block number
-        //        0, : state root
-        //        0, : block hash
-        //        program_hash,
-        //        config_hash,
-        //        verifier_address
-        //      ],
-        //      implementation_address,
-        //      finalized_implementation_bool
-        // )
-        core_contract_client
-            .upgrade_to_core_contract(
-                0u64.into(), // block number
-                0u64.into(), // state root
-                0u64.into(), // block hash
-                program_hash,
-                config_hash,
-                core_contract_client.implementation_address(),
-                Address::from_str(&self.arg_config.verifier_address.clone()).unwrap(),
-                false,
-            )
-            .await;
-        core_contract_client
-            .register_operator_core_contract(Address::from_str(&self.arg_config.operator_address.clone()).unwrap())
-            .await;
-        core_contract_client
-            .nominate_governor_core_contract(Address::from_str(&self.arg_config.l1_multisig_address.clone()).unwrap())
-            .await;
-        core_contract_client
-            .nominate_governor_core_contract_proxy(
-                Address::from_str(&self.arg_config.l1_multisig_address.clone()).unwrap(),
-            )
+            .register_operator_core_contract(parse_address("operator_address", &self.arg_config.operator_address)?)
+            .instrument(sub_step!("core_contract.register_operator", "settlement"))
             .await;
+        let l1_multisig_address = parse_address("l1_multisig_address", &self.arg_config.l1_multisig_address)?;
+        async {
+            core_contract_client.nominate_governor_core_contract(l1_multisig_address).await;
+            core_contract_client.nominate_governor_core_contract_proxy(l1_multisig_address).await;
+        }
+        .instrument(sub_step!("core_contract.nominate_governors", "settlement"))
+        .await;
+        // The multisig still needs to accept the governance, see `accept-governance` mode.
+        let governance_nominations = vec![
+            GovernanceNomination::new(core_contract_client.address(), GovernanceKind::Starknet, l1_multisig_address),
+            GovernanceNomination::new(core_contract_client.address(), GovernanceKind::Proxy, l1_multisig_address),
+        ];
+        warn_if_deployer_is_operator(
+            core_contract_client.address(),
+            self.clients.eth_client().signer().clone(),
+            self.arg_config,
+        )
+        .await
+        .wrap_err("Unable to check the core contract operators")?;
+
+        Ok(CoreContractStarknetL1Output { core_contract_client, deployment_block, governance_nominations })
+    }
+
+    /// State the core contract is initialised with. Defaults to the genesis state (zeros) unless
+    /// `initial_block_number`, `initial_state_root` and `initial_block_hash` are set in the config
+    /// (e.g. with `--from-l2-head` when migrating an existing chain).
+    fn initial_state(&self) -> color_eyre::Result<(Felt, Felt, Felt)> {
+        let block_number = Felt::from(self.arg_config.initial_block_number.unwrap_or_default());
+        let state_root = match self.arg_config.initial_state_root {
+            Some(ref state_root) => Felt::from_hex(state_root).wrap_err("Invalid initial_state_root")?,
+            None => Felt::ZERO,
+        };
+        let block_hash = match self.arg_config.initial_block_hash {
+            Some(ref block_hash) => Felt::from_hex(block_hash).wrap_err("Invalid initial_block_hash")?,
+            None => Felt::ZERO,
+        };
+        Ok((block_number, state_root, block_hash))
+    }
+}
+
+fn parse_address(field: &str, value: &str) -> color_eyre::Result<Address> {
+    Address::from_str(value).wrap_err_with(|| format!("Invalid {field} : {value}"))
+}
 
-        CoreContractStarknetL1Output { core_contract_client }
+/// Fetches the latest block number, state root and block hash of the L2 chain.
+pub async fn get_l2_head(provider_l2: &JsonRpcClient<HttpTransport>) -> color_eyre::Result<(u64, Felt, Felt)> {
+    match provider_l2.get_block_with_tx_hashes(BlockId::Tag(BlockTag::Latest)).await? {
+        MaybePendingBlockWithTxHashes::Block(block) => Ok((block.block_number, block.new_root, block.block_hash)),
+        MaybePendingBlockWithTxHashes::PendingBlock(_) => Err(eyre!("Latest L2 block is still pending")),
     }
 }
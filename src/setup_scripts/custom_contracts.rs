@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+
+use color_eyre::eyre::eyre;
+use serde::{Deserialize, Serialize};
+use starknet::accounts::ConnectedAccount;
+use starknet::core::types::{BlockId, BlockTag, Felt};
+use starknet_providers::Provider;
+use tracing::Instrument;
+
+use crate::contract_clients::config::Clients;
+use crate::contract_clients::deployer::{Deployer, DeploymentMethod};
+use crate::contract_clients::utils::{declare_contract, DeclarationInput, RpcAccount};
+use crate::utils::artifacts::load_json_artifact;
+use crate::utils::logging::{sub_step, traced};
+use crate::utils::{invoke_contract, save_to_json, JsonValueType};
+use crate::ConfigFile;
+
+/// Contract which is not part of the bootstrapper (oracle, paymaster, registry...) to declare and
+/// deploy, from the `custom_contracts` section of the config.
+///
+/// Calldata values are felts (hex or decimal) or `$<name>` to use the address of a custom contract
+/// deployed before.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomContract {
+    pub name: String,
+    pub artifact: CustomContractArtifact,
+    /// Expected class hash, checked after the declaration.
+    pub class_hash: Option<String>,
+    #[serde(default)]
+    pub constructor_calldata: Vec<String>,
+    pub salt: Option<String>,
+    #[serde(default)]
+    pub deployer: CustomContractDeployer,
+    /// Invokes sent once the contract is deployed.
+    #[serde(default)]
+    pub invokes: Vec<CustomContractInvoke>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomContractArtifact {
+    Sierra { sierra_path: String, casm_path: String },
+    Legacy { path: String },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomContractDeployer {
+    /// Through the UDC (`udc_address`), the Cairo 1 one with the `udc` deployment method.
+    #[default]
+    Udc,
+    /// Same as [`CustomContractDeployer::Udc`] with the deployer address mixed in the address.
+    UdcUnique,
+    /// `deploy_contract` on the bootstrapper account (from zero).
+    Account,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomContractInvoke {
+    /// Defaults to the deployed contract.
+    pub contract_address: Option<String>,
+    pub entrypoint: String,
+    #[serde(default)]
+    pub calldata: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomContractOutput {
+    pub name: String,
+    pub class_hash: Felt,
+    pub address: Felt,
+}
+
+pub struct CustomContractsSetup<'a> {
+    account: RpcAccount<'a>,
+    arg_config: &'a ConfigFile,
+    clients: &'a Clients,
+}
+
+impl<'a> CustomContractsSetup<'a> {
+    pub fn new(account: RpcAccount<'a>, arg_config: &'a ConfigFile, clients: &'a Clients) -> Self {
+        Self { account, arg_config, clients }
+    }
+
+    /// Declares and deploys the `custom_contracts` in order, then sends their invokes.
+    pub async fn setup(&self) -> color_eyre::Result<Vec<CustomContractOutput>> {
+        let mut addresses: HashMap<String, Felt> = HashMap::new();
+        let mut outputs = vec![];
+
+        for contract in self.arg_config.custom_contracts.clone().unwrap_or_default() {
+            let class_hash =
+                traced(sub_step!("custom_contracts.declare", "l2", contract = %contract.name), self.declare(&contract))
+                    .await?;
+            log::info!("🧩 {} class hash declared : {:?}", contract.name, class_hash);
+
+            let address = traced(
+                sub_step!("custom_contracts.deploy", "l2", contract = %contract.name),
+                self.deploy(&contract, class_hash, &addresses),
+            )
+            .await?;
+            log::info!("🧩 {} address : {:?}", contract.name, address);
+            addresses.insert(contract.name.clone(), address);
+
+            for invoke in &contract.invokes {
+                let target = match &invoke.contract_address {
+                    Some(contract_address) => parse_value(contract_address, &addresses)?,
+                    None => address,
+                };
+                let calldata = parse_values(&invoke.calldata, &addresses)?;
+                invoke_contract(target, &invoke.entrypoint, calldata, &self.account)
+                    .instrument(sub_step!(
+                        "custom_contracts.invoke",
+                        "l2",
+                        contract = %contract.name,
+                        entrypoint = %invoke.entrypoint
+                    ))
+                    .await;
+                log::info!("🧩 {} : {} ✅", contract.name, invoke.entrypoint);
+            }
+
+            save_to_json(&format!("{}_address", contract.name), &JsonValueType::StringType(address.to_string()))?;
+            outputs.push(CustomContractOutput { name: contract.name, class_hash, address });
+        }
+
+        Ok(outputs)
+    }
+
+    async fn declare(&self, contract: &CustomContract) -> color_eyre::Result<Felt> {
+        let class_hash = match &contract.artifact {
+            CustomContractArtifact::Sierra { sierra_path, casm_path } => {
+                declare_contract(DeclarationInput::DeclarationInputs(
+                    sierra_path.clone(),
+                    casm_path.clone(),
+                    self.account.clone(),
+                ))
+                .await?
+            }
+            CustomContractArtifact::Legacy { path } => {
+                self.clients.declare_v0().declare(load_json_artifact(path)?, self.clients.provider_l2()).await?
+            }
+        };
+        if let Some(expected) = &contract.class_hash {
+            let expected = Felt::from_hex(expected)?;
+            if expected != class_hash {
+                return Err(eyre!(
+                    "{} class hash is {:?}, expected {:?} (from config)",
+                    contract.name,
+                    class_hash,
+                    expected
+                ));
+            }
+        }
+        Ok(class_hash)
+    }
+
+    async fn deploy(
+        &self,
+        contract: &CustomContract,
+        class_hash: Felt,
+        addresses: &HashMap<String, Felt>,
+    ) -> color_eyre::Result<Felt> {
+        let salt = match &contract.salt {
+            Some(salt) => parse_value(salt, addresses)?,
+            None => Felt::ZERO,
+        };
+        let constructor_calldata = parse_values(&contract.constructor_calldata, addresses)?;
+
+        let (deployer, unique) = match contract.deployer {
+            CustomContractDeployer::Udc | CustomContractDeployer::UdcUnique => {
+                let unique = matches!(contract.deployer, CustomContractDeployer::UdcUnique);
+                (self.udc_deployer()?, unique)
+            }
+            CustomContractDeployer::Account => {
+                if self.arg_config.l2_deployment_method() != DeploymentMethod::Account {
+                    return Err(eyre!(
+                        "{} : only the bootstrapper account has a deploy_contract entrypoint",
+                        contract.name
+                    ));
+                }
+                let method = DeploymentMethod::Account;
+                (Deployer::new(self.account.clone(), method, method.default_udc_address()), false)
+            }
+        };
+        let expected_address = deployer.deployed_address(class_hash, salt, unique, &constructor_calldata);
+
+        if self.account.provider().get_class_hash_at(BlockId::Tag(BlockTag::Pending), expected_address).await.is_ok() {
+            log::info!("ℹ️ {} is already deployed at {:?}. Skipping....", contract.name, expected_address);
+            return Ok(expected_address);
+        }
+
+        let address = deployer.deploy(class_hash, salt, unique, &constructor_calldata).await?;
+        if address != expected_address {
+            log::warn!("⚠️ {} deployed at {:?}, expected {:?}", contract.name, address, expected_address);
+        }
+        Ok(address)
+    }
+
+    /// The UDC of the `l2_deployment_method`. The bootstrapper account deploys through the legacy
+    /// UDC of the `udc` step.
+    fn udc_deployer(&self) -> color_eyre::Result<Deployer<'a>> {
+        match self.arg_config.l2_deployment_method() {
+            DeploymentMethod::Account => {
+                let udc_address = Felt::from_hex(
+                    self.arg_config
+                        .udc_address
+                        .as_ref()
+                        .ok_or_else(|| eyre!("udc_address not in config. Run with mode UDC"))?,
+                )?;
+                Ok(Deployer::new(self.account.clone(), DeploymentMethod::LegacyUdc, udc_address))
+            }
+            DeploymentMethod::LegacyUdc | DeploymentMethod::Udc => {
+                Deployer::from_config(self.account.clone(), self.arg_config)
+            }
+        }
+    }
+}
+
+fn parse_values(values: &[String], addresses: &HashMap<String, Felt>) -> color_eyre::Result<Vec<Felt>> {
+    values.iter().map(|value| parse_value(value, addresses)).collect()
+}
+
+fn parse_value(value: &str, addresses: &HashMap<String, Felt>) -> color_eyre::Result<Felt> {
+    if let Some(name) = value.strip_prefix('$') {
+        return addresses.get(name).copied().ok_or_else(|| eyre!("Unknown custom contract ${name}"));
+    }
+    if value.starts_with("0x") { Ok(Felt::from_hex(value)?) } else { Ok(Felt::from_dec_str(value)?) }
+}
@@ -1,24 +1,29 @@
 use std::str::FromStr;
 use std::time::Duration;
 
-use ethers::abi::Address;
+use color_eyre::eyre::{eyre, WrapErr};
 use serde::Serialize;
-use starknet::accounts::{Account, ConnectedAccount};
 use starknet::core::types::Felt;
 use starknet_providers::jsonrpc::HttpTransport;
 use starknet_providers::JsonRpcClient;
 use tokio::time::sleep;
+use tracing::Instrument;
 
 use crate::contract_clients::config::Clients;
-use crate::contract_clients::core_contract::CoreContract;
-use crate::contract_clients::eth_bridge::{BridgeDeployable, StarknetLegacyEthBridge};
+use crate::contract_clients::deployer::Deployer;
+use crate::contract_clients::eth_bridge::StarknetLegacyEthBridge;
+use crate::contract_clients::governance::GovernanceNomination;
 use crate::contract_clients::utils::{
-    build_single_owner_account, declare_contract, deploy_proxy_contract, init_governance_proxy, DeclarationInput,
-    RpcAccount,
+    declare_contract, deploy_proxy_contract, init_governance_proxy, DeclarationInput, RpcAccount,
 };
-use crate::helpers::account_actions::{get_contract_address_from_deploy_tx, AccountActions};
-use crate::utils::constants::{ERC20_LEGACY_PATH, LEGACY_BRIDGE_PATH, PROXY_LEGACY_PATH, STARKGATE_PROXY_PATH};
-use crate::utils::{convert_to_hex, invoke_contract, save_to_json, wait_for_transaction, JsonValueType};
+use crate::settlement::{address_from_felt, SettlementLayer};
+use crate::utils::constants::{
+    EIC_ETH_BRIDGE_CASM_PATH, EIC_ETH_BRIDGE_SIERRA_PATH, ERC20_LEGACY_PATH, LEGACY_BRIDGE_PATH,
+    NEW_ETH_BRIDGE_CASM_PATH, NEW_ETH_BRIDGE_SIERRA_PATH, NEW_ETH_TOKEN_CASM_PATH, NEW_ETH_TOKEN_SIERRA_PATH,
+    PROXY_LEGACY_PATH, STARKGATE_PROXY_PATH,
+};
+use crate::utils::logging::{sub_step, traced};
+use crate::utils::{invoke_contract, save_to_json, wait_for_transaction, JsonValueType};
 use crate::ConfigFile;
 
 pub struct EthBridge<'a> {
@@ -26,20 +31,32 @@ pub struct EthBridge<'a> {
     account_address: Felt,
     arg_config: &'a ConfigFile,
     clients: &'a Clients,
-    core_contract: &'a dyn CoreContract,
+    settlement: &'a dyn SettlementLayer,
 }
 
+/// In `cairo1_only` mode the ETH token and bridge are not behind a legacy proxy, the `_proxy`
+/// addresses are the contracts themselves and only the Cairo 1 class hashes are set.
 #[derive(Serialize, Clone)]
 pub struct EthBridgeSetupOutput {
-    pub l2_legacy_proxy_class_hash: Felt,
-    pub l2_erc20_legacy_class_hash: Felt,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub l2_legacy_proxy_class_hash: Option<Felt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub l2_erc20_legacy_class_hash: Option<Felt>,
     pub l2_eth_proxy_address: Felt,
-    pub l2_starkgate_proxy_class_hash: Felt,
-    pub l2_legacy_eth_bridge_class_hash: Felt,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub l2_starkgate_proxy_class_hash: Option<Felt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub l2_legacy_eth_bridge_class_hash: Option<Felt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub l2_eth_token_class_hash: Option<Felt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub l2_eth_bridge_class_hash: Option<Felt>,
     pub l2_eth_bridge_proxy_address: Felt,
-    pub l1_bridge_address: Address,
+    pub l1_bridge_address: Felt,
+    /// Proxy governance nomination of the L1 bridge (not in dev mode), reported in the top level
+    /// `governance_nominations`.
     #[serde(skip)]
-    pub l1_bridge: StarknetLegacyEthBridge,
+    pub governance_nominations: Vec<GovernanceNomination>,
 }
 
 impl<'a> EthBridge<'a> {
@@ -48,86 +65,82 @@ impl<'a> EthBridge<'a> {
         account_address: Felt,
         arg_config: &'a ConfigFile,
         clients: &'a Clients,
-        core_contract: &'a dyn CoreContract,
+        settlement: &'a dyn SettlementLayer,
     ) -> Self {
-        Self { account, account_address, arg_config, clients, core_contract }
+        Self { account, account_address, arg_config, clients, settlement }
     }
 
-    pub async fn setup(&self) -> EthBridgeSetupOutput {
-        let legacy_proxy_class_hash = declare_contract(DeclarationInput::LegacyDeclarationInputs(
-            String::from(PROXY_LEGACY_PATH),
-            self.arg_config.rollup_declare_v0_seq_url.clone(),
-            self.clients.provider_l2(),
-        ))
-        .await;
+    pub async fn setup(&self) -> color_eyre::Result<EthBridgeSetupOutput> {
+        if self.arg_config.cairo1_only() {
+            return self.setup_cairo_1().await;
+        }
+
+        let legacy_proxy_class_hash = traced(
+            sub_step!("eth_bridge.declare_legacy_proxy", "l2"),
+            declare_contract(DeclarationInput::LegacyDeclarationInputs(String::from(PROXY_LEGACY_PATH), self.clients)),
+        )
+        .await?;
         log::info!("🎡 Legacy proxy class hash declared.");
-        save_to_json("legacy_proxy_class_hash", &JsonValueType::StringType(legacy_proxy_class_hash.to_string()))
-            .unwrap();
-        sleep(Duration::from_secs(10)).await;
+        save_to_json("legacy_proxy_class_hash", &JsonValueType::StringType(legacy_proxy_class_hash.to_string()))?;
 
-        let starkgate_proxy_class_hash = declare_contract(DeclarationInput::LegacyDeclarationInputs(
-            String::from(STARKGATE_PROXY_PATH),
-            self.arg_config.rollup_declare_v0_seq_url.clone(),
-            self.clients.provider_l2(),
-        ))
-        .await;
+        let starkgate_proxy_class_hash = traced(
+            sub_step!("eth_bridge.declare_starkgate_proxy", "l2"),
+            declare_contract(DeclarationInput::LegacyDeclarationInputs(
+                String::from(STARKGATE_PROXY_PATH),
+                self.clients,
+            )),
+        )
+        .await?;
         log::info!("🎡 Starkgate proxy class hash declared.");
-        save_to_json("starkgate_proxy_class_hash", &JsonValueType::StringType(starkgate_proxy_class_hash.to_string()))
-            .unwrap();
-        sleep(Duration::from_secs(10)).await;
+        save_to_json("starkgate_proxy_class_hash", &JsonValueType::StringType(starkgate_proxy_class_hash.to_string()))?;
 
-        let erc20_legacy_class_hash = declare_contract(DeclarationInput::LegacyDeclarationInputs(
-            String::from(ERC20_LEGACY_PATH),
-            self.arg_config.rollup_declare_v0_seq_url.clone(),
-            self.clients.provider_l2(),
-        ))
-        .await;
+        let erc20_legacy_class_hash = traced(
+            sub_step!("eth_bridge.declare_erc20_legacy", "l2"),
+            declare_contract(DeclarationInput::LegacyDeclarationInputs(String::from(ERC20_LEGACY_PATH), self.clients)),
+        )
+        .await?;
         log::info!("🎡 ERC20 legacy class hash declared.");
-        save_to_json("erc20_legacy_class_hash", &JsonValueType::StringType(erc20_legacy_class_hash.to_string()))
-            .unwrap();
-        sleep(Duration::from_secs(10)).await;
+        save_to_json("erc20_legacy_class_hash", &JsonValueType::StringType(erc20_legacy_class_hash.to_string()))?;
 
-        let legacy_eth_bridge_class_hash = declare_contract(DeclarationInput::LegacyDeclarationInputs(
-            String::from(LEGACY_BRIDGE_PATH),
-            self.arg_config.rollup_declare_v0_seq_url.clone(),
-            self.clients.provider_l2(),
-        ))
-        .await;
+        let legacy_eth_bridge_class_hash = traced(
+            sub_step!("eth_bridge.declare_legacy_bridge", "l2"),
+            declare_contract(DeclarationInput::LegacyDeclarationInputs(String::from(LEGACY_BRIDGE_PATH), self.clients)),
+        )
+        .await?;
         log::info!("🎡 Legacy ETH Bridge class hash declared");
         save_to_json(
             "legacy_eth_bridge_class_hash",
             &JsonValueType::StringType(legacy_eth_bridge_class_hash.to_string()),
-        )
-        .unwrap();
-        sleep(Duration::from_secs(10)).await;
+        )?;
 
-        let eth_proxy_address = deploy_proxy_contract(
-            &self.account,
-            self.account_address,
-            legacy_proxy_class_hash,
-            // salt taken from : https://sepolia.starkscan.co/tx/0x06a5a493cf33919e58aa4c75777bffdef97c0e39cac968896d7bee8cc67905a1
-            Felt::from_str("0x322c2610264639f6b2cee681ac53fa65c37e187ea24292d1b21d859c55e1a78").unwrap(),
-            Felt::ONE,
+        let deployer = self.deployer()?;
+        let eth_proxy_address = traced(
+            sub_step!("eth_bridge.deploy_eth_token_proxy", "l2"),
+            deploy_proxy_contract(
+                &deployer,
+                legacy_proxy_class_hash,
+                // salt taken from : https://sepolia.starkscan.co/tx/0x06a5a493cf33919e58aa4c75777bffdef97c0e39cac968896d7bee8cc67905a1
+                Felt::from_str("0x322c2610264639f6b2cee681ac53fa65c37e187ea24292d1b21d859c55e1a78").unwrap(),
+                false,
+            ),
         )
         .await;
         log::info!("✴️ ETH ERC20 proxy deployed [ETH : {:?}]", eth_proxy_address);
-        save_to_json("l2_eth_address_proxy", &JsonValueType::StringType(eth_proxy_address.to_string())).unwrap();
+        save_to_json("l2_eth_address_proxy", &JsonValueType::StringType(eth_proxy_address.to_string()))?;
         sleep(Duration::from_secs(10)).await;
 
-        let eth_bridge_proxy_address = deploy_proxy_contract(
-            &self.account,
-            self.account_address,
-            legacy_proxy_class_hash,
-            Felt::from_str("0xabcdabcdabcd").unwrap(),
-            Felt::ZERO,
+        let eth_bridge_proxy_address = traced(
+            sub_step!("eth_bridge.deploy_eth_bridge_proxy", "l2"),
+            deploy_proxy_contract(&deployer, legacy_proxy_class_hash, Felt::from_str("0xabcdabcdabcd").unwrap(), true),
         )
         .await;
         log::info!("✴️ ETH Bridge proxy deployed [ETH Bridge : {:?}]", eth_bridge_proxy_address);
-        save_to_json("ETH_l2_bridge_address_proxy", &JsonValueType::StringType(eth_bridge_proxy_address.to_string()))
-            .unwrap();
+        save_to_json("ETH_l2_bridge_address_proxy", &JsonValueType::StringType(eth_bridge_proxy_address.to_string()))?;
         sleep(Duration::from_secs(10)).await;
 
-        init_governance_proxy(&self.account, eth_proxy_address, "eth_proxy_address : init_governance_proxy").await;
+        init_governance_proxy(&self.account, eth_proxy_address, "eth_proxy_address : init_governance_proxy")
+            .instrument(sub_step!("eth_bridge.init_eth_token_governance", "l2"))
+            .await;
         sleep(Duration::from_secs(10)).await;
 
         init_governance_proxy(
@@ -135,87 +148,228 @@ impl<'a> EthBridge<'a> {
             eth_bridge_proxy_address,
             "eth_bridge_proxy_address : init_governance_proxy",
         )
+        .instrument(sub_step!("eth_bridge.init_eth_bridge_governance", "l2"))
         .await;
         sleep(Duration::from_secs(10)).await;
 
-        let eth_bridge =
-            StarknetLegacyEthBridge::deploy(self.core_contract.client().clone(), self.arg_config.dev).await;
+        let l1_bridge = self
+            .settlement
+            .deploy_eth_bridge()
+            .instrument(sub_step!("eth_bridge.deploy_l1_bridge", "settlement"))
+            .await
+            .wrap_err("Failed to deploy the ETH bridge on the settlement layer")?;
+        let l1_bridge_address = l1_bridge.address();
+        save_to_json("ETH_l1_bridge_address", &JsonValueType::EthAddress(address_from_felt(l1_bridge_address)))?;
 
-        log::info!("✴️ ETH Bridge L1 deployment completed [Eth Bridge Address (L1) : {:?}]", eth_bridge.address());
-        save_to_json("ETH_l1_bridge_address", &JsonValueType::EthAddress(eth_bridge.address())).unwrap();
-
-        let account = build_single_owner_account(
-            self.clients.provider_l2(),
-            &self.arg_config.rollup_priv_key,
-            &convert_to_hex(&self.account_address.to_string()),
-            false,
-        )
-        .await;
-
-        let l2_bridge_address = StarknetLegacyEthBridge::deploy_l2_contracts(
-            self.clients.provider_l2(),
-            legacy_eth_bridge_class_hash,
-            eth_bridge_proxy_address,
-            &account,
+        let l2_bridge_address = traced(
+            sub_step!("eth_bridge.deploy_l2_bridge", "l2"),
+            StarknetLegacyEthBridge::deploy_l2_contracts(
+                self.clients.provider_l2(),
+                legacy_eth_bridge_class_hash,
+                eth_bridge_proxy_address,
+                &deployer,
+            ),
         )
         .await;
 
         log::info!("✴️ ETH Bridge L2 deployment completed [Eth Bridge Address (L2) : {:?}]", l2_bridge_address);
-        save_to_json("ETH_l2_bridge_address", &JsonValueType::StringType(l2_bridge_address.to_string())).unwrap();
+        save_to_json("ETH_l2_bridge_address", &JsonValueType::StringType(l2_bridge_address.to_string()))?;
 
-        let eth_address = deploy_eth_token_on_l2(
-            self.clients.provider_l2(),
-            eth_proxy_address,
-            erc20_legacy_class_hash,
-            &account,
-            l2_bridge_address,
+        let eth_address = traced(
+            sub_step!("eth_bridge.deploy_eth_token", "l2"),
+            deploy_eth_token_on_l2(
+                self.clients.provider_l2(),
+                eth_proxy_address,
+                erc20_legacy_class_hash,
+                &deployer,
+                l2_bridge_address,
+            ),
         )
-        .await;
+        .await?;
 
         log::info!("✴️ L2 ETH token deployment successful.");
         // save_to_json("l2_eth_address", &JsonValueType::StringType(eth_address.to_string()))?;
-        if self.arg_config.dev {
-            eth_bridge.initialize(self.core_contract.address()).await;
-        } else {
-            eth_bridge.add_implementation_eth_bridge(self.core_contract.address()).await;
-            eth_bridge.upgrade_to_eth_bridge(self.core_contract.address()).await;
-        }
-        log::info!("✴️ ETH Bridge initialization on L1 completed");
 
-        sleep(Duration::from_secs(self.arg_config.l1_wait_time.parse().unwrap())).await;
+        sleep(Duration::from_secs(self.arg_config.l1_wait_time.parse()?)).await;
 
-        eth_bridge
-            .setup_l2_bridge(
-                self.clients.provider_l2(),
-                l2_bridge_address,
-                eth_address,
-                &self.arg_config.rollup_priv_key,
-                &account,
-            )
-            .await;
+        StarknetLegacyEthBridge::setup_l2_bridge(
+            self.clients.provider_l2(),
+            l2_bridge_address,
+            eth_address,
+            &self.arg_config.rollup_priv_key,
+            l1_bridge.address(),
+            &self.account,
+        )
+        .instrument(sub_step!("eth_bridge.setup_l2_bridge", "l2"))
+        .await;
         log::info!("✴️ ETH Bridge initialization and setup on L2 completed");
 
-        eth_bridge
-            .setup_l1_bridge(
-                "10000000000000000000000000000000000000000",
-                "10000000000000000000000000000000000000000",
-                l2_bridge_address,
-                Address::from_str(&self.arg_config.l1_multisig_address.to_string()).unwrap(),
-                self.arg_config.dev,
-            )
-            .await;
+        l1_bridge
+            .link_l2_bridge(l2_bridge_address)
+            .instrument(sub_step!("eth_bridge.link_l1_bridge", "settlement"))
+            .await
+            .wrap_err("Failed to set up the ETH bridge on L1")?;
         log::info!("✴️ ETH Bridge setup on L1 completed");
 
-        EthBridgeSetupOutput {
-            l2_legacy_proxy_class_hash: legacy_proxy_class_hash,
-            l2_starkgate_proxy_class_hash: starkgate_proxy_class_hash,
-            l2_erc20_legacy_class_hash: erc20_legacy_class_hash,
-            l2_legacy_eth_bridge_class_hash: legacy_eth_bridge_class_hash,
+        let governance_nominations = l1_bridge.governance_nominations();
+
+        Ok(EthBridgeSetupOutput {
+            l2_legacy_proxy_class_hash: Some(legacy_proxy_class_hash),
+            l2_starkgate_proxy_class_hash: Some(starkgate_proxy_class_hash),
+            l2_erc20_legacy_class_hash: Some(erc20_legacy_class_hash),
+            l2_legacy_eth_bridge_class_hash: Some(legacy_eth_bridge_class_hash),
+            l2_eth_token_class_hash: None,
+            l2_eth_bridge_class_hash: None,
             l2_eth_proxy_address: eth_proxy_address,
             l2_eth_bridge_proxy_address: eth_bridge_proxy_address,
-            l1_bridge_address: eth_bridge.address(),
-            l1_bridge: eth_bridge,
+            l1_bridge_address,
+            governance_nominations,
+        })
+    }
+
+    /// Deploys the ETH token and bridge directly as the classes `upgrade_eth_token_to_cairo_1` and
+    /// `upgrade_eth_bridge_to_cairo_1` end on, no legacy class is declared. The ETH token of the
+    /// L1 bridge is registered in the L2 bridge through the same EIC as the upgrade.
+    async fn setup_cairo_1(&self) -> color_eyre::Result<EthBridgeSetupOutput> {
+        // The account registers the roles of the bridge, it has to be its constructor caller.
+        let bridge_deployer = Deployer::for_caller_roles(self.account.clone(), self.arg_config)?;
+        let eth_token_class_hash = traced(
+            sub_step!("eth_bridge.declare_eth_token", "l2"),
+            declare_contract(DeclarationInput::DeclarationInputs(
+                String::from(NEW_ETH_TOKEN_SIERRA_PATH),
+                String::from(NEW_ETH_TOKEN_CASM_PATH),
+                self.account.clone(),
+            )),
+        )
+        .await?;
+        log::info!("🎡 ETH token class hash declared.");
+        save_to_json("eth_token_class_hash", &JsonValueType::StringType(eth_token_class_hash.to_string()))?;
+
+        let eth_bridge_class_hash = traced(
+            sub_step!("eth_bridge.declare_eth_bridge", "l2"),
+            declare_contract(DeclarationInput::DeclarationInputs(
+                String::from(NEW_ETH_BRIDGE_SIERRA_PATH),
+                String::from(NEW_ETH_BRIDGE_CASM_PATH),
+                self.account.clone(),
+            )),
+        )
+        .await?;
+        log::info!("🎡 ETH bridge class hash declared.");
+        save_to_json("eth_bridge_class_hash", &JsonValueType::StringType(eth_bridge_class_hash.to_string()))?;
+
+        let eth_bridge_eic_class_hash = traced(
+            sub_step!("eth_bridge.declare_eth_bridge_eic", "l2"),
+            declare_contract(DeclarationInput::DeclarationInputs(
+                String::from(EIC_ETH_BRIDGE_SIERRA_PATH),
+                String::from(EIC_ETH_BRIDGE_CASM_PATH),
+                self.account.clone(),
+            )),
+        )
+        .await?;
+        log::info!("🎡 ETH bridge EIC class hash declared.");
+
+        let deployer = self.deployer()?;
+        // upgrade_delay : 0
+        let l2_bridge_address = traced(
+            sub_step!("eth_bridge.deploy_l2_bridge", "l2"),
+            bridge_deployer.deploy(
+                eth_bridge_class_hash,
+                Felt::from_hex_unchecked("0xabcdabcdabcd"),
+                true,
+                &[Felt::ZERO],
+            ),
+        )
+        .await
+        .wrap_err("Error deploying the ETH bridge")?;
+        log::info!("✴️ ETH Bridge deployed [ETH Bridge : {:?}]", l2_bridge_address);
+        save_to_json("ETH_l2_bridge_address", &JsonValueType::StringType(l2_bridge_address.to_string()))?;
+
+        // name, symbol, decimals, initial_supply (u256), recipient, permitted_minter,
+        // provisional_governance_admin, upgrade_delay. The governance admin is explicit, the token
+        // can be deployed through a UDC.
+        let eth_address = traced(
+            sub_step!("eth_bridge.deploy_eth_token", "l2"),
+            deployer.deploy(
+                eth_token_class_hash,
+                Felt::from_hex_unchecked("0x322c2610264639f6b2cee681ac53fa65c37e187ea24292d1b21d859c55e1a78"),
+                true,
+                &[
+                    Felt::from_bytes_be_slice("Ether".as_bytes()),
+                    Felt::from_bytes_be_slice("ETH".as_bytes()),
+                    Felt::from(18u64),
+                    Felt::ZERO,
+                    Felt::ZERO,
+                    self.account_address,
+                    l2_bridge_address,
+                    self.account_address,
+                    Felt::ZERO,
+                ],
+            ),
+        )
+        .await
+        .wrap_err("Error deploying the ETH token")?;
+        log::info!("✴️ ETH token deployed [ETH : {:?}]", eth_address);
+        save_to_json("l2_eth_address", &JsonValueType::StringType(eth_address.to_string()))?;
+
+        let l1_bridge = self
+            .settlement
+            .deploy_eth_bridge()
+            .instrument(sub_step!("eth_bridge.deploy_l1_bridge", "settlement"))
+            .await
+            .wrap_err("Failed to deploy the ETH bridge on the settlement layer")?;
+        let l1_bridge_address = l1_bridge.address();
+        save_to_json("ETH_l1_bridge_address", &JsonValueType::EthAddress(address_from_felt(l1_bridge_address)))?;
+
+        async {
+            for method in ["register_app_role_admin", "register_app_governor", "register_upgrade_governor"] {
+                invoke_contract(l2_bridge_address, method, vec![self.account_address], &self.account).await;
+            }
+            invoke_contract(l2_bridge_address, "set_l1_bridge", vec![l1_bridge.address()], &self.account).await;
+            // ImplementationData { impl_hash, eic_data: Some(EICData { eic_hash, eic_init_data:
+            // [l1_token ("ETH"), l2_token] }), final: false }
+            let implementation_data = vec![
+                eth_bridge_class_hash,
+                Felt::ZERO,
+                eth_bridge_eic_class_hash,
+                Felt::TWO,
+                Felt::from_hex_unchecked("0x455448"),
+                eth_address,
+                Felt::ZERO,
+            ];
+            invoke_contract(l2_bridge_address, "add_new_implementation", implementation_data.clone(), &self.account)
+                .await;
+            invoke_contract(l2_bridge_address, "replace_to", implementation_data, &self.account).await;
         }
+        .instrument(sub_step!("eth_bridge.setup_l2_bridge", "l2"))
+        .await;
+        log::info!("✴️ ETH Bridge setup on L2 completed");
+
+        l1_bridge
+            .link_l2_bridge(l2_bridge_address)
+            .instrument(sub_step!("eth_bridge.link_l1_bridge", "settlement"))
+            .await
+            .wrap_err("Failed to set up the ETH bridge on L1")?;
+        log::info!("✴️ ETH Bridge setup on L1 completed");
+
+        let governance_nominations = l1_bridge.governance_nominations();
+
+        Ok(EthBridgeSetupOutput {
+            l2_legacy_proxy_class_hash: None,
+            l2_starkgate_proxy_class_hash: None,
+            l2_erc20_legacy_class_hash: None,
+            l2_legacy_eth_bridge_class_hash: None,
+            l2_eth_token_class_hash: Some(eth_token_class_hash),
+            l2_eth_bridge_class_hash: Some(eth_bridge_class_hash),
+            l2_eth_proxy_address: eth_address,
+            l2_eth_bridge_proxy_address: l2_bridge_address,
+            l1_bridge_address,
+            governance_nominations,
+        })
+    }
+
+    /// Deploys from the account with the configured `l2_deployment_method`.
+    fn deployer(&self) -> color_eyre::Result<Deployer<'a>> {
+        Deployer::from_config(self.account.clone(), self.arg_config)
     }
 }
 
@@ -223,21 +377,14 @@ pub async fn deploy_eth_token_on_l2(
     rpc_provider_l2: &JsonRpcClient<HttpTransport>,
     eth_proxy_address: Felt,
     eth_erc20_class_hash: Felt,
-    account: &RpcAccount<'_>,
+    deployer: &Deployer<'_>,
     eth_legacy_bridge_address: Felt,
-) -> Felt {
-    let deploy_tx = account
-        .invoke_contract(
-            account.address(),
-            "deploy_contract",
-            vec![eth_erc20_class_hash, Felt::ZERO, Felt::ZERO, Felt::ZERO],
-            None,
-        )
-        .send()
+) -> color_eyre::Result<Felt> {
+    let account = deployer.account();
+    let contract_address = deployer
+        .deploy(eth_erc20_class_hash, Felt::ZERO, true, &[])
         .await
-        .expect("Error deploying the contract proxy.");
-    wait_for_transaction(rpc_provider_l2, deploy_tx.transaction_hash, "deploy_eth_token_on_l2 : deploy").await.unwrap();
-    let contract_address = get_contract_address_from_deploy_tx(account.provider(), &deploy_tx).await.unwrap();
+        .wrap_err("Error deploying the ETH token implementation")?;
 
     log::info!("Contract address (eth erc20) : {:?}", contract_address);
 
@@ -264,7 +411,7 @@ pub async fn deploy_eth_token_on_l2(
         "deploy_eth_token_on_l2 : add_implementation",
     )
     .await
-    .unwrap();
+    .map_err(|err| eyre!("{err:#}"))?;
 
     let upgrade_to_txn = invoke_contract(
         eth_proxy_address,
@@ -285,6 +432,6 @@ pub async fn deploy_eth_token_on_l2(
 
     wait_for_transaction(rpc_provider_l2, upgrade_to_txn.transaction_hash, "deploy_eth_token_on_l2 : upgrade_to")
         .await
-        .unwrap();
-    eth_proxy_address
+        .map_err(|err| eyre!("{err:#}"))?;
+    Ok(eth_proxy_address)
 }
@@ -0,0 +1,381 @@
+//! Genesis state of a new appchain : the classes, contracts and storage `setup-l2` creates with
+//! transactions (deployer account, UDC, ETH token and ETH bridge behind their proxies), written as
+//! a Madara genesis bundle so that the chain starts bridged at block 0.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use color_eyre::eyre::eyre;
+use ethers::abi::Address;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use starknet::core::types::contract::legacy::LegacyContractClass;
+use starknet::core::types::contract::{CompiledClass, SierraClass};
+use starknet::core::types::Felt;
+use starknet::signers::SigningKey;
+use starknet_core::utils::{get_contract_address, get_storage_var_address};
+
+use crate::contract_clients::governance::GovernanceNomination;
+use crate::export::set_yaml_values;
+use crate::utils::artifacts::load_artifact;
+use crate::utils::constants::{
+    BRAAVOS_AGGREGATOR_PATH, ERC20_CASM_PATH, ERC20_LEGACY_PATH, ERC20_SIERRA_PATH, LEGACY_BRIDGE_PATH,
+    OZ_ACCOUNT_CASM_PATH, OZ_ACCOUNT_SIERRA_PATH, PROXY_LEGACY_PATH, STARKGATE_PROXY_PATH, STARKNET_UDC_ADDRESS,
+    TOKEN_BRIDGE_CASM_PATH, TOKEN_BRIDGE_SIERRA_PATH, UDC_PATH,
+};
+use crate::ConfigFile;
+
+/// Salt of the ETH bridge proxy, as in the `eth-bridge` step.
+const ETH_BRIDGE_PROXY_SALT: Felt = Felt::from_hex_unchecked("0xabcdabcdabcd");
+/// `ISRC6_ID`, registered by the OpenZeppelin account constructor.
+const ISRC6_ID: Felt = Felt::from_hex_unchecked("0x2ceccef7f994940b3962a6c67e0ba4fcd37df7d131417c604f91e03caecc1cd");
+/// ETH balance of the deployer account when it is not in `genesis_balances` (1000 ETH).
+const DEFAULT_DEPLOYER_BALANCE: u128 = 1_000_000_000_000_000_000_000;
+
+/// Extra ETH balance (in wei) set in the genesis state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenesisBalance {
+    pub address: String,
+    pub amount: String,
+}
+
+/// `genesis.json` in the layout of `bin/configs/genesis-assets/genesis.json`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GenesisState {
+    pub sierra_class_hash_to_casm_class_hash: Vec<(Felt, Felt)>,
+    pub contract_classes: Vec<(Felt, GenesisClass)>,
+    pub contracts: Vec<(Felt, Felt)>,
+    /// Left empty : the deployer account and the balances are part of `contracts` and `storage`.
+    pub predeployed_accounts: Vec<Felt>,
+    pub storage: Vec<((Felt, Felt), Felt)>,
+    pub eth_fee_token_address: Felt,
+    pub strk_fee_token_address: Felt,
+    pub chain_id: String,
+}
+
+/// Class file of the bundle, relative to `genesis.json`. `version` is 0 for legacy classes.
+#[derive(Debug, Clone, Serialize)]
+pub struct GenesisClass {
+    pub path: String,
+    pub version: u8,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GenesisSetupOutput {
+    pub genesis_file: PathBuf,
+    pub chain_config_file: PathBuf,
+    pub l2_deployer_address: Felt,
+    pub udc_address: Felt,
+    pub l2_eth_token_proxy_address: Felt,
+    pub l2_eth_bridge_proxy_address: Felt,
+    pub l1_eth_bridge_address: Address,
+    /// `l2_multisig_address` when it is nominated governor of the ETH token and bridge proxies. It
+    /// becomes governor once it calls `accept_governance` on them.
+    pub l2_governance_nominee: Option<Felt>,
+    /// Proxy governance nomination of the L1 bridge when it is deployed by the `genesis` mode.
+    #[serde(skip)]
+    pub governance_nominations: Vec<GovernanceNomination>,
+}
+
+/// Addresses of the genesis contracts, they only depend on the config and the artifacts.
+#[derive(Debug, Clone, Copy)]
+pub struct GenesisAddresses {
+    pub l2_deployer: Felt,
+    pub udc: Felt,
+    pub eth_token_proxy: Felt,
+    pub eth_token: Felt,
+    pub eth_bridge_proxy: Felt,
+    pub eth_bridge: Felt,
+}
+
+pub struct GenesisSetup<'a> {
+    arg_config: &'a ConfigFile,
+    classes: GenesisClasses,
+}
+
+struct LegacyArtifact {
+    path: &'static str,
+    class_hash: Felt,
+    /// Storage vars of the program, `identifiers` of the compiled artifact.
+    identifiers: Value,
+}
+
+struct SierraArtifact {
+    path: &'static str,
+    class_hash: Felt,
+    compiled_class_hash: Felt,
+}
+
+struct GenesisClasses {
+    legacy_proxy: LegacyArtifact,
+    starkgate_proxy: LegacyArtifact,
+    erc20_legacy: LegacyArtifact,
+    legacy_eth_bridge: LegacyArtifact,
+    udc: LegacyArtifact,
+    braavos_aggregator: LegacyArtifact,
+    oz_account: SierraArtifact,
+    erc20: SierraArtifact,
+    token_bridge: SierraArtifact,
+}
+
+impl<'a> GenesisSetup<'a> {
+    pub fn new(arg_config: &'a ConfigFile) -> color_eyre::Result<Self> {
+        let classes = GenesisClasses {
+            legacy_proxy: LegacyArtifact::load(PROXY_LEGACY_PATH)?,
+            starkgate_proxy: LegacyArtifact::load(STARKGATE_PROXY_PATH)?,
+            erc20_legacy: LegacyArtifact::load(ERC20_LEGACY_PATH)?,
+            legacy_eth_bridge: LegacyArtifact::load(LEGACY_BRIDGE_PATH)?,
+            udc: LegacyArtifact::load(UDC_PATH)?,
+            braavos_aggregator: LegacyArtifact::load(BRAAVOS_AGGREGATOR_PATH)?,
+            oz_account: SierraArtifact::load(OZ_ACCOUNT_SIERRA_PATH, OZ_ACCOUNT_CASM_PATH)?,
+            erc20: SierraArtifact::load(ERC20_SIERRA_PATH, ERC20_CASM_PATH)?,
+            token_bridge: SierraArtifact::load(TOKEN_BRIDGE_SIERRA_PATH, TOKEN_BRIDGE_CASM_PATH)?,
+        };
+        Ok(Self { arg_config, classes })
+    }
+
+    pub fn addresses(&self) -> color_eyre::Result<GenesisAddresses> {
+        let classes = &self.classes;
+        Ok(GenesisAddresses {
+            // Same address as the account deployed from `rollup_priv_key` by `setup-l2`.
+            l2_deployer: get_contract_address(
+                Felt::ZERO,
+                classes.oz_account.class_hash,
+                &[self.deployer_public_key()?],
+                Felt::ZERO,
+            ),
+            udc: Felt::from_hex(STARKNET_UDC_ADDRESS)?,
+            eth_token_proxy: Felt::from_hex(&self.arg_config.fee_token_address)?,
+            eth_token: get_contract_address(Felt::ZERO, classes.erc20_legacy.class_hash, &[], Felt::ZERO),
+            eth_bridge_proxy: get_contract_address(
+                ETH_BRIDGE_PROXY_SALT,
+                classes.legacy_proxy.class_hash,
+                &[],
+                Felt::ZERO,
+            ),
+            eth_bridge: get_contract_address(Felt::ZERO, classes.legacy_eth_bridge.class_hash, &[], Felt::ZERO),
+        })
+    }
+
+    /// Genesis state with the ETH bridge linked to `l1_eth_bridge` on the settlement layer.
+    pub fn genesis_state(&self, l1_eth_bridge: Felt) -> color_eyre::Result<GenesisState> {
+        let classes = &self.classes;
+        let addresses = self.addresses()?;
+        let mut state = GenesisState {
+            eth_fee_token_address: addresses.eth_token_proxy,
+            strk_fee_token_address: Felt::from_hex(&self.arg_config.native_fee_token_address)?,
+            chain_id: self.arg_config.app_chain_id.clone(),
+            ..Default::default()
+        };
+
+        for class in [
+            &classes.legacy_proxy,
+            &classes.starkgate_proxy,
+            &classes.erc20_legacy,
+            &classes.legacy_eth_bridge,
+            &classes.udc,
+            &classes.braavos_aggregator,
+        ] {
+            state.contract_classes.push((class.class_hash, GenesisClass { path: file_name(class.path)?, version: 0 }));
+        }
+        for class in [&classes.oz_account, &classes.erc20, &classes.token_bridge] {
+            state.contract_classes.push((class.class_hash, GenesisClass { path: file_name(class.path)?, version: 1 }));
+            state.sierra_class_hash_to_casm_class_hash.push((class.class_hash, class.compiled_class_hash));
+        }
+
+        state.contracts = vec![
+            (addresses.l2_deployer, classes.oz_account.class_hash),
+            (addresses.udc, classes.udc.class_hash),
+            (addresses.eth_token_proxy, classes.legacy_proxy.class_hash),
+            (addresses.eth_token, classes.erc20_legacy.class_hash),
+            (addresses.eth_bridge_proxy, classes.legacy_proxy.class_hash),
+            (addresses.eth_bridge, classes.legacy_eth_bridge.class_hash),
+        ];
+
+        // Deployer account : constructor of the OpenZeppelin account.
+        state.write(addresses.l2_deployer, "Account_public_key", &[], self.deployer_public_key()?)?;
+        state.write(addresses.l2_deployer, "SRC5_supported_interfaces", &[ISRC6_ID], Felt::ONE)?;
+
+        // Proxies : `init_governance` by the deployer, `nominate_new_governor` of the L2 multisig,
+        // then `add_implementation` and `upgrade_to`.
+        let nominee = self.l2_governance_nominee(addresses.l2_deployer)?;
+        for (proxy, implementation) in
+            [(addresses.eth_token_proxy, addresses.eth_token), (addresses.eth_bridge_proxy, addresses.eth_bridge)]
+        {
+            let proxy_class = &classes.legacy_proxy;
+            state.write_legacy(proxy, proxy_class, "governance_initialized", &[], Felt::ONE)?;
+            state.write_legacy(proxy, proxy_class, "governors", &[addresses.l2_deployer], Felt::ONE)?;
+            if let Some(nominee) = nominee {
+                state.write_legacy(proxy, proxy_class, "candidates", &[nominee], Felt::ONE)?;
+            }
+            state.write_legacy(proxy, proxy_class, "impl_address", &[], implementation)?;
+        }
+
+        // ETH token : `initialize` from `upgrade_to` (name, symbol, decimals, minter) and the balances.
+        let erc20 = &classes.erc20_legacy;
+        let eth = addresses.eth_token_proxy;
+        state.write_legacy(eth, erc20, "ERC20_name", &[], Felt::from_bytes_be_slice(b"Ether"))?;
+        state.write_legacy(eth, erc20, "ERC20_symbol", &[], Felt::from_bytes_be_slice(b"ETH"))?;
+        state.write_legacy(eth, erc20, "ERC20_decimals", &[], Felt::from(18u8))?;
+        state.write_legacy(eth, erc20, "permitted_minter", &[], addresses.eth_bridge_proxy)?;
+        let mut total_supply = 0u128;
+        for (address, amount) in self.balances(addresses.l2_deployer)? {
+            total_supply = total_supply.checked_add(amount).ok_or_else(|| eyre!("Genesis ETH supply overflows"))?;
+            state.write_legacy_u256(eth, erc20, "ERC20_balances", &[address], amount)?;
+        }
+        state.write_legacy_u256(eth, erc20, "ERC20_total_supply", &[], total_supply)?;
+
+        // ETH bridge : `initialize` (governor), `set_l2_token` and `set_l1_bridge`.
+        let bridge = &classes.legacy_eth_bridge;
+        let eth_bridge = addresses.eth_bridge_proxy;
+        state.write_legacy(eth_bridge, bridge, "governor", &[], addresses.l2_deployer)?;
+        state.write_legacy(eth_bridge, bridge, "l2_token", &[], addresses.eth_token_proxy)?;
+        state.write_legacy(eth_bridge, bridge, "l1_bridge", &[], l1_eth_bridge)?;
+
+        Ok(state)
+    }
+
+    /// Writes `genesis.json`, the class files and `chain-config.yaml` to `dir`. The chain config is
+    /// `base_chain_config` with the chain id, fee tokens and core contract of the genesis set, the
+    /// one Madara is then started with (`--chain-config-path`).
+    pub fn write_bundle(
+        &self,
+        dir: &Path,
+        state: &GenesisState,
+        base_chain_config: &str,
+        core_contract_address: Option<&str>,
+    ) -> color_eyre::Result<(PathBuf, PathBuf)> {
+        fs::create_dir_all(dir)?;
+        let classes = &self.classes;
+        for path in [
+            classes.legacy_proxy.path,
+            classes.starkgate_proxy.path,
+            classes.erc20_legacy.path,
+            classes.legacy_eth_bridge.path,
+            classes.udc.path,
+            classes.braavos_aggregator.path,
+            classes.oz_account.path,
+            classes.erc20.path,
+            classes.token_bridge.path,
+        ] {
+            fs::write(dir.join(file_name(path)?), load_artifact(path)?)?;
+        }
+
+        let genesis_file = dir.join("genesis.json");
+        fs::write(&genesis_file, serde_json::to_string_pretty(state)?)?;
+
+        let mut chain_config = vec![
+            ("chain_name", state.chain_id.clone()),
+            ("chain_id", state.chain_id.clone()),
+            ("native_fee_token_address", state.strk_fee_token_address.to_hex_string()),
+            ("parent_fee_token_address", state.eth_fee_token_address.to_hex_string()),
+        ];
+        if let Some(core_contract_address) = core_contract_address {
+            chain_config.push(("eth_core_contract_address", core_contract_address.to_string()));
+        }
+        let chain_config_file = dir.join("chain-config.yaml");
+        fs::write(&chain_config_file, set_yaml_values(base_chain_config, &chain_config))?;
+
+        Ok((genesis_file, chain_config_file))
+    }
+
+    /// `l2_multisig_address`, unless it is unset (zero) or the deployer which already governs the
+    /// proxies.
+    pub fn l2_governance_nominee(&self, l2_deployer: Felt) -> color_eyre::Result<Option<Felt>> {
+        let multisig = Felt::from_hex(&self.arg_config.l2_multisig_address)
+            .map_err(|_| eyre!("Invalid l2_multisig_address : {}", self.arg_config.l2_multisig_address))?;
+        Ok((multisig != Felt::ZERO && multisig != l2_deployer).then_some(multisig))
+    }
+
+    fn deployer_public_key(&self) -> color_eyre::Result<Felt> {
+        Ok(SigningKey::from_secret_scalar(Felt::from_hex(&self.arg_config.rollup_priv_key)?).verifying_key().scalar())
+    }
+
+    /// `genesis_balances`, plus the deployer with [`DEFAULT_DEPLOYER_BALANCE`] unless listed.
+    fn balances(&self, l2_deployer: Felt) -> color_eyre::Result<Vec<(Felt, u128)>> {
+        let mut balances = vec![];
+        for balance in self.arg_config.genesis_balances.iter().flatten() {
+            let address = Felt::from_hex(&balance.address)?;
+            let amount = u128::from_str(&balance.amount)
+                .map_err(|_| eyre!("Invalid genesis balance for {} : {}", balance.address, balance.amount))?;
+            balances.push((address, amount));
+        }
+        if !balances.iter().any(|(address, _)| *address == l2_deployer) {
+            balances.push((l2_deployer, DEFAULT_DEPLOYER_BALANCE));
+        }
+        Ok(balances)
+    }
+}
+
+impl GenesisState {
+    fn write(&mut self, contract: Felt, var: &str, keys: &[Felt], value: Felt) -> color_eyre::Result<()> {
+        self.storage.push(((contract, get_storage_var_address(var, keys)?), value));
+        Ok(())
+    }
+
+    /// Storage var of a Cairo 0 contract, checked against the compiled program.
+    fn write_legacy(
+        &mut self,
+        contract: Felt,
+        class: &LegacyArtifact,
+        var: &str,
+        keys: &[Felt],
+        value: Felt,
+    ) -> color_eyre::Result<()> {
+        class.check_storage_var(var)?;
+        self.write(contract, var, keys, value)
+    }
+
+    /// `Uint256` storage var : the low word at the var address, the high word (always 0) after it.
+    fn write_legacy_u256(
+        &mut self,
+        contract: Felt,
+        class: &LegacyArtifact,
+        var: &str,
+        keys: &[Felt],
+        value: u128,
+    ) -> color_eyre::Result<()> {
+        class.check_storage_var(var)?;
+        let address = get_storage_var_address(var, keys)?;
+        self.storage.push(((contract, address), Felt::from(value)));
+        self.storage.push(((contract, address + Felt::ONE), Felt::ZERO));
+        Ok(())
+    }
+}
+
+impl LegacyArtifact {
+    fn load(path: &'static str) -> color_eyre::Result<Self> {
+        let artifact: Value = serde_json::from_slice(&load_artifact(path)?)?;
+        let class_hash = serde_json::from_value::<LegacyContractClass>(artifact.clone())?.class_hash()?;
+        let identifiers = artifact["program"]["identifiers"].clone();
+        Ok(Self { path, class_hash, identifiers })
+    }
+
+    fn check_storage_var(&self, var: &str) -> color_eyre::Result<()> {
+        let suffix = format!(".{var}.addr");
+        let found = self.identifiers.as_object().is_some_and(|ids| ids.keys().any(|id| id.ends_with(&suffix)));
+        match found {
+            true => Ok(()),
+            false => {
+                Err(eyre!("No `{}` storage var in {}, the genesis storage layout does not match it", var, self.path))
+            }
+        }
+    }
+}
+
+impl SierraArtifact {
+    fn load(sierra_path: &'static str, casm_path: &str) -> color_eyre::Result<Self> {
+        let class_hash = serde_json::from_slice::<SierraClass>(&load_artifact(sierra_path)?)?.class_hash()?;
+        let compiled_class_hash = serde_json::from_slice::<CompiledClass>(&load_artifact(casm_path)?)?.class_hash()?;
+        Ok(Self { path: sierra_path, class_hash, compiled_class_hash })
+    }
+}
+
+fn file_name(path: &str) -> color_eyre::Result<String> {
+    Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(str::to_string)
+        .ok_or_else(|| eyre!("Invalid artifact path {}", path))
+}
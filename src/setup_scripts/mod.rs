@@ -1,10 +1,17 @@
+pub mod accept_governance;
 pub mod account_setup;
 pub mod argent;
 pub mod braavos;
 pub mod core_contract;
+pub mod custom_contracts;
 pub mod erc20_bridge;
 pub mod eth_bridge;
+pub mod genesis;
+pub mod operators;
+pub mod piltover;
 pub mod udc;
+pub mod update_core_contract;
 pub mod upgrade_eth_token;
 pub mod upgrade_l1_bridge;
 pub mod upgrade_l2_bridge;
+pub mod upgrade_token_bridge;
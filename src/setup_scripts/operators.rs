@@ -0,0 +1,99 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use color_eyre::eyre::eyre;
+use ethers::abi::Address;
+use ethers::providers::Middleware;
+use serde::Serialize;
+use zaun_utils::LocalWalletSignerMiddleware;
+
+use crate::contract_clients::core_contract::{
+    get_operators_core_contract, is_governor_core_contract, is_operator_core_contract,
+    unregister_operator_core_contract, CoreContract,
+};
+use crate::{ConfigFile, CoreContractMode};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OperatorsOutput {
+    pub operators: Vec<Address>,
+}
+
+/// Registers `register_operator_addresses` and unregisters `unregister_operator_addresses` on the
+/// core contract, then returns the resulting operator set.
+///
+/// The caller (`client`, `eth_priv_key`) must be a governor of the core contract to change the
+/// operator set.
+pub async fn manage_operators(
+    core_contract: &dyn CoreContract,
+    client: Arc<LocalWalletSignerMiddleware>,
+    config_file: &ConfigFile,
+) -> color_eyre::Result<OperatorsOutput> {
+    let address = core_contract.address();
+    let to_register = parse_addresses(&config_file.register_operator_addresses)?;
+    let to_unregister = parse_addresses(&config_file.unregister_operator_addresses)?;
+
+    if !to_register.is_empty() || !to_unregister.is_empty() {
+        let caller = client.address();
+        if !is_governor_core_contract(address, client.clone(), caller).await? {
+            return Err(eyre!("{:?} is not a governor of the core contract, can't change the operators", caller));
+        }
+    }
+
+    for operator in to_register {
+        if is_operator_core_contract(address, client.clone(), operator).await? {
+            log::info!("ℹ️ {:?} is already an operator. Skipping....", operator);
+            continue;
+        }
+        core_contract.register_operator_core_contract(operator).await;
+        log::info!("🧑‍✈️ Operator registered : {:?}", operator);
+    }
+
+    for operator in to_unregister {
+        if !is_operator_core_contract(address, client.clone(), operator).await? {
+            log::info!("ℹ️ {:?} is not an operator. Skipping....", operator);
+            continue;
+        }
+        unregister_operator_core_contract(address, client.clone(), operator).await?;
+        log::info!("🧑‍✈️ Operator unregistered : {:?}", operator);
+    }
+
+    if config_file.core_contract_deployment_block.is_none() {
+        log::warn!("⚠️ core_contract_deployment_block not in config, operator events are queried from genesis.");
+    }
+    let operators = get_operators_core_contract(
+        address,
+        client.clone(),
+        config_file.core_contract_deployment_block.unwrap_or_default(),
+    )
+    .await?;
+    log::info!("🧑‍✈️ Core contract operators : {:?}", operators);
+    warn_if_deployer_is_operator(address, client, config_file).await?;
+
+    Ok(OperatorsOutput { operators })
+}
+
+/// The deployer EOA gets registered as operator by `initialize_with` and is often used as
+/// `operator_address` on devnets. On production core contracts the operator should be the
+/// dedicated sequencer / orchestrator key.
+pub async fn warn_if_deployer_is_operator(
+    core_contract: Address,
+    client: Arc<LocalWalletSignerMiddleware>,
+    config_file: &ConfigFile,
+) -> color_eyre::Result<()> {
+    if !matches!(config_file.core_contract_mode, CoreContractMode::Production) {
+        return Ok(());
+    }
+    let deployer = client.address();
+    if is_operator_core_contract(core_contract, client, deployer).await? {
+        log::warn!(
+            "⚠️ Deployer {:?} is still an operator of the production core contract. Unregister it with \
+             `unregister_operator_addresses` once the operator is set up.",
+            deployer
+        );
+    }
+    Ok(())
+}
+
+fn parse_addresses(addresses: &Option<Vec<String>>) -> color_eyre::Result<Vec<Address>> {
+    addresses.iter().flatten().map(|address| Ok(Address::from_str(address)?)).collect()
+}
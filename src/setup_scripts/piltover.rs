@@ -0,0 +1,142 @@
+use color_eyre::eyre::bail;
+use serde::Serialize;
+use starknet::accounts::{Account, ConnectedAccount};
+use starknet::core::types::{BlockId, BlockTag, Felt};
+use starknet_providers::Provider;
+use tracing::Instrument;
+
+use crate::contract_clients::deployer::{Deployer, DeploymentMethod};
+use crate::contract_clients::piltover::PiltoverCoreContract;
+use crate::contract_clients::utils::{declare_contract, get_bridge_init_configs, DeclarationInput, RpcAccount};
+use crate::utils::constants::{PILTOVER_APPCHAIN_CASM_PATH, PILTOVER_APPCHAIN_SIERRA_PATH, STARKNET_UDC_ADDRESS};
+use crate::utils::logging::{sub_step, traced};
+use crate::utils::{save_to_json, JsonValueType};
+use crate::ConfigFile;
+
+/// Appchain setup on a Starknet settlement chain (`settlement_layer: starknet`) : the Piltover
+/// core contract only, this path deploys no token bridge on either side. The contract is owned by
+/// the deployer account until it is set up, then by `settlement_multisig_address`.
+pub struct PiltoverSetup<'a> {
+    account: RpcAccount<'a>,
+    arg_config: &'a ConfigFile,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PiltoverSetupOutput {
+    pub core_contract_class_hash: Felt,
+    pub core_contract_address: Felt,
+    pub core_contract_owner: Felt,
+}
+
+impl<'a> PiltoverSetup<'a> {
+    /// `account` is the `settlement_account_address` account on the settlement chain.
+    pub fn new(account: RpcAccount<'a>, arg_config: &'a ConfigFile) -> Self {
+        Self { account, arg_config }
+    }
+
+    pub async fn setup(&self) -> color_eyre::Result<PiltoverSetupOutput> {
+        let core_contract_class_hash = traced(
+            sub_step!("piltover.declare_core_contract", "settlement"),
+            declare_contract(DeclarationInput::DeclarationInputs(
+                String::from(PILTOVER_APPCHAIN_SIERRA_PATH),
+                String::from(PILTOVER_APPCHAIN_CASM_PATH),
+                self.account.clone(),
+            )),
+        )
+        .await?;
+        log::info!("🛰️ Piltover core contract class hash declared : {:?}", core_contract_class_hash);
+
+        let constructor_calldata = PiltoverCoreContract::constructor_calldata(
+            self.account.address(),
+            felt_or_zero(&self.arg_config.initial_state_root)?,
+            Felt::from(self.arg_config.initial_block_number.unwrap_or_default()),
+            felt_or_zero(&self.arg_config.initial_block_hash)?,
+        );
+        let core_contract_address = traced(
+            sub_step!("piltover.deploy_core_contract", "settlement"),
+            self.deploy("Piltover core contract", core_contract_class_hash, constructor_calldata),
+        )
+        .await?;
+        log::info!("🛰️ Piltover core contract address : {:?}", core_contract_address);
+        save_to_json(
+            "settlement_core_contract_address",
+            &JsonValueType::StringType(core_contract_address.to_string()),
+        )?;
+
+        let core_contract = PiltoverCoreContract::new(self.account.clone(), core_contract_address);
+        let (program_hash, config_hash) = get_bridge_init_configs(self.arg_config);
+        core_contract
+            .set_program_info(program_hash, config_hash)
+            .instrument(sub_step!("piltover.set_program_info", "settlement"))
+            .await;
+        log::info!("🛰️ Piltover core contract : set_program_info ✅");
+        match &self.arg_config.facts_registry_address {
+            Some(facts_registry) => {
+                core_contract
+                    .set_facts_registry(Felt::from_hex(facts_registry)?)
+                    .instrument(sub_step!("piltover.set_facts_registry", "settlement"))
+                    .await;
+                log::info!("🛰️ Piltover core contract : set_facts_registry ✅");
+            }
+            None => log::warn!("⚠️ facts_registry_address is not set, the Piltover facts registry is left unset"),
+        }
+        let operator = Felt::from_hex(&self.arg_config.operator_address)?;
+        if !core_contract.is_operator(operator).await? {
+            core_contract
+                .register_operator(operator)
+                .instrument(sub_step!("piltover.register_operator", "settlement"))
+                .await;
+            log::info!("🛰️ Piltover core contract : register_operator ✅");
+        }
+
+        let core_contract_owner = match &self.arg_config.settlement_multisig_address {
+            Some(multisig) => {
+                let multisig = Felt::from_hex(multisig)?;
+                core_contract
+                    .transfer_ownership(multisig)
+                    .instrument(sub_step!("piltover.transfer_ownership", "settlement"))
+                    .await;
+                let owner = core_contract.owner().await?;
+                if owner != multisig {
+                    bail!(
+                        "The Piltover core contract is owned by {} after its transfer to {}",
+                        owner.to_hex_string(),
+                        multisig.to_hex_string()
+                    );
+                }
+                log::info!("🛰️ Piltover core contract : ownership transferred to {:?} ✅", multisig);
+                multisig
+            }
+            None => {
+                log::warn!(
+                    "⚠️ settlement_multisig_address is not set, the Piltover core contract stays owned by {:?}",
+                    self.account.address()
+                );
+                self.account.address()
+            }
+        };
+
+        Ok(PiltoverSetupOutput { core_contract_class_hash, core_contract_address, core_contract_owner })
+    }
+
+    /// `deployContract` on the settlement chain UDC, skipped if the contract is already deployed.
+    async fn deploy(&self, name: &str, class_hash: Felt, constructor_calldata: Vec<Felt>) -> color_eyre::Result<Felt> {
+        let udc_address =
+            Felt::from_hex(self.arg_config.settlement_udc_address.as_deref().unwrap_or(STARKNET_UDC_ADDRESS))?;
+        let deployer = Deployer::new(self.account.clone(), DeploymentMethod::LegacyUdc, udc_address);
+        let expected_address = deployer.deployed_address(class_hash, Felt::ZERO, true, &constructor_calldata);
+        if self.account.provider().get_class_hash_at(BlockId::Tag(BlockTag::Pending), expected_address).await.is_ok() {
+            log::info!("ℹ️ {} is already deployed at {:?}. Skipping....", name, expected_address);
+            return Ok(expected_address);
+        }
+
+        deployer.deploy(class_hash, Felt::ZERO, true, &constructor_calldata).await
+    }
+}
+
+fn felt_or_zero(value: &Option<String>) -> color_eyre::Result<Felt> {
+    match value {
+        Some(value) => Ok(Felt::from_hex(value)?),
+        None => Ok(Felt::ZERO),
+    }
+}
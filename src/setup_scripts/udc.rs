@@ -1,21 +1,17 @@
-use std::time::Duration;
-
+use color_eyre::eyre::WrapErr;
 use serde::Serialize;
-use starknet::accounts::ConnectedAccount;
-use starknet::core::types::Felt;
-use tokio::time::sleep;
+use starknet::core::types::{BlockId, BlockTag, Felt};
+use starknet::providers::Provider;
 
 use crate::contract_clients::config::Clients;
-use crate::contract_clients::utils::{declare_contract, DeclarationInput, RpcAccount};
-use crate::helpers::account_actions::{get_contract_address_from_deploy_tx, AccountActions};
+use crate::contract_clients::deployer::Deployer;
+use crate::contract_clients::utils::{declare_contract, DeclarationInput};
 use crate::utils::constants::UDC_PATH;
-use crate::utils::{save_to_json, wait_for_transaction, JsonValueType};
-use crate::ConfigFile;
+use crate::utils::logging::{sub_step, traced};
+use crate::utils::{save_to_json, JsonValueType};
 
 pub struct UdcSetup<'a> {
-    account: RpcAccount<'a>,
-    account_address: Felt,
-    arg_config: &'a ConfigFile,
+    deployer: Deployer<'a>,
     clients: &'a Clients,
 }
 
@@ -26,48 +22,43 @@ pub struct UdcSetupOutput {
 }
 
 impl<'a> UdcSetup<'a> {
-    pub fn new(
-        account: RpcAccount<'a>,
-        account_address: Felt,
-        arg_config: &'a ConfigFile,
-        clients: &'a Clients,
-    ) -> Self {
-        Self { account, account_address, arg_config, clients }
+    pub fn new(deployer: Deployer<'a>, clients: &'a Clients) -> Self {
+        Self { deployer, clients }
     }
 
-    pub async fn setup(&self) -> UdcSetupOutput {
-        let udc_class_hash = declare_contract(DeclarationInput::LegacyDeclarationInputs(
-            String::from(UDC_PATH),
-            self.arg_config.rollup_declare_v0_seq_url.clone(),
-            self.clients.provider_l2(),
-        ))
-        .await;
+    pub async fn setup(&self) -> color_eyre::Result<UdcSetupOutput> {
+        let udc_class_hash = traced(
+            sub_step!("udc.declare_udc", "l2"),
+            declare_contract(DeclarationInput::LegacyDeclarationInputs(String::from(UDC_PATH), self.clients)),
+        )
+        .await?;
         log::info!("📣 UDC Class Hash Declared.");
-        save_to_json("udc_class_hash", &JsonValueType::StringType(udc_class_hash.to_string())).unwrap();
-        sleep(Duration::from_secs(10)).await;
+        save_to_json("udc_class_hash", &JsonValueType::StringType(udc_class_hash.to_string()))?;
 
-        let txn = self
-            .account
-            .invoke_contract(
-                self.account_address,
-                "deploy_contract",
-                Vec::from([udc_class_hash, Felt::ZERO, Felt::ONE, Felt::ZERO]),
-                None,
-            )
-            .send()
-            .await
-            .unwrap();
-        wait_for_transaction(
-            self.account.provider(),
-            txn.transaction_hash,
-            "deploy_non_bridge_contracts : deploy_contract : udc",
-        )
-        .await
-        .unwrap();
-        let udc_address = get_contract_address_from_deploy_tx(self.account.provider(), &txn).await.unwrap();
-        save_to_json("udc_address", &JsonValueType::StringType(udc_address.to_string())).unwrap();
+        let udc_address =
+            traced(sub_step!("udc.deploy_udc", "l2"), self.deployer.deploy(udc_class_hash, Felt::ZERO, false, &[]))
+                .await
+                .wrap_err("Error deploying the UDC")?;
+        save_to_json("udc_address", &JsonValueType::StringType(udc_address.to_string()))?;
         log::info!("📣 udc_address : {:?}", udc_address);
 
-        UdcSetupOutput { udc_class_hash, udc_address }
+        Ok(UdcSetupOutput { udc_class_hash, udc_address })
+    }
+
+    /// The UDC is a legacy class, in `cairo1_only` mode the one already deployed at `udc_address`
+    /// (genesis state) is used. So is it when the L2 deployments go through that UDC.
+    pub async fn predeployed(&self, udc_address: Felt) -> color_eyre::Result<UdcSetupOutput> {
+        let udc_class_hash = self
+            .clients
+            .provider_l2()
+            .get_class_hash_at(BlockId::Tag(BlockTag::Pending), udc_address)
+            .await
+            .wrap_err_with(|| {
+                format!("No UDC deployed at {:?}, `cairo1_only` and the UDC deployment methods need it", udc_address)
+            })?;
+        log::info!("📣 Using the deployed UDC [UDC : {:?}]", udc_address);
+        save_to_json("udc_address", &JsonValueType::StringType(udc_address.to_string()))?;
+
+        Ok(UdcSetupOutput { udc_class_hash, udc_address })
     }
 }
@@ -0,0 +1,116 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use ethers::abi::Address;
+use serde::Serialize;
+use tracing::Instrument;
+use zaun_utils::LocalWalletSignerMiddleware;
+
+use crate::contract_clients::core_contract::{
+    get_core_contract_state, set_config_hash_core_contract, set_program_hash_core_contract, CoreContractL1State,
+};
+use crate::contract_clients::proxy_upgrade::{
+    ImplementationSource, L1ProxyUpgrader, PreparedProxyUpgrade, ProxyUpgrade,
+};
+use crate::contract_clients::utils::get_bridge_init_configs;
+use crate::utils::logging::sub_step;
+use crate::utils::pad_bytes;
+use crate::ConfigFile;
+
+#[derive(Serialize, Clone)]
+pub struct CoreContractUpdateOutput {
+    pub previous_state: CoreContractL1State,
+    pub current_state: CoreContractL1State,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub implementation_upgrade: Option<PreparedProxyUpgrade>,
+}
+
+/// Brings an already deployed core contract in line with the config file. `client` must be a
+/// governor of the core contract.
+///
+/// # Steps
+/// 1. Reads the current state (block number, state root, block hash, program and config hash).
+/// 2. Sets `sn_os_program_hash` as program hash if it changed.
+/// 3. Recomputes the config hash (see [`get_bridge_init_configs`]) and sets it if it changed.
+/// 4. If `core_contract_new_implementation_address` is set, upgrades the proxy to it. The proxy is
+///    already initialised so the init data is empty, the state stays in the proxy storage.
+/// 5. If `core_contract_verifier_eic_address` is set, the upgrade goes through this EIC with
+///    `verifier_address` as init data to rotate the verifier. Without a new implementation the
+///    proxy is upgraded to its current implementation.
+pub async fn update_core_contract(
+    core_contract: Address,
+    client: Arc<LocalWalletSignerMiddleware>,
+    config_file: &ConfigFile,
+) -> color_eyre::Result<CoreContractUpdateOutput> {
+    let previous_state = get_core_contract_state(core_contract, client.clone()).await?;
+    log::info!("📦 Core contract state : {:?}", previous_state);
+
+    let (program_hash, config_hash) = get_bridge_init_configs(config_file);
+
+    let upgrader = L1ProxyUpgrader::new(client.clone());
+    let verifier_eic = match config_file.core_contract_verifier_eic_address {
+        Some(ref eic_address) => Some(Address::from_str(eic_address)?),
+        None => None,
+    };
+    let implementation = match config_file.core_contract_new_implementation_address {
+        Some(ref implementation_address) => Some(Address::from_str(implementation_address)?),
+        None if verifier_eic.is_some() => Some(upgrader.implementation(core_contract).await?),
+        None => None,
+    };
+
+    let implementation_upgrade = match implementation {
+        Some(implementation) => {
+            let upgrade = upgrader
+                .upgrade(&core_contract_upgrade(
+                    core_contract,
+                    implementation,
+                    verifier_eic,
+                    Address::from_str(&config_file.verifier_address)?,
+                ))
+                .instrument(sub_step!("update_core_contract.upgrade_implementation", "settlement"))
+                .await?;
+            log::info!("📦 Core contract implementation upgrade : {:?}", upgrade.implementation_address);
+            Some(upgrade)
+        }
+        None => None,
+    };
+
+    if previous_state.program_hash != program_hash {
+        set_program_hash_core_contract(core_contract, client.clone(), program_hash)
+            .instrument(sub_step!("update_core_contract.set_program_hash", "settlement"))
+            .await?;
+        log::info!("📦 Program hash updated : {:?} -> {:?}", previous_state.program_hash, program_hash);
+    }
+    if previous_state.config_hash != config_hash {
+        set_config_hash_core_contract(core_contract, client.clone(), config_hash)
+            .instrument(sub_step!("update_core_contract.set_config_hash", "settlement"))
+            .await?;
+        log::info!("📦 Config hash updated : {:?} -> {:?}", previous_state.config_hash, config_hash);
+    }
+
+    let current_state = get_core_contract_state(core_contract, client).await?;
+    log::info!("✅ Core contract update complete.");
+    Ok(CoreContractUpdateOutput { previous_state, current_state, implementation_upgrade })
+}
+
+/// Upgrade of an initialised core contract proxy. `initialize` reverts with `UNEXPECTED_INIT_DATA`
+/// when an initialised contract gets init data, so it is only sent to the verifier EIC.
+pub fn core_contract_upgrade(
+    proxy_address: Address,
+    implementation: Address,
+    verifier_eic: Option<Address>,
+    verifier_address: Address,
+) -> ProxyUpgrade {
+    let (eic, init_data) = match verifier_eic {
+        Some(eic) => (Some(ImplementationSource::Deployed(eic)), pad_bytes(verifier_address)),
+        None => (None, vec![]),
+    };
+    ProxyUpgrade {
+        proxy_address,
+        implementation: ImplementationSource::Deployed(implementation),
+        eic,
+        init_data,
+        finalize: false,
+        post_upgrade: vec![],
+    }
+}
@@ -1,13 +1,14 @@
 use std::time::Duration;
 
-use starknet::accounts::{Account, ConnectedAccount};
+use starknet::accounts::Account;
 use starknet_providers::jsonrpc::HttpTransport;
 use starknet_providers::JsonRpcClient;
 use starknet_types_core::felt::Felt;
 use tokio::time::sleep;
 
-use crate::contract_clients::utils::{declare_contract, DeclarationInput, RpcAccount};
-use crate::helpers::account_actions::{get_contract_address_from_deploy_tx, AccountActions};
+use crate::contract_clients::deployer::Deployer;
+use crate::contract_clients::utils::{declare_contract, DeclarationInput};
+use crate::helpers::account_actions::AccountActions;
 use crate::utils::constants::{
     EIC_ETH_TOKEN_CASM_PATH, EIC_ETH_TOKEN_SIERRA_PATH, NEW_ETH_TOKEN_CASM_PATH, NEW_ETH_TOKEN_SIERRA_PATH,
 };
@@ -23,20 +24,21 @@ use crate::utils::wait_for_transaction;
 ///    - Adding and replacing the new implementation class hash
 ///
 /// # Arguments
-/// * `account` - The RPC account used to perform the transactions
+/// * `deployer` - Deploys the EIC and the implementation, its account performs the transactions
 /// * `rpc_provider_l2` - JSON-RPC client for L2 network communication
 /// * `l2_eth_token_address` - The address of the existing ETH token contract on L2
 pub async fn upgrade_eth_token_to_cairo_1(
-    account: &RpcAccount<'_>,
+    deployer: &Deployer<'_>,
     rpc_provider_l2: &JsonRpcClient<HttpTransport>,
     l2_eth_token_address: Felt,
-) {
+) -> color_eyre::Result<()> {
+    let account = deployer.account();
     let eth_eic_class_hash = declare_contract(DeclarationInput::DeclarationInputs(
         String::from(EIC_ETH_TOKEN_SIERRA_PATH),
         String::from(EIC_ETH_TOKEN_CASM_PATH),
         account.clone(),
     ))
-    .await;
+    .await?;
     sleep(Duration::from_secs(5)).await;
     log::debug!("ETH EIC declared ✅. Class hash : {:?}", eth_eic_class_hash);
 
@@ -45,37 +47,23 @@ pub async fn upgrade_eth_token_to_cairo_1(
         String::from(NEW_ETH_TOKEN_CASM_PATH),
         account.clone(),
     ))
-    .await;
+    .await?;
     sleep(Duration::from_secs(5)).await;
     log::debug!("New ETH token declared ✅. Class hash : {:?}", new_eth_token_class_hash);
 
-    let eth_eic_deploy_tx = account
-        .invoke_contract(
-            account.address(),
-            "deploy_contract",
-            vec![eth_eic_class_hash, Felt::ZERO, Felt::ZERO, Felt::ZERO],
-            None,
-        )
-        .send()
+    let eth_eic_contract_address = deployer
+        .deploy(eth_eic_class_hash, Felt::ZERO, true, &[])
         .await
         .expect("Error deploying the contract : eth_eic_deploy_tx");
-    wait_for_transaction(rpc_provider_l2, eth_eic_deploy_tx.transaction_hash, "deploy_eth_token_on_l2 : deploy")
-        .await
-        .unwrap();
-    let eth_eic_contract_address =
-        get_contract_address_from_deploy_tx(account.provider(), &eth_eic_deploy_tx).await.unwrap();
     log::debug!("✅ eth eic contract address : {:?}", eth_eic_contract_address);
     sleep(Duration::from_secs(5)).await;
 
-    let new_token_eth_deploy_tx = account
-        .invoke_contract(
-            account.address(),
-            "deploy_contract",
-            vec![
-                new_eth_token_class_hash,
-                Felt::ZERO,
-                Felt::ZERO,
-                Felt::from(9u64),
+    let new_eth_token_contract_address = deployer
+        .deploy(
+            new_eth_token_class_hash,
+            Felt::ZERO,
+            true,
+            &[
                 Felt::from_hex("eee").unwrap(),
                 Felt::from_hex("eeee").unwrap(),
                 Felt::from(6u64),
@@ -86,16 +74,9 @@ pub async fn upgrade_eth_token_to_cairo_1(
                 Felt::from_hex("137e2eb39d5b20f7257425dbea0a97ab6a53941e7ccdc9168ba3b0f8b39d1ce").unwrap(),
                 Felt::from(0),
             ],
-            None,
         )
-        .send()
         .await
         .expect("Error deploying the contract : new_token_eth_deploy_tx");
-    wait_for_transaction(rpc_provider_l2, new_token_eth_deploy_tx.transaction_hash, "deploy_eth_token_on_l2 : deploy")
-        .await
-        .unwrap();
-    let new_eth_token_contract_address =
-        get_contract_address_from_deploy_tx(account.provider(), &new_token_eth_deploy_tx).await.unwrap();
     log::debug!("✅ new eth contract address : {:?}", new_eth_token_contract_address);
     sleep(Duration::from_secs(5)).await;
 
@@ -206,4 +187,5 @@ pub async fn upgrade_eth_token_to_cairo_1(
     );
 
     log::info!("Eth token upgraded successfully ✅");
+    Ok(())
 }
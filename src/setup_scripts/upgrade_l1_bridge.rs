@@ -1,105 +1,105 @@
 use std::str::FromStr;
-use std::sync::Arc;
 
-use ethereum_instance::Error;
-use ethers::prelude::{abigen, Bytes, SignerMiddleware};
-use ethers::providers::{Http, Provider};
-use ethers::signers::{LocalWallet, Signer};
+use color_eyre::eyre::eyre;
+use ethers::contract::ContractCall;
+use ethers::prelude::abigen;
 use ethers::types::{Address, U256};
-use tokio::time::{sleep, Duration};
+use tracing::Instrument;
+use zaun_utils::LocalWalletSignerMiddleware;
 
+use crate::contract_clients::config::Clients;
+use crate::contract_clients::proxy_upgrade::{
+    ImplementationSource, L1ProxyUpgrader, PostUpgradeCall, PreparedProxyUpgrade, ProxyUpgrade,
+};
+use crate::utils::constants::{EIC_ETH_BRIDGE_L1_PATH, NEW_ETH_BRIDGE_L1_PATH};
+use crate::utils::logging::sub_step;
 use crate::ConfigFile;
 
+// Only the functions used after the upgrade, the full ABI lives in the (runtime loaded) artifact.
 abigen!(
-    EthereumL1BridgeProxy,
+    EthereumNewBridge,
     r"[
-        function addImplementation(address newImplementation, bytes data, bool finalize)
-        function upgradeTo(address newImplementation, bytes data, bool finalize)
+        function registerAppRoleAdmin(address account)
+        function registerGovernanceAdmin(address account)
+        function registerAppGovernor(address account)
+        function setMaxTotalBalance(address token, uint256 maxTotalBalance)
     ]",
 );
 
-abigen!(EthereumNewBridge, "artifacts/upgrade-contracts/eth_bridge_upgraded.json");
-abigen!(EthereumNewBridgeEIC, "artifacts/upgrade-contracts/eic_eth_bridge.json");
-
 /// Upgrades the L1 Ethereum bridge implementation with a new version, including deployment of new
 /// contracts and configuration of administrative roles.
 ///
 /// # Arguments
 /// * `ethereum_bridge_address` - The address of the existing Ethereum bridge contract on L1
-/// * `config_file` - Configuration file containing network and wallet settings
+/// * `config_file` - Configuration file containing the L1 deployer address
+/// * `clients` - Clients whose L1 signer sends the upgrade
 ///
 /// # Returns
-/// * `Result<()>` - Result indicating success or failure of the upgrade process
+/// * `Result<PreparedProxyUpgrade>` - The upgrade applied to the bridge proxy. If the proxy has an
+///   activation delay the upgrade is only added and has to be finished later.
 ///
 /// # Steps
-/// 1. Initializes provider and wallet connections using config settings
-/// 2. Deploys new bridge implementation and EIC (External Implementation Contract)
-/// 3. Sets up proxy connection to existing bridge
-/// 4. Performs upgrade sequence:
+/// 1. Deploys new bridge implementation and EIC (External Implementation Contract)
+/// 2. Performs upgrade sequence through the [`L1ProxyUpgrader`]:
 ///    - Adds new implementation to proxy
-///    - Upgrades to new implementation
-///    - Registers administrative roles (app role admin, governance admin, app governor)
-///    - Sets maximum total balance for ETH
-pub async fn upgrade_l1_bridge(ethereum_bridge_address: Address, config_file: &ConfigFile) -> color_eyre::Result<()> {
-    let config_file = Arc::from(config_file);
-
-    let provider = Provider::<Http>::try_from(config_file.eth_rpc.clone()).map_err(|_| Error::UrlParser)?;
-    let wallet: LocalWallet = config_file.eth_priv_key.parse().expect("Failed to parse private key");
-    let signer_client =
-        Arc::new(SignerMiddleware::new(provider.clone(), wallet.with_chain_id(config_file.eth_chain_id)));
-
-    let new_eth_bridge_client = EthereumNewBridge::deploy(signer_client.clone(), ())?.send().await?;
-    log::debug!("New ETH bridge deployed : {:?}", new_eth_bridge_client.address());
-    let eic_eth_bridge_client = EthereumNewBridgeEIC::deploy(signer_client.clone(), ())?.send().await?;
-    log::debug!("New ETH bridge EIC deployed : {:?}", eic_eth_bridge_client.address());
+///    - Upgrades to new implementation (when there is no activation delay)
+/// 3. Registers administrative roles (app role admin, governance admin, app governor) and sets the
+///    maximum total balance for ETH on the proxy, once it points to the new implementation. These
+///    calls are kept in the pending upgrade otherwise.
+pub async fn upgrade_l1_bridge(
+    ethereum_bridge_address: Address,
+    config_file: &ConfigFile,
+    clients: &Clients,
+) -> color_eyre::Result<PreparedProxyUpgrade> {
+    let signer_client = clients.eth_client().signer().clone();
 
-    let eth_bridge_proxy_client = EthereumL1BridgeProxy::new(ethereum_bridge_address, signer_client.clone());
+    let l1_deployer_address = Address::from_str(&config_file.l1_deployer_address)?;
+    // Only used to encode the calls, they are sent to the proxy.
+    let new_eth_bridge = EthereumNewBridge::new(ethereum_bridge_address, signer_client.clone());
+    let post_upgrade = vec![
+        post_upgrade_call("registerAppRoleAdmin", new_eth_bridge.register_app_role_admin(l1_deployer_address))?,
+        post_upgrade_call("registerGovernanceAdmin", new_eth_bridge.register_governance_admin(l1_deployer_address))?,
+        post_upgrade_call("registerAppGovernor", new_eth_bridge.register_app_governor(l1_deployer_address))?,
+        post_upgrade_call(
+            "setMaxTotalBalance",
+            new_eth_bridge.set_max_total_balance(
+                Address::from_str("0x0000000000000000000000000000000000455448").unwrap(),
+                U256::from_dec_str("10000000000000000000000000").unwrap(),
+            ),
+        )?,
+    ];
 
-    // Building calldata :
-    let client_clone = eic_eth_bridge_client.clone();
-    let address = client_clone.address();
-    let eic_eth_bridge_bytes = address.as_ref();
-    // let eic_eth_bridge_bytes = eic_eth_bridge_client.address().as_ref();
-    let mut padded_eic_eth_bridge_address = Vec::with_capacity(32);
-    padded_eic_eth_bridge_address.extend(vec![0u8; 32 - eic_eth_bridge_bytes.len()]);
-    padded_eic_eth_bridge_address.extend_from_slice(eic_eth_bridge_bytes);
-    let empty_bytes = Bytes::from_str("0000000000000000000000000000000000000000000000000000000000000000")?;
-    let call_data = [padded_eic_eth_bridge_address, empty_bytes.to_vec(), empty_bytes.to_vec()].concat();
-    let call_data = Bytes::from(call_data);
-
-    eth_bridge_proxy_client
-        .add_implementation(new_eth_bridge_client.address(), call_data.clone(), false)
-        .send()
-        .await?;
-    log::debug!("New ETH bridge add_implementation ✅");
-    sleep(Duration::from_secs(20)).await;
-    eth_bridge_proxy_client.upgrade_to(new_eth_bridge_client.address(), call_data, false).send().await?;
-    log::debug!("New ETH bridge upgrade_to ✅");
-    sleep(Duration::from_secs(20)).await;
-    new_eth_bridge_client
-        .register_app_role_admin(Address::from_str(&config_file.l1_deployer_address.clone())?)
-        .send()
+    let upgrader = L1ProxyUpgrader::new(signer_client);
+    // Calldata : [eic_address, 0 (eth token), 0 (max total balance)]
+    let prepared_upgrade = upgrader
+        .upgrade(&ProxyUpgrade {
+            proxy_address: ethereum_bridge_address,
+            implementation: ImplementationSource::Artifact(String::from(NEW_ETH_BRIDGE_L1_PATH)),
+            eic: Some(ImplementationSource::Artifact(String::from(EIC_ETH_BRIDGE_L1_PATH))),
+            init_data: [[0u8; 32], [0u8; 32]].concat(),
+            finalize: false,
+            post_upgrade,
+        })
+        .instrument(sub_step!("upgrade_l1_bridge.upgrade_eth_bridge", "settlement"))
         .await?;
-    sleep(Duration::from_secs(20)).await;
-    new_eth_bridge_client
-        .register_governance_admin(Address::from_str(&config_file.l1_deployer_address.clone())?)
-        .send()
-        .await?;
-    sleep(Duration::from_secs(20)).await;
-    new_eth_bridge_client
-        .register_app_governor(Address::from_str(&config_file.l1_deployer_address.clone())?)
-        .send()
-        .await?;
-    sleep(Duration::from_secs(20)).await;
-    new_eth_bridge_client
-        .set_max_total_balance(
-            Address::from_str("0x0000000000000000000000000000000000455448").unwrap(),
-            U256::from_dec_str("10000000000000000000000000").unwrap(),
-        )
-        .send()
-        .await?;
-    log::debug!("New ETH bridge set_max_total_balance ✅");
+    log::debug!("New ETH bridge deployed : {:?}", prepared_upgrade.implementation_address);
+    log::debug!("New ETH bridge EIC deployed : {:?}", prepared_upgrade.eic_address);
+
+    // The roles and the ETH limit are set by the upgrader after `upgradeTo`.
+    match prepared_upgrade.upgraded {
+        true => log::info!("Eth bridge L1 upgraded successfully ✅"),
+        false => log::info!(
+            "⏳ Eth bridge L1 upgrade pending, run apply-pending-upgrades after timestamp {}",
+            prepared_upgrade.activation_timestamp
+        ),
+    }
+    Ok(prepared_upgrade)
+}
 
-    log::info!("Eth bridge L1 upgraded successfully ✅");
-    Ok(())
+fn post_upgrade_call<M>(
+    description: &str,
+    call: ContractCall<LocalWalletSignerMiddleware, M>,
+) -> color_eyre::Result<PostUpgradeCall> {
+    let calldata = call.calldata().ok_or_else(|| eyre!("Unable to encode {description}"))?;
+    Ok(PostUpgradeCall { description: description.to_string(), calldata })
 }
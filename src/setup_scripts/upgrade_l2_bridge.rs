@@ -1,13 +1,14 @@
 use std::time::Duration;
 
-use starknet::accounts::{Account, ConnectedAccount};
+use starknet::accounts::Account;
 use starknet_providers::jsonrpc::HttpTransport;
 use starknet_providers::JsonRpcClient;
 use starknet_types_core::felt::Felt;
 use tokio::time::sleep;
 
-use crate::contract_clients::utils::{declare_contract, DeclarationInput, RpcAccount};
-use crate::helpers::account_actions::{get_contract_address_from_deploy_tx, AccountActions};
+use crate::contract_clients::deployer::Deployer;
+use crate::contract_clients::utils::{declare_contract, DeclarationInput};
+use crate::helpers::account_actions::AccountActions;
 use crate::utils::constants::{
     EIC_ETH_BRIDGE_CASM_PATH, EIC_ETH_BRIDGE_SIERRA_PATH, NEW_ETH_BRIDGE_CASM_PATH, NEW_ETH_BRIDGE_SIERRA_PATH,
 };
@@ -17,7 +18,7 @@ use crate::utils::wait_for_transaction;
 /// declarations, deployments, and configuration steps.
 ///
 /// # Arguments
-/// * `account` - The RPC account used to perform the transactions
+/// * `deployer` - Deploys the EIC and the implementation, its account performs the transactions
 /// * `rpc_provider_l2` - JSON-RPC client for L2 network communication
 /// * `l2_eth_bridge_address` - The address of the existing ETH bridge contract on L2
 /// * `l2_eth_token_address` - The address of the ETH token contract on L2
@@ -31,17 +32,18 @@ use crate::utils::wait_for_transaction;
 ///    - Registers governance and upgrade administrators
 ///    - Adds and replaces implementation class hash
 pub async fn upgrade_eth_bridge_to_cairo_1(
-    account: &RpcAccount<'_>,
+    deployer: &Deployer<'_>,
     rpc_provider_l2: &JsonRpcClient<HttpTransport>,
     l2_eth_bridge_address: Felt,
     l2_eth_token_address: Felt,
-) {
+) -> color_eyre::Result<()> {
+    let account = deployer.account();
     let eth_bridge_eic_class_hash = declare_contract(DeclarationInput::DeclarationInputs(
         String::from(EIC_ETH_BRIDGE_SIERRA_PATH),
         String::from(EIC_ETH_BRIDGE_CASM_PATH),
         account.clone(),
     ))
-    .await;
+    .await?;
     sleep(Duration::from_secs(5)).await;
     log::debug!("ETH Bridge EIC declared ✅, Class hash : {:?}", eth_bridge_eic_class_hash);
 
@@ -50,39 +52,21 @@ pub async fn upgrade_eth_bridge_to_cairo_1(
         String::from(NEW_ETH_BRIDGE_CASM_PATH),
         account.clone(),
     ))
-    .await;
+    .await?;
     sleep(Duration::from_secs(5)).await;
     log::debug!("New ETH Bridge declared ✅, Class hash : {:?}", new_eth_bridge_class_hash);
 
-    let bridge_eic_deploy_tx = account
-        .invoke_contract(
-            account.address(),
-            "deploy_contract",
-            vec![eth_bridge_eic_class_hash, Felt::ONE, Felt::ZERO, Felt::ZERO],
-            None,
-        )
-        .send()
+    let eth_bridge_eic_contract_address = deployer
+        .deploy(eth_bridge_eic_class_hash, Felt::ONE, true, &[])
         .await
         .expect("Error deploying the contract : eth_eic_deploy_tx");
-    wait_for_transaction(rpc_provider_l2, bridge_eic_deploy_tx.transaction_hash, " : deploy").await.unwrap();
-    let eth_bridge_eic_contract_address =
-        get_contract_address_from_deploy_tx(account.provider(), &bridge_eic_deploy_tx).await.unwrap();
     log::debug!("✅ eth bridge eic contract address : {:?}", eth_bridge_eic_contract_address);
     sleep(Duration::from_secs(5)).await;
 
-    let new_bridge_eth_deploy_tx = account
-        .invoke_contract(
-            account.address(),
-            "deploy_contract",
-            vec![new_eth_bridge_class_hash, Felt::ONE, Felt::ZERO, Felt::from(1u64), Felt::from(0)],
-            None,
-        )
-        .send()
+    let new_eth_bridge_contract_address = deployer
+        .deploy(new_eth_bridge_class_hash, Felt::ONE, true, &[Felt::from(0)])
         .await
         .expect("Error deploying the contract : new_token_eth_deploy_tx");
-    wait_for_transaction(rpc_provider_l2, new_bridge_eth_deploy_tx.transaction_hash, " : deploy").await.unwrap();
-    let new_eth_bridge_contract_address =
-        get_contract_address_from_deploy_tx(account.provider(), &new_bridge_eth_deploy_tx).await.unwrap();
     log::debug!("✅ new eth bridge contract address : {:?}", new_eth_bridge_contract_address);
     sleep(Duration::from_secs(5)).await;
 
@@ -207,4 +191,5 @@ pub async fn upgrade_eth_bridge_to_cairo_1(
     );
 
     log::info!("Eth bridge L2 upgraded successfully ✅");
+    Ok(())
 }
@@ -0,0 +1,199 @@
+use std::str::FromStr;
+
+use color_eyre::eyre::eyre;
+use ethers::prelude::abigen;
+use ethers::types::Address;
+use serde::Serialize;
+use starknet::accounts::{Account, ConnectedAccount};
+use starknet_core::types::{BlockId, BlockTag, FunctionCall};
+use starknet_core::utils::get_selector_from_name;
+use starknet_providers::Provider;
+use starknet_types_core::felt::Felt;
+use tracing::Instrument;
+
+use crate::contract_clients::config::Clients;
+use crate::contract_clients::proxy_upgrade::{
+    ImplementationSource, L1ProxyUpgrader, PreparedProxyUpgrade, ProxyUpgrade,
+};
+use crate::contract_clients::utils::{declare_contract, DeclarationInput, RpcAccount};
+use crate::utils::constants::{
+    NEW_MANAGER_L1_PATH, NEW_REGISTRY_L1_PATH, NEW_TOKEN_BRIDGE_L1_PATH, TOKEN_BRIDGE_CASM_PATH,
+    TOKEN_BRIDGE_SIERRA_PATH,
+};
+use crate::utils::invoke_contract;
+use crate::utils::logging::{sub_step, traced};
+use crate::ConfigFile;
+
+abigen!(
+    StarkgateRegistry,
+    r"[
+        function getBridge(address token) external view returns (address)
+    ]",
+);
+
+#[derive(Serialize, Clone)]
+pub struct TokenBridgeUpgradeOutput {
+    pub l1_manager_upgrade: PreparedProxyUpgrade,
+    pub l1_registry_upgrade: PreparedProxyUpgrade,
+    pub l1_token_bridge_upgrade: PreparedProxyUpgrade,
+    pub l2_token_bridge_class_hash: Felt,
+}
+
+/// Upgrades the StarkGate ERC20 token bridge stack deployed by the `erc20-bridge` mode to the
+/// artifacts currently present in `artifacts/`.
+///
+/// # Steps
+/// 1. Upgrades the L1 manager, registry and token bridge proxies (no EIC, empty init data as the
+///    contracts are already initialised).
+/// 2. Declares the new Cairo 1 token bridge class and moves the L2 bridge to it using
+///    `add_new_implementation` / `replace_to`.
+/// 3. Checks that every enrolled token still resolves to the L1 bridge in the registry and to an L2
+///    token on the L2 bridge.
+pub async fn upgrade_token_bridge(
+    account: &RpcAccount<'_>,
+    config_file: &ConfigFile,
+    clients: &Clients,
+) -> color_eyre::Result<TokenBridgeUpgradeOutput> {
+    let l1_manager_address = l1_address(&config_file.l1_manager_address, "l1_manager_address")?;
+    let l1_registry_address = l1_address(&config_file.l1_registry_address, "l1_registry_address")?;
+    let l1_token_bridge_address = l1_address(&config_file.l1_token_bridge_address, "l1_token_bridge_address")?;
+    let l2_token_bridge_address = Felt::from_hex(
+        config_file.l2_token_bridge_address.as_ref().ok_or_else(|| eyre!("l2_token_bridge_address not in config."))?,
+    )?;
+
+    let upgrader = L1ProxyUpgrader::new(clients.eth_client().signer().clone());
+    let l1_manager_upgrade = upgrader
+        .upgrade(&proxy_upgrade(l1_manager_address, NEW_MANAGER_L1_PATH))
+        .instrument(sub_step!("upgrade_token_bridge.upgrade_l1_manager", "settlement"))
+        .await?;
+    log::info!("❇️ Starkgate manager upgrade : {:?}", l1_manager_upgrade.implementation_address);
+    let l1_registry_upgrade = upgrader
+        .upgrade(&proxy_upgrade(l1_registry_address, NEW_REGISTRY_L1_PATH))
+        .instrument(sub_step!("upgrade_token_bridge.upgrade_l1_registry", "settlement"))
+        .await?;
+    log::info!("❇️ Starkgate registry upgrade : {:?}", l1_registry_upgrade.implementation_address);
+    let l1_token_bridge_upgrade = upgrader
+        .upgrade(&proxy_upgrade(l1_token_bridge_address, NEW_TOKEN_BRIDGE_L1_PATH))
+        .instrument(sub_step!("upgrade_token_bridge.upgrade_l1_token_bridge", "settlement"))
+        .await?;
+    log::info!("❇️ Starknet token bridge upgrade : {:?}", l1_token_bridge_upgrade.implementation_address);
+
+    let l2_token_bridge_class_hash = traced(
+        sub_step!("upgrade_token_bridge.upgrade_l2_token_bridge", "l2"),
+        upgrade_l2_token_bridge(account, l2_token_bridge_address),
+    )
+    .await?;
+
+    validate_enrolled_tokens(
+        config_file,
+        clients,
+        l1_registry_address,
+        l1_token_bridge_address,
+        l2_token_bridge_address,
+    )
+    .instrument(sub_step!("upgrade_token_bridge.validate_enrolled_tokens", "l2"))
+    .await?;
+
+    log::info!("Token bridge upgraded successfully ✅");
+    Ok(TokenBridgeUpgradeOutput {
+        l1_manager_upgrade,
+        l1_registry_upgrade,
+        l1_token_bridge_upgrade,
+        l2_token_bridge_class_hash,
+    })
+}
+
+/// Moves the L2 token bridge to the latest token bridge class. Returns the class hash in use after
+/// the upgrade.
+async fn upgrade_l2_token_bridge(account: &RpcAccount<'_>, l2_token_bridge_address: Felt) -> color_eyre::Result<Felt> {
+    let new_class_hash = declare_contract(DeclarationInput::DeclarationInputs(
+        String::from(TOKEN_BRIDGE_SIERRA_PATH),
+        String::from(TOKEN_BRIDGE_CASM_PATH),
+        account.clone(),
+    ))
+    .await?;
+    log::debug!("New token bridge declared ✅, Class hash : {:?}", new_class_hash);
+
+    let current_class_hash =
+        account.provider().get_class_hash_at(BlockId::Tag(BlockTag::Pending), l2_token_bridge_address).await?;
+    if current_class_hash == new_class_hash {
+        log::info!("ℹ️ L2 token bridge is already using class {:?}. Skipping....", new_class_hash);
+        return Ok(new_class_hash);
+    }
+
+    // The deployer is the governance admin of the bridge, it needs to be upgrade governor as well
+    // to call `add_new_implementation` and `replace_to`.
+    invoke_contract(l2_token_bridge_address, "register_upgrade_governor", vec![account.address()], account).await;
+    log::debug!("upgrade_l2_token_bridge : register_upgrade_governor ✅");
+
+    // implementation data : [class hash, eic data (None), final]
+    let implementation_data = vec![new_class_hash, Felt::ONE, Felt::ZERO];
+    invoke_contract(l2_token_bridge_address, "add_new_implementation", implementation_data.clone(), account).await;
+    log::debug!("upgrade_l2_token_bridge : add_new_implementation ✅");
+    invoke_contract(l2_token_bridge_address, "replace_to", implementation_data, account).await;
+    log::debug!("upgrade_l2_token_bridge : replace_to ✅");
+
+    let upgraded_class_hash =
+        account.provider().get_class_hash_at(BlockId::Tag(BlockTag::Pending), l2_token_bridge_address).await?;
+    if upgraded_class_hash != new_class_hash {
+        return Err(eyre!(
+            "L2 token bridge class hash is {:?} after replace_to, expected {:?}",
+            upgraded_class_hash,
+            new_class_hash
+        ));
+    }
+    log::info!("Token bridge L2 upgraded successfully ✅");
+    Ok(new_class_hash)
+}
+
+async fn validate_enrolled_tokens(
+    config_file: &ConfigFile,
+    clients: &Clients,
+    l1_registry_address: Address,
+    l1_token_bridge_address: Address,
+    l2_token_bridge_address: Felt,
+) -> color_eyre::Result<()> {
+    let registry = StarkgateRegistry::new(l1_registry_address, clients.eth_client().signer().clone());
+    for token in config_file.l1_enrolled_token_addresses.clone().unwrap_or_default() {
+        let token = Address::from_str(&token)?;
+
+        let bridge = registry.get_bridge(token).call().await?;
+        if bridge != l1_token_bridge_address {
+            return Err(eyre!("Token {:?} resolves to bridge {:?} in the registry after upgrade", token, bridge));
+        }
+
+        let l2_token = clients
+            .provider_l2()
+            .call(
+                FunctionCall {
+                    contract_address: l2_token_bridge_address,
+                    entry_point_selector: get_selector_from_name("get_l2_token")?,
+                    calldata: vec![Felt::from_bytes_be_slice(token.as_bytes())],
+                },
+                BlockId::Tag(BlockTag::Pending),
+            )
+            .await?;
+        if l2_token.first().copied().unwrap_or(Felt::ZERO) == Felt::ZERO {
+            return Err(eyre!("Token {:?} has no L2 token on the L2 bridge after upgrade", token));
+        }
+        log::debug!("validate_enrolled_tokens : {:?} -> {:?} ✅", token, l2_token[0]);
+    }
+    Ok(())
+}
+
+fn proxy_upgrade(proxy_address: Address, artifact_path: &str) -> ProxyUpgrade {
+    ProxyUpgrade {
+        proxy_address,
+        implementation: ImplementationSource::Artifact(String::from(artifact_path)),
+        eic: None,
+        // Contracts are already initialised so the init data must be empty.
+        init_data: vec![],
+        finalize: false,
+        post_upgrade: vec![],
+    }
+}
+
+fn l1_address(address: &Option<String>, name: &str) -> color_eyre::Result<Address> {
+    let address = address.as_ref().ok_or_else(|| eyre!("{name} not in config."))?;
+    Ok(Address::from_str(address)?)
+}
@@ -0,0 +1,278 @@
+use std::fmt;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use starknet::core::types::Felt;
+
+use crate::contract_clients::deployer::DeploymentMethod;
+use crate::settlement::address_from_felt;
+use crate::utils::constants::{
+    ARGENT_ACCOUNT_CASM_PATH, ARGENT_ACCOUNT_SIERRA_PATH, BRAAVOS_ACCOUNT_CASM_PATH, BRAAVOS_ACCOUNT_SIERRA_PATH,
+    BRAAVOS_AGGREGATOR_PATH, BRAAVOS_BASE_ACCOUNT_CASM_PATH, BRAAVOS_BASE_ACCOUNT_SIERRA_PATH,
+    EIC_ETH_BRIDGE_CASM_PATH, EIC_ETH_BRIDGE_L1_PATH, EIC_ETH_BRIDGE_SIERRA_PATH, EIC_ETH_TOKEN_CASM_PATH,
+    EIC_ETH_TOKEN_SIERRA_PATH, ERC20_CASM_PATH, ERC20_LEGACY_PATH, ERC20_SIERRA_PATH, LEGACY_BRIDGE_PATH,
+    NEW_ETH_BRIDGE_CASM_PATH, NEW_ETH_BRIDGE_L1_PATH, NEW_ETH_BRIDGE_SIERRA_PATH, NEW_ETH_TOKEN_CASM_PATH,
+    NEW_ETH_TOKEN_SIERRA_PATH, PILTOVER_APPCHAIN_CASM_PATH, PILTOVER_APPCHAIN_SIERRA_PATH, PROXY_LEGACY_PATH,
+    STARKGATE_PROXY_PATH, TOKEN_BRIDGE_CASM_PATH, TOKEN_BRIDGE_SIERRA_PATH, UDC_PATH,
+};
+use crate::{ConfigFile, SettlementLayerKind};
+
+/// Named step of the bootstrap. The variants are in execution order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Step {
+    CoreContract,
+    EthBridge,
+    Erc20Bridge,
+    Udc,
+    Argent,
+    Braavos,
+    CustomContracts,
+    UpgradeEthBridge,
+}
+
+impl Step {
+    pub const ALL: [Step; 8] = [
+        Step::CoreContract,
+        Step::EthBridge,
+        Step::Erc20Bridge,
+        Step::Udc,
+        Step::Argent,
+        Step::Braavos,
+        Step::CustomContracts,
+        Step::UpgradeEthBridge,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Step::CoreContract => "core-contract",
+            Step::EthBridge => "eth-bridge",
+            Step::Erc20Bridge => "erc20-bridge",
+            Step::Udc => "udc",
+            Step::Argent => "argent",
+            Step::Braavos => "braavos",
+            Step::CustomContracts => "custom-contracts",
+            Step::UpgradeEthBridge => "upgrade-eth-bridge",
+        }
+    }
+
+    /// Chains the step sends transactions to, reported in the logs.
+    pub fn chains(&self) -> &'static str {
+        match self {
+            Step::CoreContract => "settlement",
+            Step::EthBridge | Step::Erc20Bridge | Step::UpgradeEthBridge => "settlement,l2",
+            Step::Udc | Step::Argent | Step::Braavos | Step::CustomContracts => "l2",
+        }
+    }
+
+    /// Artifacts declared or deployed by the step, checked by the preflight.
+    pub fn artifacts(&self, config: &ConfigFile) -> &'static [&'static str] {
+        let starknet_settlement = config.settlement_layer() == SettlementLayerKind::Starknet;
+        let cairo1_only = config.cairo1_only();
+        match self {
+            Step::CoreContract if starknet_settlement => &[PILTOVER_APPCHAIN_SIERRA_PATH, PILTOVER_APPCHAIN_CASM_PATH],
+            // Deployed from the zaun bytecode.
+            Step::CoreContract => &[],
+            Step::EthBridge | Step::Erc20Bridge if starknet_settlement => &[],
+            Step::EthBridge if cairo1_only => &[
+                NEW_ETH_TOKEN_SIERRA_PATH,
+                NEW_ETH_TOKEN_CASM_PATH,
+                NEW_ETH_BRIDGE_SIERRA_PATH,
+                NEW_ETH_BRIDGE_CASM_PATH,
+                EIC_ETH_BRIDGE_SIERRA_PATH,
+                EIC_ETH_BRIDGE_CASM_PATH,
+            ],
+            Step::EthBridge => &[PROXY_LEGACY_PATH, STARKGATE_PROXY_PATH, ERC20_LEGACY_PATH, LEGACY_BRIDGE_PATH],
+            Step::Erc20Bridge => {
+                &[ERC20_SIERRA_PATH, ERC20_CASM_PATH, TOKEN_BRIDGE_SIERRA_PATH, TOKEN_BRIDGE_CASM_PATH]
+            }
+            // Already deployed in `cairo1_only` mode and when deploying through it.
+            Step::Udc if cairo1_only || config.l2_deployment_method() != DeploymentMethod::Account => &[],
+            Step::Udc => &[UDC_PATH],
+            Step::Argent => &[ARGENT_ACCOUNT_SIERRA_PATH, ARGENT_ACCOUNT_CASM_PATH],
+            Step::Braavos if cairo1_only => &[
+                BRAAVOS_ACCOUNT_SIERRA_PATH,
+                BRAAVOS_ACCOUNT_CASM_PATH,
+                BRAAVOS_BASE_ACCOUNT_SIERRA_PATH,
+                BRAAVOS_BASE_ACCOUNT_CASM_PATH,
+            ],
+            Step::Braavos => &[
+                BRAAVOS_ACCOUNT_SIERRA_PATH,
+                BRAAVOS_ACCOUNT_CASM_PATH,
+                BRAAVOS_BASE_ACCOUNT_SIERRA_PATH,
+                BRAAVOS_BASE_ACCOUNT_CASM_PATH,
+                BRAAVOS_AGGREGATOR_PATH,
+            ],
+            // Read from the paths of the config.
+            Step::CustomContracts => &[],
+            Step::UpgradeEthBridge if starknet_settlement => &[],
+            Step::UpgradeEthBridge if cairo1_only => &[NEW_ETH_BRIDGE_L1_PATH, EIC_ETH_BRIDGE_L1_PATH],
+            Step::UpgradeEthBridge => &[
+                EIC_ETH_TOKEN_SIERRA_PATH,
+                EIC_ETH_TOKEN_CASM_PATH,
+                NEW_ETH_TOKEN_SIERRA_PATH,
+                NEW_ETH_TOKEN_CASM_PATH,
+                EIC_ETH_BRIDGE_SIERRA_PATH,
+                EIC_ETH_BRIDGE_CASM_PATH,
+                NEW_ETH_BRIDGE_SIERRA_PATH,
+                NEW_ETH_BRIDGE_CASM_PATH,
+                NEW_ETH_BRIDGE_L1_PATH,
+                EIC_ETH_BRIDGE_L1_PATH,
+            ],
+        }
+    }
+
+    /// Steps whose outputs are needed by this step.
+    pub fn dependencies(&self) -> &'static [Step] {
+        match self {
+            Step::CoreContract | Step::Udc | Step::Argent => &[],
+            Step::EthBridge | Step::Erc20Bridge => &[Step::CoreContract],
+            Step::Braavos | Step::CustomContracts => &[Step::Udc],
+            Step::UpgradeEthBridge => &[Step::EthBridge],
+        }
+    }
+
+    /// Whether the outputs of this step needed by the other steps are in the config (from the
+    /// config file, a previous manifest or an earlier step of the run).
+    pub fn is_resolved(&self, config: &ConfigFile) -> bool {
+        let starknet_settlement = config.settlement_layer() == SettlementLayerKind::Starknet;
+        match self {
+            Step::CoreContract if starknet_settlement => config.core_contract_address.is_some(),
+            Step::CoreContract => {
+                config.core_contract_address.is_some() && config.core_contract_implementation_address.is_some()
+            }
+            // Not run with a Starknet settlement layer.
+            Step::EthBridge if starknet_settlement => true,
+            Step::EthBridge => {
+                config.l1_eth_bridge_address.is_some()
+                    && config.l2_eth_token_proxy_address.is_some()
+                    && config.l2_eth_bridge_proxy_address.is_some()
+            }
+            Step::Udc => config.udc_address.is_some(),
+            // No step depends on them.
+            Step::Erc20Bridge | Step::Argent | Step::Braavos | Step::CustomContracts | Step::UpgradeEthBridge => true,
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Steps to run, from the `--only`, `--skip` and `--from` flags.
+#[derive(Debug, Clone, Default)]
+pub struct StepSelection {
+    pub only: Vec<Step>,
+    pub skip: Vec<Step>,
+    pub from: Option<Step>,
+}
+
+impl StepSelection {
+    /// Selected steps, in execution order.
+    pub fn plan(&self) -> Vec<Step> {
+        let start = self.from.map(|from| Step::ALL.iter().position(|step| *step == from).unwrap()).unwrap_or(0);
+        Step::ALL[start..]
+            .iter()
+            .filter(|step| self.only.is_empty() || self.only.contains(step))
+            .filter(|step| !self.skip.contains(step))
+            .copied()
+            .collect()
+    }
+}
+
+/// First dependency of the plan which is neither run before the step needing it nor resolved
+/// in the config, as `(step, dependency)`.
+pub fn missing_prerequisite(plan: &[Step], config: &ConfigFile) -> Option<(Step, Step)> {
+    plan.iter().enumerate().find_map(|(index, step)| {
+        step.dependencies()
+            .iter()
+            .find(|dependency| !plan[..index].contains(dependency) && !dependency.is_resolved(config))
+            .map(|dependency| (*step, *dependency))
+    })
+}
+
+/// The step graph, with the planned steps marked.
+pub fn render_graph(plan: &[Step]) -> String {
+    Step::ALL
+        .iter()
+        .map(|step| {
+            let marker = if plan.contains(step) { "*" } else { " " };
+            let dependencies = step.dependencies().iter().map(Step::name).collect::<Vec<_>>().join(", ");
+            if dependencies.is_empty() {
+                format!("{marker} {step}")
+            } else {
+                format!("{marker} {:<20} <- {dependencies}", step.name())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Fills the addresses missing from the config with the ones of a previous output (`--output-file`)
+/// so that the steps of a previous run don't have to run again. Values already in the config are
+/// kept.
+pub fn apply_manifest(config: &mut ConfigFile, manifest: &Value) {
+    // Config field and the paths it can be found at in the output.
+    let fields: [(&mut Option<String>, &[&[&str]]); 11] = [
+        (
+            &mut config.core_contract_address,
+            &[&["starknet_contract_address"], &["piltover_setup_outputs", "core_contract_address"]],
+        ),
+        (&mut config.core_contract_implementation_address, &[&["starknet_contract_implementation_address"]]),
+        (
+            &mut config.l1_eth_bridge_address,
+            &[&["eth_bridge_setup_outputs", "l1_bridge_address"], &["genesis_outputs", "l1_eth_bridge_address"]],
+        ),
+        (
+            &mut config.l2_eth_token_proxy_address,
+            &[
+                &["eth_bridge_setup_outputs", "l2_eth_proxy_address"],
+                &["genesis_outputs", "l2_eth_token_proxy_address"],
+            ],
+        ),
+        (
+            &mut config.l2_eth_bridge_proxy_address,
+            &[
+                &["eth_bridge_setup_outputs", "l2_eth_bridge_proxy_address"],
+                &["genesis_outputs", "l2_eth_bridge_proxy_address"],
+            ],
+        ),
+        (&mut config.l1_token_bridge_address, &[&["erc20_bridge_setup_outputs", "l1_token_bridge_proxy"]]),
+        (&mut config.l1_manager_address, &[&["erc20_bridge_setup_outputs", "l1_manager_address"]]),
+        (&mut config.l1_registry_address, &[&["erc20_bridge_setup_outputs", "l1_registry_address"]]),
+        (&mut config.l2_token_bridge_address, &[&["erc20_bridge_setup_outputs", "l2_token_bridge"]]),
+        (&mut config.udc_address, &[&["udc_setup_outputs", "udc_address"], &["genesis_outputs", "udc_address"]]),
+        (&mut config.l2_deployer_address, &[&["l2_deployer_address"]]),
+    ];
+    for (field, paths) in fields {
+        if field.is_some() {
+            continue;
+        }
+        *field = paths.iter().find_map(|path| match path.iter().try_fold(manifest, |value, key| value.get(key)) {
+            Some(Value::String(value)) => Some(value.clone()),
+            _ => None,
+        });
+    }
+    if config.core_contract_deployment_block.is_none() {
+        config.core_contract_deployment_block =
+            manifest.get("starknet_contract_deployment_block").and_then(Value::as_u64);
+    }
+
+    // The bridge outputs hold the settlement addresses as felts, the config expects Ethereum
+    // addresses.
+    if config.settlement_layer() == SettlementLayerKind::Ethereum {
+        for field in [
+            &mut config.l1_eth_bridge_address,
+            &mut config.l1_token_bridge_address,
+            &mut config.l1_manager_address,
+            &mut config.l1_registry_address,
+        ] {
+            if let Some(address) = field.as_deref().and_then(|address| Felt::from_hex(address).ok()) {
+                *field = Some(format!("{:?}", address_from_felt(address)));
+            }
+        }
+    }
+}
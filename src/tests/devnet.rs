@@ -0,0 +1,21 @@
+//! Devnet state handling, without starting anvil or Madara.
+
+use std::fs;
+
+use crate::devnet::reset_madara_db;
+
+#[test]
+fn previous_madara_db_is_removed_unless_kept() {
+    let dir = tempfile::tempdir().unwrap();
+    let madara_db = dir.path().join("madara-db");
+    fs::create_dir_all(madara_db.join("db")).unwrap();
+
+    reset_madara_db(&madara_db, true).unwrap();
+    assert!(madara_db.join("db").exists());
+
+    reset_madara_db(&madara_db, false).unwrap();
+    assert!(!madara_db.exists());
+
+    // Nothing to remove on the first run.
+    reset_madara_db(&madara_db, false).unwrap();
+}
@@ -0,0 +1,58 @@
+use serde_json::json;
+
+use crate::export::{export_values, render_template, set_yaml_values};
+use crate::steps::apply_manifest;
+use crate::ConfigFile;
+
+#[test]
+fn export_values_include_the_manifest_addresses() {
+    let mut config = ConfigFile { core_contract_address: None, ..ConfigFile::default() };
+    apply_manifest(
+        &mut config,
+        &json!({
+            "starknet_contract_address": "0xc0",
+            "eth_bridge_setup_outputs": { "l2_eth_bridge_proxy_address": "0xb1" }
+        }),
+    );
+
+    let values = export_values(&config);
+
+    assert_eq!(values["l1_core_contract_address"], "0xc0");
+    assert_eq!(values["l2_eth_bridge_address"], "0xb1");
+    assert!(!values.contains_key("l1_token_bridge_address"));
+}
+
+#[test]
+fn manifest_bridge_addresses_are_ethereum_addresses() {
+    let mut config = ConfigFile { l1_eth_bridge_address: None, ..ConfigFile::default() };
+    apply_manifest(
+        &mut config,
+        &json!({ "eth_bridge_setup_outputs": { "l1_bridge_address": "0x5fbdb2315678afecb367f032d93f642f64180aa" } }),
+    );
+
+    assert_eq!(config.l1_eth_bridge_address.as_deref(), Some("0x05fbdb2315678afecb367f032d93f642f64180aa"));
+}
+
+#[test]
+fn templates_fail_on_unknown_placeholders() {
+    let values = export_values(&ConfigFile::default());
+
+    assert_eq!(
+        render_template("CORE={{ l1_core_contract_address }}\n", &values).unwrap(),
+        format!("CORE={}\n", values["l1_core_contract_address"])
+    );
+    assert!(render_template("{{unknown}}", &values).is_err());
+    assert!(render_template("{{l1_core_contract_address", &values).is_err());
+}
+
+#[test]
+fn yaml_values_keep_the_other_keys() {
+    let yaml = "chain_name: \"Madara\"\neth_core_contract_address: \"0x0\"\nblock_time: \"6s\"\n";
+
+    let updated = set_yaml_values(yaml, &[("eth_core_contract_address", "0xc0".to_string()), ("chain_id", "X".into())]);
+
+    assert_eq!(
+        updated,
+        "chain_name: \"Madara\"\neth_core_contract_address: \"0xc0\"\nblock_time: \"6s\"\nchain_id: \"X\"\n"
+    );
+}
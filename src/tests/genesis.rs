@@ -0,0 +1,113 @@
+//! Genesis bundle, built from the artifacts without any chain, and the checks of the `genesis` mode.
+
+use serde_json::Value;
+use starknet::core::types::Felt;
+use starknet_core::utils::get_storage_var_address;
+
+use crate::setup_scripts::genesis::{GenesisBalance, GenesisSetup};
+use crate::tests::mock_l2::MockL2;
+use crate::tests::unverified_artifacts;
+use crate::{Bootstrapper, BootstrapperError, ConfigFile};
+
+const L1_ETH_BRIDGE: Felt = Felt::from_hex_unchecked("0xe1");
+
+fn storage_value(storage: &[((Felt, Felt), Felt)], contract: Felt, var: &str, keys: &[Felt]) -> Option<Felt> {
+    let key = get_storage_var_address(var, keys).unwrap();
+    storage.iter().find(|((address, k), _)| *address == contract && *k == key).map(|(_, value)| *value)
+}
+
+#[test]
+fn genesis_state_links_the_eth_bridge_and_funds_the_deployer() {
+    let user = Felt::from_hex_unchecked("0x7e57");
+    let config = ConfigFile {
+        genesis_balances: Some(vec![GenesisBalance { address: user.to_hex_string(), amount: "42".to_string() }]),
+        ..ConfigFile::default()
+    };
+    unverified_artifacts();
+    let genesis = GenesisSetup::new(&config).unwrap();
+    let addresses = genesis.addresses().unwrap();
+    let state = genesis.genesis_state(L1_ETH_BRIDGE).unwrap();
+
+    assert_eq!(state.eth_fee_token_address, Felt::from_hex(&config.fee_token_address).unwrap());
+    for address in [addresses.l2_deployer, addresses.udc, addresses.eth_token_proxy, addresses.eth_bridge_proxy] {
+        assert!(state.contracts.iter().any(|(contract, _)| *contract == address));
+    }
+    // Every predeployed contract has a genesis class.
+    for (_, class_hash) in &state.contracts {
+        assert!(state.contract_classes.iter().any(|(hash, _)| hash == class_hash));
+    }
+
+    let storage = &state.storage;
+    let bridge = addresses.eth_bridge_proxy;
+    assert_eq!(storage_value(storage, bridge, "l1_bridge", &[]), Some(L1_ETH_BRIDGE));
+    assert_eq!(storage_value(storage, bridge, "l2_token", &[]), Some(addresses.eth_token_proxy));
+    assert_eq!(storage_value(storage, bridge, "governors", &[addresses.l2_deployer]), Some(Felt::ONE));
+    // The L2 multisig is a pending governor of both proxies.
+    let multisig = Felt::from_hex(&config.l2_multisig_address).unwrap();
+    for proxy in [bridge, addresses.eth_token_proxy] {
+        assert_eq!(storage_value(storage, proxy, "candidates", &[multisig]), Some(Felt::ONE));
+    }
+
+    let eth = addresses.eth_token_proxy;
+    assert_eq!(storage_value(storage, eth, "impl_address", &[]), Some(addresses.eth_token));
+    assert_eq!(storage_value(storage, eth, "permitted_minter", &[]), Some(bridge));
+    assert_eq!(storage_value(storage, eth, "ERC20_balances", &[user]), Some(Felt::from(42u8)));
+    let deployer_balance = storage_value(storage, eth, "ERC20_balances", &[addresses.l2_deployer]).unwrap();
+    assert_eq!(storage_value(storage, eth, "ERC20_total_supply", &[]), Some(deployer_balance + Felt::from(42u8)));
+}
+
+#[test]
+fn genesis_bundle_references_its_class_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = ConfigFile::default();
+    unverified_artifacts();
+    let genesis = GenesisSetup::new(&config).unwrap();
+    let state = genesis.genesis_state(L1_ETH_BRIDGE).unwrap();
+
+    let base = "chain_name: \"Madara\"\nchain_id: \"BASE_CHAIN\"\nblock_time: \"30s\"\n";
+
+    let (genesis_file, chain_config_file) = genesis.write_bundle(dir.path(), &state, base, Some("0xc0")).unwrap();
+
+    let written: Value = serde_json::from_str(&std::fs::read_to_string(genesis_file).unwrap()).unwrap();
+    for class in written["contract_classes"].as_array().unwrap() {
+        assert!(dir.path().join(class[1]["path"].as_str().unwrap()).exists());
+    }
+    let chain_config = std::fs::read_to_string(chain_config_file).unwrap();
+    assert!(chain_config.contains("eth_core_contract_address: \"0xc0\""));
+    assert!(chain_config.contains(&format!("chain_id: \"{}\"", state.chain_id)));
+    assert!(chain_config.contains("block_time: \"30s\""));
+    assert!(!chain_config.contains("BASE_CHAIN"));
+}
+
+#[test]
+fn genesis_state_does_not_nominate_an_unset_l2_multisig() {
+    let config = ConfigFile { l2_multisig_address: "0x0".to_string(), ..ConfigFile::default() };
+    unverified_artifacts();
+    let genesis = GenesisSetup::new(&config).unwrap();
+    let addresses = genesis.addresses().unwrap();
+    let state = genesis.genesis_state(L1_ETH_BRIDGE).unwrap();
+
+    assert_eq!(genesis.l2_governance_nominee(addresses.l2_deployer).unwrap(), None);
+    assert_eq!(storage_value(&state.storage, addresses.eth_bridge_proxy, "candidates", &[Felt::ZERO]), None);
+}
+
+#[tokio::test]
+async fn genesis_rejects_a_udc_address_other_than_the_predeployed_one() {
+    let dir = tempfile::tempdir().unwrap();
+    let base_chain_config = dir.path().join("devnet.yaml");
+    std::fs::write(&base_chain_config, "chain_name: \"Madara\"\n").unwrap();
+    let l2 = MockL2::start();
+    let config = ConfigFile {
+        udc_address: Some("0xdc".to_string()),
+        genesis_base_chain_config: Some(base_chain_config.display().to_string()),
+        genesis_dir: Some(dir.path().join("genesis").display().to_string()),
+        ..l2.config()
+    };
+    unverified_artifacts();
+    let mut bootstrapper = Bootstrapper::builder(config).build().await.unwrap();
+
+    let err = bootstrapper.genesis().await.unwrap_err();
+
+    assert!(matches!(err, BootstrapperError::InvalidConfig { field: "udc_address", .. }));
+    assert!(!dir.path().join("genesis").exists());
+}
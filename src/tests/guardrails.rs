@@ -0,0 +1,91 @@
+//! Config guardrails of the production chains.
+
+use starknet::core::types::Felt;
+
+use crate::contract_clients::config::Clients;
+use crate::guardrails::{config_violations, is_production_chain, production_violations};
+use crate::tests::constants::L2_DEPLOYER_ADDRESS;
+use crate::tests::mock_l2::MockL2;
+use crate::{ConfigFile, CoreContractMode, SettlementLayerKind};
+
+#[test]
+fn config_violations_refuse_the_dev_options_and_the_deployer_multisigs() {
+    let config = ConfigFile {
+        dev: true,
+        core_contract_mode: CoreContractMode::Dev,
+        l1_multisig_address: "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_string(),
+        l2_multisig_address: L2_DEPLOYER_ADDRESS.to_string(),
+        l2_deployer_address: Some(L2_DEPLOYER_ADDRESS.to_string()),
+        ..ConfigFile::default()
+    };
+
+    let violations = config_violations(&config);
+
+    assert_eq!(violations.len(), 4, "{violations:?}");
+    assert!(violations.iter().any(|violation| violation.contains("`dev`")));
+    assert!(violations.iter().any(|violation| violation.contains("`core_contract_mode`")));
+    assert!(violations.iter().any(|violation| violation.contains("`l1_multisig_address` is the L1 deployer")));
+    assert!(violations.iter().any(|violation| violation.contains("`l2_multisig_address` is the L2 deployer")));
+}
+
+#[test]
+fn config_violations_accept_a_production_config() {
+    let config = ConfigFile {
+        core_contract_mode: CoreContractMode::Production,
+        l2_deployer_address: Some(L2_DEPLOYER_ADDRESS.to_string()),
+        ..ConfigFile::default()
+    };
+
+    assert!(config_violations(&config).is_empty());
+}
+
+#[test]
+fn config_violations_require_a_settlement_multisig_on_starknet() {
+    let config = ConfigFile {
+        core_contract_mode: CoreContractMode::Production,
+        l2_deployer_address: Some(L2_DEPLOYER_ADDRESS.to_string()),
+        settlement_layer: Some(SettlementLayerKind::Starknet),
+        settlement_account_address: Some("0x5e771e".to_string()),
+        ..ConfigFile::default()
+    };
+    assert_eq!(config_violations(&config), vec!["`settlement_multisig_address` is unset or invalid"]);
+
+    let config = ConfigFile { settlement_multisig_address: Some("0x5e771e".to_string()), ..config };
+    assert_eq!(config_violations(&config), vec!["`settlement_multisig_address` is the settlement account"]);
+
+    let config = ConfigFile { settlement_multisig_address: Some("0x3a1715".to_string()), ..config };
+    assert!(config_violations(&config).is_empty());
+}
+
+#[tokio::test]
+async fn production_violations_require_a_deployed_settlement_multisig() {
+    let settlement = MockL2::start();
+    let config = ConfigFile {
+        core_contract_mode: CoreContractMode::Production,
+        l2_deployer_address: Some(L2_DEPLOYER_ADDRESS.to_string()),
+        settlement_layer: Some(SettlementLayerKind::Starknet),
+        settlement_rpc: Some(settlement.url()),
+        settlement_multisig_address: Some("0x3a1715".to_string()),
+        ..ConfigFile::default()
+    };
+    let clients = Clients::init_from_config(&config).await.unwrap();
+
+    let violations = production_violations(&config, &clients).await;
+    assert_eq!(violations.len(), 1, "{violations:?}");
+    assert!(violations[0].contains("is not deployed"), "{violations:?}");
+
+    settlement.deploy_at(Felt::from(0x3a1715u64), Felt::from(0x5afeu64));
+    assert!(production_violations(&config, &clients).await.is_empty());
+}
+
+#[tokio::test]
+async fn is_production_chain_fails_closed_on_an_unreachable_starknet_settlement() {
+    let config = ConfigFile {
+        settlement_layer: Some(SettlementLayerKind::Starknet),
+        settlement_rpc: Some("http://127.0.0.1:1".to_string()),
+        ..ConfigFile::default()
+    };
+    let clients = Clients::init_from_config(&config).await.unwrap();
+
+    assert!(is_production_chain(&config, &clients).await);
+}
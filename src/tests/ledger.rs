@@ -0,0 +1,90 @@
+use ethers::types::U256;
+
+use crate::ledger::{format_u256_units, format_units, FeeUnit, Fees, Ledger, LedgerChain, LedgerEntry};
+use crate::steps::Step;
+use crate::tests::constants::L2_DEPLOYER_ADDRESS;
+use crate::tests::mock_l1::MockL1;
+use crate::tests::mock_l2::MockL2;
+use crate::{Bootstrapper, BootstrapperError, ConfigFile};
+
+fn entry(step: &str, chain: LedgerChain, tx_hash: &str, fee: u128, fee_unit: FeeUnit) -> LedgerEntry {
+    LedgerEntry {
+        step: step.to_string(),
+        chain,
+        tx_hash: tx_hash.to_string(),
+        block_number: None,
+        target: None,
+        function: None,
+        calldata: vec![],
+        gas_used: None,
+        effective_gas_price: None,
+        fee,
+        fee_unit,
+    }
+}
+
+#[test]
+fn cost_report_sums_the_fees_per_step_and_chain() {
+    let ledger = Ledger::new(vec![
+        entry("core_contract", LedgerChain::Settlement, "0x1", 3, FeeUnit::Wei),
+        entry("eth_bridge", LedgerChain::Settlement, "0x2", 5, FeeUnit::Wei),
+        entry("eth_bridge", LedgerChain::L2, "0x3", 7, FeeUnit::Wei),
+        entry("eth_bridge", LedgerChain::L2, "0x4", 11, FeeUnit::Fri),
+    ]);
+
+    let report = &ledger.cost_report;
+    assert_eq!(
+        report.components.iter().map(|component| component.step.as_str()).collect::<Vec<_>>(),
+        ["core_contract", "eth_bridge"]
+    );
+    assert_eq!(report.components[1].transactions, 3);
+    assert_eq!(report.components[1].l2, Fees { wei: 7, fri: 11 });
+    assert_eq!(report.settlement, Fees { wei: 8, fri: 0 });
+    assert_eq!(report.l2, Fees { wei: 7, fri: 11 });
+}
+
+#[test]
+fn merged_ledgers_keep_pending_transactions_once() {
+    let pending = entry("udc", LedgerChain::L2, "0xa", 1, FeeUnit::Wei);
+    let included = LedgerEntry { block_number: Some(12), step: "argent".to_string(), ..pending.clone() };
+
+    let ledger = Ledger::new(vec![pending]).merge(Ledger::new(vec![included]));
+
+    assert_eq!(ledger.transactions.len(), 1);
+    assert_eq!(ledger.transactions[0].step, "udc");
+    assert_eq!(ledger.transactions[0].block_number, Some(12));
+    assert_eq!(ledger.cost_report.l2.wei, 1);
+}
+
+#[test]
+fn fees_are_formatted_with_18_decimals() {
+    assert_eq!(format_units(0), "0");
+    assert_eq!(format_units(1_500_000_000_000_000_000), "1.5");
+    assert_eq!(format_units(21_000 * 30_000_000_000), "0.00063");
+    assert_eq!(format_u256_units(U256::from(1_500_000_000_000_000_000u128)), "1.5");
+    assert_eq!(format_u256_units(U256::exp10(40) + U256::exp10(17)), "10000000000000000000000.1");
+}
+
+#[tokio::test]
+async fn failed_run_keeps_the_ledger_of_the_previous_steps() {
+    let (l1, l2) = (MockL1::start(), MockL2::start());
+    let config = ConfigFile {
+        eth_rpc: l1.url(),
+        l2_deployer_address: Some(L2_DEPLOYER_ADDRESS.to_string()),
+        udc_address: Some("0xnot_a_felt".to_string()),
+        ..l2.config()
+    };
+    let mut bootstrapper = Bootstrapper::builder(config).build().await.unwrap();
+
+    let err = bootstrapper.run_steps(&[Step::CoreContract, Step::Udc]).await.unwrap_err();
+
+    let (step, ledger, source) = match err {
+        BootstrapperError::StepFailed { step, ledger: Some(ledger), source } => (step, ledger, source),
+        err => panic!("Unexpected error : {err:?}"),
+    };
+    assert_eq!(step, Step::Udc.name());
+    assert!(matches!(*source, BootstrapperError::InvalidConfig { field: "udc_address", .. }));
+    assert_eq!(ledger.transactions.len(), l1.transactions().len());
+    assert!(ledger.transactions.iter().all(|entry| entry.step == Step::CoreContract.name()));
+    assert_eq!(ledger.cost_report.components.len(), 1);
+}
@@ -0,0 +1,76 @@
+//! Declarations of the legacy classes through the declare v0 backends of the mock appchain RPC.
+//! The clock is paused so the retries and the polls are skipped.
+
+use starknet::core::types::contract::legacy::LegacyContractClass;
+use starknet::core::types::{BlockId, BlockTag};
+use starknet::providers::Provider;
+
+use crate::contract_clients::config::Clients;
+use crate::contract_clients::declare_v0::DeclareV0Backend;
+use crate::tests::mock_l2::MockL2;
+use crate::utils::artifacts::load_json_artifact;
+use crate::utils::constants::UDC_PATH;
+use crate::ConfigFile;
+
+async fn mock_clients(l2: &MockL2, backend: Option<DeclareV0Backend>) -> Clients {
+    let config = ConfigFile { declare_v0_backend: backend, ..l2.config() };
+    Clients::init_from_config(&config).await.unwrap()
+}
+
+fn udc_class() -> LegacyContractClass {
+    load_json_artifact(UDC_PATH).unwrap()
+}
+
+#[tokio::test(start_paused = true)]
+async fn legacy_declaration_retries_the_transient_errors() {
+    let l2 = MockL2::start();
+    let clients = mock_clients(&l2, None).await;
+    l2.fail_legacy_declarations(vec![(-32603, "Internal error"), (63, "Unexpected error")]);
+
+    let class_hash = clients.declare_v0().declare(udc_class(), clients.provider_l2()).await.unwrap();
+
+    assert_eq!(class_hash, udc_class().class_hash().unwrap());
+    assert_eq!(clients.declare_v0().backend().await, DeclareV0Backend::MadaraAdmin);
+    assert_eq!(l2.legacy_declarations().len(), 1);
+    assert!(clients.provider_l2().get_class(BlockId::Tag(BlockTag::Pending), class_hash).await.is_ok());
+}
+
+#[tokio::test(start_paused = true)]
+async fn legacy_declaration_fails_when_the_node_rejects_the_class() {
+    let l2 = MockL2::start();
+    let clients = mock_clients(&l2, None).await;
+    l2.fail_legacy_declarations(vec![(55, "Account validation failed")]);
+
+    let err = clients.declare_v0().declare(udc_class(), clients.provider_l2()).await.unwrap_err().to_string();
+
+    assert!(err.contains("rejected the declaration"), "{err}");
+    assert!(err.contains("Account validation failed"), "{err}");
+    assert!(l2.legacy_declarations().is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn legacy_declaration_goes_through_the_gateway() {
+    let l2 = MockL2::start();
+    let clients = mock_clients(&l2, Some(DeclareV0Backend::Gateway)).await;
+
+    let class_hash = clients.declare_v0().declare(udc_class(), clients.provider_l2()).await.unwrap();
+
+    assert_eq!(class_hash, udc_class().class_hash().unwrap());
+    let declarations = l2.legacy_declarations();
+    assert_eq!(declarations.len(), 1);
+    assert_eq!(declarations[0]["type"], "DECLARE");
+    assert!(declarations[0]["contract_class"]["program"].is_string());
+}
+
+#[tokio::test(start_paused = true)]
+async fn predeclared_backend_requires_the_class() {
+    let l2 = MockL2::start();
+    let clients = mock_clients(&l2, Some(DeclareV0Backend::Predeclared)).await;
+
+    let err = clients.declare_v0().declare(udc_class(), clients.provider_l2()).await.unwrap_err().to_string();
+    assert!(err.contains("`predeclared`"), "{err}");
+    assert!(l2.legacy_declarations().is_empty());
+
+    l2.declare_class(udc_class().class_hash().unwrap());
+    assert!(clients.declare_v0().declare(udc_class(), clients.provider_l2()).await.is_ok());
+}
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use ethers::abi::{encode, Token};
+use ethers::middleware::SignerMiddleware;
+use ethers::providers::{Http, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, Block, Bytes, Transaction, TransactionReceipt, H256, U256, U64};
+use ethers::utils::{get_contract_address, id, rlp};
+use serde_json::{json, Value};
+use zaun_utils::LocalWalletSignerMiddleware;
+
+use crate::tests::constants::ETH_PRIV_KEY;
+use crate::tests::mock_rpc::{param, rpc_response, MockServer, RpcError, RpcResult};
+use crate::tests::unverified_artifacts;
+
+/// Default `eth_chain_id` of the config.
+const CHAIN_ID: u64 = 31337;
+
+/// Timestamp of the block 0, each transaction mines a block one second later.
+const GENESIS_TIMESTAMP: u64 = 1_700_000_000;
+
+const GAS_PRICE: u64 = 1_000_000_000;
+
+/// Local HTTP stand-in for the L1 JSON-RPC used through ethers. Signed transactions are decoded,
+/// mined right away (one block each) and recorded so that the tests can check the calls sent by
+/// the setup scripts. `eth_call` answers the results set with [`MockL1::set_call_result`], a zero
+/// word otherwise. Like a StarkWare proxy, `implementation()` answers the implementation of the
+/// last `upgradeTo` sent to the contract when no result is set.
+pub struct MockL1 {
+    server: MockServer,
+    state: Arc<Mutex<MockL1State>>,
+}
+
+/// Transaction sent to the [`MockL1`].
+#[derive(Debug, Clone)]
+pub struct MockL1Transaction {
+    pub from: Address,
+    pub to: Option<Address>,
+    pub data: Bytes,
+    /// Address of the deployed contract, for the deployments.
+    pub contract_address: Option<Address>,
+}
+
+impl MockL1Transaction {
+    /// `signature` is the solidity signature of the function, e.g. `upgradeTo(address,bytes,bool)`.
+    pub fn is(&self, to: Address, signature: &str) -> bool {
+        self.to == Some(to) && self.data.starts_with(&id(signature))
+    }
+
+    /// ABI encoded arguments of the call.
+    pub fn args(&self) -> &[u8] {
+        self.data.get(4..).unwrap_or_default()
+    }
+}
+
+#[derive(Default)]
+struct MockL1State {
+    transactions: Vec<Transaction>,
+    receipts: HashMap<H256, TransactionReceipt>,
+    nonces: HashMap<Address, u64>,
+    call_results: HashMap<(Address, [u8; 4]), Bytes>,
+    implementations: HashMap<Address, Address>,
+    block_number: u64,
+}
+
+impl MockL1 {
+    pub fn start() -> Self {
+        unverified_artifacts();
+        let state = Arc::new(Mutex::new(MockL1State::default()));
+        let server_state = state.clone();
+        let server = MockServer::start(move |_, body| {
+            ("200 OK", rpc_response(body, |method, params| server_state.lock().unwrap().handle(method, params)))
+        });
+        Self { server, state }
+    }
+
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    /// Client of the deployer (`eth_priv_key` of the default config). Unlike the client of
+    /// [`crate::contract_clients::config::Clients`] (ethers default of 7 secs), it polls the
+    /// pending transactions every few milliseconds.
+    pub fn client(&self) -> Arc<LocalWalletSignerMiddleware> {
+        let provider = Provider::<Http>::try_from(self.url()).unwrap().interval(Duration::from_millis(10));
+        let wallet: LocalWallet = ETH_PRIV_KEY.parse().unwrap();
+        Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(CHAIN_ID)))
+    }
+
+    /// Result of `eth_call` for `signature` on `contract`.
+    pub fn set_call_result(&self, contract: Address, signature: &str, result: &[Token]) {
+        self.state.lock().unwrap().call_results.insert((contract, id(signature)), Bytes::from(encode(result)));
+    }
+
+    /// Transactions mined by the mock, in order.
+    pub fn transactions(&self) -> Vec<MockL1Transaction> {
+        let state = self.state.lock().unwrap();
+        state
+            .transactions
+            .iter()
+            .map(|transaction| MockL1Transaction {
+                from: transaction.from,
+                to: transaction.to,
+                data: transaction.input.clone(),
+                contract_address: state.receipts[&transaction.hash].contract_address,
+            })
+            .collect()
+    }
+
+    /// Addresses of the deployed contracts, in order.
+    pub fn deployments(&self) -> Vec<Address> {
+        self.transactions().into_iter().filter_map(|transaction| transaction.contract_address).collect()
+    }
+
+    /// Transactions sent to `to` (deployments excluded), in order.
+    pub fn calls_to(&self, to: Address) -> Vec<MockL1Transaction> {
+        self.transactions().into_iter().filter(|transaction| transaction.to == Some(to)).collect()
+    }
+
+    /// Timestamp of the latest block.
+    pub fn timestamp(&self) -> U256 {
+        U256::from(GENESIS_TIMESTAMP + self.state.lock().unwrap().block_number)
+    }
+}
+
+impl MockL1State {
+    fn handle(&mut self, method: &Value, params: &Value) -> RpcResult {
+        match method.as_str().unwrap_or_default() {
+            "eth_chainId" => Ok(json!(U64::from(CHAIN_ID))),
+            "net_version" => Ok(json!(CHAIN_ID.to_string())),
+            "eth_blockNumber" => Ok(json!(U64::from(self.block_number))),
+            "eth_getBlockByNumber" => {
+                let number = match param(params, 0, "block").as_str() {
+                    Some("latest" | "pending") | None => self.block_number,
+                    Some(_) => value::<U64>(param(params, 0, "block"))?.as_u64(),
+                };
+                Ok(self.block(number, param(params, 1, "full").as_bool().unwrap_or_default()))
+            }
+            "eth_getTransactionCount" => {
+                let address: Address = value(param(params, 0, "address"))?;
+                Ok(json!(U256::from(self.nonces.get(&address).copied().unwrap_or_default())))
+            }
+            "eth_getBalance" => Ok(json!(U256::exp10(24))),
+            "eth_gasPrice" | "eth_maxPriorityFeePerGas" => Ok(json!(U256::from(GAS_PRICE))),
+            "eth_feeHistory" => Ok(json!({
+                "oldestBlock": U64::from(self.block_number),
+                "baseFeePerGas": [U256::from(GAS_PRICE), U256::from(GAS_PRICE)],
+                "gasUsedRatio": [0.5],
+                "reward": [[U256::from(GAS_PRICE)]],
+            })),
+            "eth_estimateGas" => Ok(json!(U256::from(30_000_000))),
+            "eth_getCode" => {
+                let address: Address = value(param(params, 0, "address"))?;
+                let deployed = self.receipts.values().any(|receipt| receipt.contract_address == Some(address));
+                Ok(json!(if deployed { "0x60" } else { "0x" }))
+            }
+            "eth_call" => {
+                let request = param(params, 0, "transaction");
+                let to: Address = value(&request["to"])?;
+                let data: Bytes = value(if request["input"].is_null() { &request["data"] } else { &request["input"] })?;
+                let selector = data.get(..4).and_then(|selector| selector.try_into().ok()).unwrap_or_default();
+                let result = self.call_results.get(&(to, selector)).cloned().or_else(|| {
+                    let implementation =
+                        self.implementations.get(&to).filter(|_| selector == id("implementation()"))?;
+                    Some(Bytes::from(encode(&[Token::Address(*implementation)])))
+                });
+                Ok(json!(result.unwrap_or_else(|| Bytes::from(vec![0; 32]))))
+            }
+            "eth_getLogs" => Ok(json!([])),
+            "eth_sendRawTransaction" => self.send_raw_transaction(value(param(params, 0, "data"))?),
+            "eth_getTransactionByHash" => {
+                let hash: H256 = value(param(params, 0, "hash"))?;
+                Ok(json!(self.transactions.iter().find(|transaction| transaction.hash == hash)))
+            }
+            "eth_getTransactionReceipt" => {
+                let hash: H256 = value(param(params, 0, "hash"))?;
+                Ok(json!(self.receipts.get(&hash)))
+            }
+            _ => Err(RpcError(-32601, "Method not found")),
+        }
+    }
+
+    /// Decodes the signed transaction and mines it in a new block.
+    fn send_raw_transaction(&mut self, raw: Bytes) -> RpcResult {
+        let mut transaction: Transaction = rlp::decode(&raw).map_err(|_| RpcError(-32602, "Invalid transaction"))?;
+        let from = transaction.recover_from_mut().map_err(|_| RpcError(-32602, "Invalid signature"))?;
+        let nonce = self.nonces.entry(from).or_default();
+        if transaction.nonce != U256::from(*nonce) {
+            return Err(RpcError(-32000, "Invalid nonce"));
+        }
+        *nonce += 1;
+
+        self.block_number += 1;
+        let block_number = U64::from(self.block_number);
+        let block_hash = H256::from_low_u64_be(self.block_number);
+        transaction.block_number = Some(block_number);
+        transaction.block_hash = Some(block_hash);
+        transaction.transaction_index = Some(U64::zero());
+
+        if transaction.input.starts_with(&id("upgradeTo(address,bytes,bool)")) {
+            if let (Some(to), Some(implementation)) = (transaction.to, transaction.input.get(16..36)) {
+                self.implementations.insert(to, Address::from_slice(implementation));
+            }
+        }
+
+        let contract_address = match transaction.to {
+            Some(_) => None,
+            None => Some(get_contract_address(from, transaction.nonce)),
+        };
+        let receipt = TransactionReceipt {
+            transaction_hash: transaction.hash,
+            transaction_index: U64::zero(),
+            block_hash: Some(block_hash),
+            block_number: Some(block_number),
+            from,
+            to: transaction.to,
+            cumulative_gas_used: U256::from(21_000),
+            gas_used: Some(U256::from(21_000)),
+            effective_gas_price: Some(U256::from(GAS_PRICE)),
+            contract_address,
+            status: Some(U64::one()),
+            transaction_type: transaction.transaction_type,
+            ..TransactionReceipt::default()
+        };
+        self.receipts.insert(transaction.hash, receipt);
+        let hash = transaction.hash;
+        self.transactions.push(transaction);
+        Ok(json!(hash))
+    }
+
+    /// Block `number` with its transaction (the transactions are mined one per block), in full or
+    /// as hashes.
+    fn block(&self, number: u64, full: bool) -> Value {
+        let transactions =
+            self.transactions.iter().filter(|transaction| transaction.block_number == Some(U64::from(number)));
+        match full {
+            true => json!(mined_block(number, transactions.cloned().collect())),
+            false => json!(mined_block(number, transactions.map(|transaction| transaction.hash).collect())),
+        }
+    }
+}
+
+fn mined_block<TX: Default>(number: u64, transactions: Vec<TX>) -> Block<TX> {
+    Block {
+        hash: Some(H256::from_low_u64_be(number)),
+        parent_hash: H256::from_low_u64_be(number.saturating_sub(1)),
+        number: Some(U64::from(number)),
+        timestamp: U256::from(GENESIS_TIMESTAMP + number),
+        gas_limit: U256::from(30_000_000),
+        base_fee_per_gas: Some(U256::from(GAS_PRICE)),
+        transactions,
+        ..Block::default()
+    }
+}
+
+fn value<T: serde::de::DeserializeOwned>(value: &Value) -> Result<T, RpcError> {
+    serde_json::from_value(value.clone()).map_err(|_| RpcError(-32602, "Invalid params"))
+}
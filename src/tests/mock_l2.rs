@@ -0,0 +1,436 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Value};
+use starknet::core::types::contract::legacy::LegacyContractClass;
+use starknet::core::types::{Felt, FlattenedSierraClass};
+use starknet::core::utils::get_selector_from_name;
+use starknet_core::utils::{get_contract_address, get_udc_deployed_address, UdcUniqueSettings, UdcUniqueness};
+
+use crate::contract_clients::legacy_class::CompressedLegacyContractClass;
+use crate::tests::mock_rpc::{param, rpc_response, MockServer, RpcError, RpcResult};
+use crate::tests::unverified_artifacts;
+use crate::utils::artifacts::load_json_artifact;
+use crate::utils::constants::{
+    BRAAVOS_AGGREGATOR_PATH, ERC20_LEGACY_PATH, LEGACY_BRIDGE_PATH, OZ_ACCOUNT_PATH, PROXY_LEGACY_PATH,
+    STARKGATE_PROXY_PATH, UDC_PATH,
+};
+use crate::ConfigFile;
+
+/// `SN_SEPOLIA`
+const CHAIN_ID: &str = "0x534e5f5345504f4c4941";
+
+/// Legacy artifacts the declarations through `madara_addDeclareV0Transaction` are matched against
+/// (by compressed program) to know the declared class hash.
+const LEGACY_ARTIFACTS: [&str; 7] = [
+    OZ_ACCOUNT_PATH,
+    PROXY_LEGACY_PATH,
+    UDC_PATH,
+    BRAAVOS_AGGREGATOR_PATH,
+    LEGACY_BRIDGE_PATH,
+    STARKGATE_PROXY_PATH,
+    ERC20_LEGACY_PATH,
+];
+
+/// Local HTTP stand-in for the appchain JSON-RPC (and the `madara_addDeclareV0Transaction`
+/// admin endpoint, and `gateway/add_transaction` for the declarations). Transactions are accepted
+/// right away and recorded so that the tests can check the calls sent by the setup scripts.
+/// `deploy_contract` / `deployContract` calls emit the `ContractDeployed` event of the UDC.
+/// Accounts are deployed at the address of their `DEPLOY_ACCOUNT` transaction and `replace_to`
+/// calls move the contract to the new class.
+pub struct MockL2 {
+    server: MockServer,
+    state: Arc<Mutex<MockL2State>>,
+}
+
+/// Call of an invoke transaction (one per call of the multicall).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockCall {
+    pub sender: Felt,
+    pub to: Felt,
+    pub selector: Felt,
+    pub calldata: Vec<Felt>,
+}
+
+impl MockCall {
+    pub fn is(&self, to: Felt, method: &str) -> bool {
+        self.to == to && self.selector == get_selector_from_name(method).unwrap()
+    }
+}
+
+#[derive(Default)]
+struct MockL2State {
+    declared_classes: HashSet<Felt>,
+    declarations: Vec<Felt>,
+    legacy_declarations: Vec<Value>,
+    legacy_declaration_errors: Vec<RpcError>,
+    calls: Vec<MockCall>,
+    deployments: Vec<(Felt, Felt)>,
+    constructor_callers: HashMap<Felt, Felt>,
+    genesis_contracts: HashMap<Felt, Felt>,
+    replaced_classes: HashMap<Felt, Felt>,
+    nonces: HashMap<Felt, u64>,
+    receipts: HashMap<Felt, Value>,
+    call_results: HashMap<(Felt, Felt), Vec<Felt>>,
+    transaction_count: u64,
+}
+
+impl MockL2 {
+    pub fn start() -> Self {
+        unverified_artifacts();
+        let state = Arc::new(Mutex::new(MockL2State::default()));
+        let server_state = state.clone();
+        let server = MockServer::start(move |path, body| handle_request(&server_state, path, body));
+        Self { server, state }
+    }
+
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    /// Config pointing the appchain RPC and declare v0 endpoints to the mock, without the waits
+    /// for the L1 and cross chain messages.
+    pub fn config(&self) -> ConfigFile {
+        ConfigFile {
+            rollup_seq_url: self.url(),
+            rollup_declare_v0_seq_url: self.url(),
+            l1_wait_time: "0".to_string(),
+            cross_chain_wait_time: 0,
+            ..ConfigFile::default()
+        }
+    }
+
+    /// Makes `get_class` succeed for `class_hash`, as if it was declared in genesis.
+    pub fn declare_class(&self, class_hash: Felt) {
+        self.state.lock().unwrap().declared_classes.insert(class_hash);
+    }
+
+    /// Makes `get_class_hash_at` answer `class_hash` for `address`, as if it was deployed in
+    /// genesis.
+    pub fn deploy_at(&self, address: Felt, class_hash: Felt) {
+        self.state.lock().unwrap().genesis_contracts.insert(address, class_hash);
+    }
+
+    /// Result of `starknet_call` for `method` on `contract` (defaults to `[0]`).
+    pub fn set_call_result(&self, contract: Felt, method: &str, result: Vec<Felt>) {
+        self.state.lock().unwrap().call_results.insert((contract, get_selector_from_name(method).unwrap()), result);
+    }
+
+    /// Class hashes of the Cairo 1 declarations, in order.
+    pub fn declarations(&self) -> Vec<Felt> {
+        self.state.lock().unwrap().declarations.clone()
+    }
+
+    /// Params of the `madara_addDeclareV0Transaction` requests, in order.
+    pub fn legacy_declarations(&self) -> Vec<Value> {
+        self.state.lock().unwrap().legacy_declarations.clone()
+    }
+
+    /// Errors answered to the next `madara_addDeclareV0Transaction` requests, in order, before
+    /// accepting them again.
+    pub fn fail_legacy_declarations(&self, errors: Vec<(i64, &'static str)>) {
+        self.state.lock().unwrap().legacy_declaration_errors =
+            errors.into_iter().map(|(code, message)| RpcError(code, message)).collect();
+    }
+
+    /// Calls of the invoke transactions, in order.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state.lock().unwrap().calls.clone()
+    }
+
+    /// `(address, class_hash)` of the contracts deployed through `deploy_contract` /
+    /// `deployContract` and of the deployed accounts.
+    pub fn deployments(&self) -> Vec<(Felt, Felt)> {
+        self.state.lock().unwrap().deployments.clone()
+    }
+
+    /// Caller of the constructor of the contract deployed at `address` : the account for its own
+    /// `deploy_contract`, the UDC otherwise. The StarkGate bridges grant it their roles.
+    pub fn constructor_caller(&self, address: Felt) -> Option<Felt> {
+        self.state.lock().unwrap().constructor_callers.get(&address).copied()
+    }
+}
+
+/// `gateway/add_transaction` for the declarations through the gateway, JSON-RPC otherwise.
+fn handle_request(state: &Mutex<MockL2State>, path: &str, body: &[u8]) -> (&'static str, String) {
+    match path {
+        path if path.ends_with("/gateway/is_alive") => ("200 OK", json!("Gateway is alive!").to_string()),
+        path if path.ends_with("/gateway/add_transaction") => {
+            let transaction = serde_json::from_slice::<Value>(body).unwrap_or_default();
+            match state.lock().unwrap().add_declare_v0(&transaction) {
+                Ok(mut result) => {
+                    result["code"] = json!("TRANSACTION_RECEIVED");
+                    ("200 OK", result.to_string())
+                }
+                Err(RpcError(_, message)) => (
+                    "400 Bad Request",
+                    json!({ "code": "StarknetErrorCode.INVALID_TRANSACTION", "message": message }).to_string(),
+                ),
+            }
+        }
+        _ => ("200 OK", rpc_response(body, |method, params| state.lock().unwrap().handle(method, params))),
+    }
+}
+
+impl MockL2State {
+    fn handle(&mut self, method: &Value, params: &Value) -> RpcResult {
+        match method.as_str().unwrap_or_default() {
+            "starknet_chainId" => Ok(json!(CHAIN_ID)),
+            "starknet_getNonce" => {
+                let address = felt(param(params, 1, "contract_address"))?;
+                Ok(json!(Felt::from(self.nonces.get(&address).copied().unwrap_or_default()).to_hex_string()))
+            }
+            "starknet_blockNumber" => Ok(json!(0)),
+            "starknet_getBlockWithTxHashes" | "starknet_getBlockWithTxs" => Ok(pending_block()),
+            "starknet_estimateFee" => {
+                let count = param(params, 0, "request").as_array().map(Vec::len).unwrap_or(1);
+                Ok(Value::Array(vec![zero_fee_estimate(); count]))
+            }
+            "starknet_getClass" => {
+                let class_hash = felt(param(params, 1, "class_hash"))?;
+                match self.declared_classes.contains(&class_hash) {
+                    true => Ok(empty_class()),
+                    false => Err(RpcError(28, "Class hash not found")),
+                }
+            }
+            "starknet_getClassAt" => {
+                self.class_hash_at(felt(param(params, 1, "contract_address"))?).map(|_| empty_class())
+            }
+            "starknet_getClassHashAt" => {
+                self.class_hash_at(felt(param(params, 1, "contract_address"))?).map(|hash| json!(hash.to_hex_string()))
+            }
+            "starknet_call" => {
+                let request = param(params, 0, "request");
+                let key = (felt(&request["contract_address"])?, felt(&request["entry_point_selector"])?);
+                let result = self.call_results.get(&key).cloned().unwrap_or(vec![Felt::ZERO]);
+                Ok(json!(result.iter().map(Felt::to_hex_string).collect::<Vec<_>>()))
+            }
+            "starknet_addInvokeTransaction" => self.add_invoke(param(params, 0, "invoke_transaction")),
+            "starknet_addDeclareTransaction" => self.add_declare(param(params, 0, "declare_transaction")),
+            "starknet_addDeployAccountTransaction" => {
+                self.add_deploy_account(param(params, 0, "deploy_account_transaction"))
+            }
+            "madara_addDeclareV0Transaction" => self.add_declare_v0(param(params, 0, "declare_v0_transaction")),
+            "starknet_getTransactionReceipt" => {
+                let transaction_hash = felt(param(params, 0, "transaction_hash"))?;
+                self.receipts.get(&transaction_hash).cloned().ok_or(RpcError(29, "Transaction hash not found"))
+            }
+            _ => Err(RpcError(-32601, "Method not found")),
+        }
+    }
+
+    fn class_hash_at(&self, address: Felt) -> Result<Felt, RpcError> {
+        if let Some(class_hash) = self.replaced_classes.get(&address) {
+            return Ok(*class_hash);
+        }
+        self.deployments
+            .iter()
+            .find(|(deployed, _)| *deployed == address)
+            .map(|(_, class_hash)| *class_hash)
+            .or_else(|| self.genesis_contracts.get(&address).copied())
+            .ok_or(RpcError(20, "Contract not found"))
+    }
+
+    fn add_invoke(&mut self, transaction: &Value) -> RpcResult {
+        let sender = felt(&transaction["sender_address"])?;
+        let calldata = felts(&transaction["calldata"])?;
+        *self.nonces.entry(sender).or_default() += 1;
+
+        let mut events = vec![];
+        for call in decode_multicall(sender, &calldata)? {
+            if let Some(event) = self.deploy(&call) {
+                events.push(event);
+            }
+            self.replace_class(&call);
+            self.calls.push(call);
+        }
+
+        let transaction_hash = self.next_transaction_hash();
+        self.receipts.insert(transaction_hash, receipt("INVOKE", transaction_hash, events));
+        Ok(json!({ "transaction_hash": transaction_hash.to_hex_string() }))
+    }
+
+    fn add_declare(&mut self, transaction: &Value) -> RpcResult {
+        let sender = felt(&transaction["sender_address"])?;
+        let class: FlattenedSierraClass = serde_json::from_value(transaction["contract_class"].clone())
+            .map_err(|_| RpcError(-32602, "Invalid params"))?;
+        let class_hash = class.class_hash();
+        *self.nonces.entry(sender).or_default() += 1;
+        self.declared_classes.insert(class_hash);
+        self.declarations.push(class_hash);
+
+        let transaction_hash = self.next_transaction_hash();
+        self.receipts.insert(transaction_hash, receipt("DECLARE", transaction_hash, vec![]));
+        Ok(json!({ "transaction_hash": transaction_hash.to_hex_string(), "class_hash": class_hash.to_hex_string() }))
+    }
+
+    fn add_deploy_account(&mut self, transaction: &Value) -> RpcResult {
+        let class_hash = felt(&transaction["class_hash"])?;
+        let salt = felt(&transaction["contract_address_salt"])?;
+        let constructor_calldata = felts(&transaction["constructor_calldata"])?;
+        let address = get_contract_address(salt, class_hash, &constructor_calldata, Felt::ZERO);
+        *self.nonces.entry(address).or_default() += 1;
+        self.deployments.push((address, class_hash));
+
+        let transaction_hash = self.next_transaction_hash();
+        let mut receipt = receipt("DEPLOY_ACCOUNT", transaction_hash, vec![]);
+        receipt["contract_address"] = json!(address.to_hex_string());
+        self.receipts.insert(transaction_hash, receipt);
+        Ok(json!({ "transaction_hash": transaction_hash.to_hex_string(), "contract_address": address.to_hex_string() }))
+    }
+
+    fn add_declare_v0(&mut self, transaction: &Value) -> RpcResult {
+        if !transaction.is_object() {
+            return Err(RpcError(-32602, "Invalid params"));
+        }
+        if !self.legacy_declaration_errors.is_empty() {
+            return Err(self.legacy_declaration_errors.remove(0));
+        }
+        self.legacy_declarations.push(transaction.clone());
+        let class_hash = legacy_class_hash(&transaction["contract_class"]["program"]).unwrap_or_default();
+        self.declared_classes.insert(class_hash);
+
+        let transaction_hash = self.next_transaction_hash();
+        self.receipts.insert(transaction_hash, receipt("DECLARE", transaction_hash, vec![]));
+        Ok(json!({ "transaction_hash": transaction_hash.to_hex_string(), "class_hash": class_hash.to_hex_string() }))
+    }
+
+    /// `ContractDeployed` event of a `deploy_contract` (account) / `deployContract` (UDC) call :
+    /// `[class_hash, salt, unique, calldata_len, calldata...]`.
+    fn deploy(&mut self, call: &MockCall) -> Option<Value> {
+        let is_deploy = ["deploy_contract", "deployContract"]
+            .into_iter()
+            .any(|method| get_selector_from_name(method).unwrap() == call.selector);
+        if !is_deploy {
+            return None;
+        }
+        let [class_hash, salt, unique, _, constructor_calldata @ ..] = call.calldata.as_slice() else {
+            return None;
+        };
+
+        let uniqueness = match *unique == Felt::ZERO {
+            true => UdcUniqueness::NotUnique,
+            false => UdcUniqueness::Unique(UdcUniqueSettings {
+                deployer_address: call.sender,
+                udc_contract_address: call.to,
+            }),
+        };
+        let address = get_udc_deployed_address(*salt, *class_hash, &uniqueness, constructor_calldata);
+        self.deployments.push((address, *class_hash));
+        self.constructor_callers.insert(address, call.to);
+
+        let mut data = vec![address, call.sender, *unique, *class_hash, Felt::from(constructor_calldata.len())];
+        data.extend(constructor_calldata);
+        data.push(*salt);
+        Some(json!({
+            "from_address": call.to.to_hex_string(),
+            "keys": [get_selector_from_name("ContractDeployed").unwrap().to_hex_string()],
+            "data": data.iter().map(Felt::to_hex_string).collect::<Vec<_>>(),
+        }))
+    }
+
+    /// `replace_to` call of an upgradable contract : `[class_hash, eic..., final]`.
+    fn replace_class(&mut self, call: &MockCall) {
+        if call.selector != get_selector_from_name("replace_to").unwrap() {
+            return;
+        }
+        if let Some(class_hash) = call.calldata.first() {
+            self.replaced_classes.insert(call.to, *class_hash);
+        }
+    }
+
+    fn next_transaction_hash(&mut self) -> Felt {
+        self.transaction_count += 1;
+        Felt::from(0x1000 + self.transaction_count)
+    }
+}
+
+/// Hash of the class of [`LEGACY_ARTIFACTS`] with the compressed `program`.
+fn legacy_class_hash(program: &Value) -> Option<Felt> {
+    LEGACY_ARTIFACTS.into_iter().find_map(|path| {
+        let class: LegacyContractClass = load_json_artifact(path).ok()?;
+        let compressed = class.compress().ok()?;
+        // Base64 through the gateway, bytes through the admin RPC.
+        let gateway_program = serde_json::to_value(&compressed).ok()?["program"].take();
+        let rpc_program = json!(CompressedLegacyContractClass::from(compressed).program);
+        if *program != gateway_program && *program != rpc_program {
+            return None;
+        }
+        class.class_hash().ok()
+    })
+}
+
+fn felt(value: &Value) -> Result<Felt, RpcError> {
+    value.as_str().and_then(|value| Felt::from_hex(value).ok()).ok_or(RpcError(-32602, "Invalid params"))
+}
+
+fn felts(value: &Value) -> Result<Vec<Felt>, RpcError> {
+    value.as_array().ok_or(RpcError(-32602, "Invalid params"))?.iter().map(felt).collect()
+}
+
+/// `__execute__` calldata of an account using the new encoding :
+/// `[calls_len, (to, selector, calldata_len, calldata...)...]`.
+fn decode_multicall(sender: Felt, calldata: &[Felt]) -> Result<Vec<MockCall>, RpcError> {
+    let invalid = || RpcError(-32602, "Invalid multicall");
+    let (calls_len, mut rest) = calldata.split_first().ok_or_else(invalid)?;
+    let mut calls = vec![];
+    for _ in 0..u64::try_from(*calls_len).map_err(|_| invalid())? {
+        let [to, selector, calldata_len, tail @ ..] = rest else { return Err(invalid()) };
+        let calldata_len = u64::try_from(*calldata_len).map_err(|_| invalid())? as usize;
+        if tail.len() < calldata_len {
+            return Err(invalid());
+        }
+        let (calldata, tail) = tail.split_at(calldata_len);
+        calls.push(MockCall { sender, to: *to, selector: *selector, calldata: calldata.to_vec() });
+        rest = tail;
+    }
+    Ok(calls)
+}
+
+fn receipt(transaction_type: &str, transaction_hash: Felt, events: Vec<Value>) -> Value {
+    json!({
+        "type": transaction_type,
+        "transaction_hash": transaction_hash.to_hex_string(),
+        "actual_fee": { "amount": "0x0", "unit": "WEI" },
+        "finality_status": "ACCEPTED_ON_L2",
+        "execution_status": "SUCCEEDED",
+        "block_hash": "0x1",
+        "block_number": 1,
+        "messages_sent": [],
+        "events": events,
+        "execution_resources": { "steps": 0, "data_availability": { "l1_gas": 0, "l1_data_gas": 0 } },
+    })
+}
+
+fn pending_block() -> Value {
+    json!({
+        "transactions": [],
+        "timestamp": 0,
+        "sequencer_address": "0x0",
+        "parent_hash": "0x0",
+        "l1_gas_price": { "price_in_fri": "0x0", "price_in_wei": "0x0" },
+        "l1_data_gas_price": { "price_in_fri": "0x0", "price_in_wei": "0x0" },
+        "l1_da_mode": "BLOB",
+        "starknet_version": "0.13.2",
+    })
+}
+
+fn zero_fee_estimate() -> Value {
+    json!({
+        "gas_consumed": "0x0",
+        "gas_price": "0x0",
+        "data_gas_consumed": "0x0",
+        "data_gas_price": "0x0",
+        "overall_fee": "0x0",
+        "unit": "FRI",
+    })
+}
+
+fn empty_class() -> Value {
+    json!({
+        "sierra_program": [],
+        "contract_class_version": "0.1.0",
+        "entry_points_by_type": { "CONSTRUCTOR": [], "EXTERNAL": [], "L1_HANDLER": [] },
+        "abi": "",
+    })
+}
@@ -0,0 +1,121 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use serde_json::{json, Value};
+
+/// Answers a request : `(path, body) -> (status, json body)`.
+type Handler = dyn Fn(&str, &[u8]) -> (&'static str, String) + Send + Sync;
+
+/// Local HTTP server the mock chains are served from, stopped when dropped.
+pub struct MockServer {
+    address: SocketAddr,
+    shutdown: Arc<AtomicBool>,
+}
+
+pub struct RpcError(pub i64, pub &'static str);
+
+pub type RpcResult = Result<Value, RpcError>;
+
+impl MockServer {
+    pub fn start(handler: impl Fn(&str, &[u8]) -> (&'static str, String) + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind the mock server");
+        let address = listener.local_addr().unwrap();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let server_shutdown = shutdown.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if server_shutdown.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Ok(stream) = stream else { continue };
+                let handler = handler.clone();
+                thread::spawn(move || serve(stream, handler.as_ref()));
+            }
+        });
+
+        Self { address, shutdown }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        // Wakes up the listener so that it sees the flag.
+        self.shutdown.store(true, Ordering::Relaxed);
+        let _ = TcpStream::connect(self.address);
+    }
+}
+
+/// Minimal HTTP/1.1 keep-alive loop : one request per `POST`.
+fn serve(stream: TcpStream, handler: &Handler) {
+    let Ok(read_half) = stream.try_clone() else { return };
+    let mut reader = BufReader::new(read_half);
+    let mut stream = stream;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 {
+            return;
+        }
+        let path = line.split_whitespace().nth(1).unwrap_or_default().to_string();
+        let mut content_length = 0;
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                return;
+            }
+            match line.trim_end().split_once(':') {
+                Some((name, value)) if name.eq_ignore_ascii_case("content-length") => {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+                Some(_) => {}
+                None => break,
+            }
+        }
+        let mut body = vec![0; content_length];
+        if reader.read_exact(&mut body).is_err() {
+            return;
+        }
+
+        let (status, response) = handler(&path, &body);
+        let written = write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            response.len(),
+            response
+        );
+        if written.is_err() {
+            return;
+        }
+    }
+}
+
+/// JSON-RPC envelope around `handle(method, params)`.
+pub fn rpc_response(body: &[u8], handle: impl FnOnce(&Value, &Value) -> RpcResult) -> String {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(request) => match handle(&request["method"], &request["params"]) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+            Err(RpcError(code, message)) => {
+                json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": code, "message": message } })
+            }
+        },
+        Err(_) => json!({ "jsonrpc": "2.0", "id": null, "error": { "code": -32700, "message": "Parse error" } }),
+    }
+    .to_string()
+}
+
+/// Positional or named JSON-RPC param.
+pub fn param<'a>(params: &'a Value, index: usize, name: &str) -> &'a Value {
+    match params {
+        Value::Array(params) => params.get(index).unwrap_or(&Value::Null),
+        params => &params[name],
+    }
+}
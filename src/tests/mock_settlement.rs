@@ -0,0 +1,120 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use starknet::core::types::Felt;
+
+use crate::contract_clients::governance::GovernanceNomination;
+use crate::settlement::{SettlementCoreContract, SettlementEthBridge, SettlementLayer, SettlementTokenBridge};
+use crate::{BootstrapperOutput, SettlementLayerKind};
+
+pub const MOCK_CORE_CONTRACT_ADDRESS: Felt = Felt::from_hex_unchecked("0xc0");
+pub const MOCK_ETH_BRIDGE_ADDRESS: Felt = Felt::from_hex_unchecked("0xe1");
+pub const MOCK_TOKEN_BRIDGE_ADDRESS: Felt = Felt::from_hex_unchecked("0x70");
+pub const MOCK_MANAGER_ADDRESS: Felt = Felt::from_hex_unchecked("0x71");
+pub const MOCK_REGISTRY_ADDRESS: Felt = Felt::from_hex_unchecked("0x72");
+pub const MOCK_TEST_TOKEN_ADDRESS: Felt = Felt::from_hex_unchecked("0x73");
+
+/// Call received by the [`MockSettlement`] or one of its bridges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettlementCall {
+    DeployCoreContract,
+    DeployEthBridge,
+    DeployTokenBridge,
+    LinkEthBridge { l2_bridge: Felt },
+    LinkTokenBridge { l2_bridge: Felt },
+}
+
+/// In-memory settlement layer : the contracts get fixed addresses, the calls are recorded and no
+/// message is ever sent.
+#[derive(Clone, Default)]
+pub struct MockSettlement {
+    calls: Arc<Mutex<Vec<SettlementCall>>>,
+}
+
+impl MockSettlement {
+    pub fn calls(&self) -> Vec<SettlementCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn record(&self, call: SettlementCall) {
+        self.calls.lock().unwrap().push(call);
+    }
+}
+
+#[async_trait(?Send)]
+impl SettlementLayer for MockSettlement {
+    fn kind(&self) -> SettlementLayerKind {
+        SettlementLayerKind::Ethereum
+    }
+
+    fn config_address(&self, address: Felt) -> String {
+        address.to_hex_string()
+    }
+
+    async fn deploy_core_contract(&self) -> color_eyre::Result<SettlementCoreContract> {
+        self.record(SettlementCall::DeployCoreContract);
+        Ok(SettlementCoreContract {
+            address: MOCK_CORE_CONTRACT_ADDRESS,
+            implementation_address: None,
+            output: BootstrapperOutput::default(),
+        })
+    }
+
+    async fn deploy_eth_bridge(&self) -> color_eyre::Result<Box<dyn SettlementEthBridge>> {
+        self.record(SettlementCall::DeployEthBridge);
+        Ok(Box::new(MockEthBridge { settlement: self.clone() }))
+    }
+
+    async fn deploy_token_bridge(&self) -> color_eyre::Result<Box<dyn SettlementTokenBridge>> {
+        self.record(SettlementCall::DeployTokenBridge);
+        Ok(Box::new(MockTokenBridge { settlement: self.clone() }))
+    }
+}
+
+struct MockEthBridge {
+    settlement: MockSettlement,
+}
+
+struct MockTokenBridge {
+    settlement: MockSettlement,
+}
+
+#[async_trait(?Send)]
+impl SettlementEthBridge for MockEthBridge {
+    fn address(&self) -> Felt {
+        MOCK_ETH_BRIDGE_ADDRESS
+    }
+
+    async fn link_l2_bridge(&self, l2_bridge: Felt) -> color_eyre::Result<()> {
+        self.settlement.record(SettlementCall::LinkEthBridge { l2_bridge });
+        Ok(())
+    }
+
+    fn governance_nominations(&self) -> Vec<GovernanceNomination> {
+        vec![]
+    }
+}
+
+#[async_trait(?Send)]
+impl SettlementTokenBridge for MockTokenBridge {
+    fn address(&self) -> Felt {
+        MOCK_TOKEN_BRIDGE_ADDRESS
+    }
+
+    fn manager_address(&self) -> Felt {
+        MOCK_MANAGER_ADDRESS
+    }
+
+    fn registry_address(&self) -> Felt {
+        MOCK_REGISTRY_ADDRESS
+    }
+
+    fn test_token_address(&self) -> Felt {
+        MOCK_TEST_TOKEN_ADDRESS
+    }
+
+    async fn link_l2_bridge(&self, l2_bridge: Felt) -> color_eyre::Result<()> {
+        self.settlement.record(SettlementCall::LinkTokenBridge { l2_bridge });
+        Ok(())
+    }
+}
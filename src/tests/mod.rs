@@ -1,43 +1,65 @@
 pub mod constants;
+mod devnet;
 mod erc20_bridge;
 mod eth_bridge;
+mod export;
+mod genesis;
+mod guardrails;
+mod ledger;
+mod legacy_declaration;
+mod mock_l1;
+mod mock_l2;
+mod mock_rpc;
+mod mock_settlement;
+mod operators;
+mod preflight;
+mod proxy_upgrade;
+mod setup_scripts;
+mod test_env;
+mod update_core_contract;
 
 use std::future::Future;
-use std::process::Command;
+use std::sync::Once;
 use std::time::Duration;
-use std::{env, fs};
 
 use rstest::rstest;
 use url::Url;
 
 use crate::contract_clients::config::Clients;
+use crate::contract_clients::eth_bridge::StarknetLegacyEthBridge;
+use crate::contract_clients::token_bridge::StarknetTokenBridge;
+use crate::settlement::address_from_felt;
+use crate::setup_scripts::erc20_bridge::Erc20BridgeSetupOutput;
+use crate::setup_scripts::eth_bridge::EthBridgeSetupOutput;
+use crate::steps::Step;
 use crate::tests::erc20_bridge::erc20_bridge_test_helper;
 use crate::tests::eth_bridge::eth_bridge_test_helper;
-use crate::{bootstrap, setup_core_contract, setup_l2, BootstrapperOutput, ConfigFile};
-
-async fn test_setup(args: &ConfigFile, clients: &Clients) -> BootstrapperOutput {
-    // Setup L1 (core contract)
-    let core_contract_client = setup_core_contract(args, clients).await;
+use crate::tests::test_env::TestEnv;
+use crate::utils::artifacts::init_artifact_registry;
+use crate::{Bootstrapper, BootstrapperOutput, ConfigFile};
+
+/// Uses the artifacts without checking them against the manifest, the tests run on whatever
+/// `make artifacts` built. The registry is process wide, every test which loads an artifact must
+/// call this first.
+pub(crate) fn unverified_artifacts() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| init_artifact_registry(None, false).expect("Artifact registry initialised before the tests"));
+}
 
-    let core_contract_address = core_contract_client.core_contract_client.address();
-    let core_contract_implementation_address = core_contract_client.core_contract_client.implementation_address();
+/// Runs the core contract step, starts Madara on it and runs the L2 steps.
+async fn test_setup(env: &mut TestEnv) -> color_eyre::Result<(Bootstrapper, BootstrapperOutput)> {
+    let mut bootstrapper = Bootstrapper::builder(env.config()).build().await?;
 
-    // Create a new config with the core contract addresses
-    let mut config = get_test_config_file();
-    config.core_contract_address = Some(format!("{:?}", core_contract_address));
-    config.core_contract_implementation_address = Some(format!("{:?}", core_contract_implementation_address));
+    // Setup L1 (core contract), its addresses are written to the bootstrapper config
+    let core_output = bootstrapper.run_steps(&[Step::CoreContract]).await?;
 
-    ensure_toolchain().expect("Not able to ensure toolchain exists.");
-    wait_for_madara().await.expect("Failed to start madara!");
+    env.start_madara().await?;
 
     // Setup L2 with the updated config
-    let l2_output = setup_l2(&mut config, clients).await;
+    let l2_steps: Vec<Step> = Step::ALL.into_iter().filter(|step| *step != Step::CoreContract).collect();
+    let l2_output = bootstrapper.run_steps(&l2_steps).await?;
 
-    BootstrapperOutput {
-        starknet_contract_address: Some(core_contract_address),
-        starknet_contract_implementation_address: Some(core_contract_implementation_address),
-        ..l2_output
-    }
+    Ok((bootstrapper, core_output.merge(l2_output)))
 }
 
 #[rstest]
@@ -45,9 +67,8 @@ async fn test_setup(args: &ConfigFile, clients: &Clients) -> BootstrapperOutput
 #[ignore = "ignored because we have a e2e test, and this is for a local test"]
 async fn deploy_bridge() -> Result<(), anyhow::Error> {
     env_logger::init();
-    let mut config = get_test_config_file();
-    let clients = Clients::init_from_config(&config).await;
-    bootstrap(&mut config, &clients).await;
+    let mut bootstrapper = Bootstrapper::builder(get_test_config_file()).build().await?;
+    bootstrapper.run_steps(&Step::ALL).await?;
 
     Ok(())
 }
@@ -57,17 +78,16 @@ async fn deploy_bridge() -> Result<(), anyhow::Error> {
 #[ignore = "ignored because we have a e2e test, and this is for a local test"]
 async fn deposit_and_withdraw_eth_bridge() -> Result<(), anyhow::Error> {
     env_logger::init();
-    let mut config = get_test_config_file();
-    let clients = Clients::init_from_config(&config).await;
-    let out = bootstrap(&mut config, &clients).await;
+    let mut bootstrapper = Bootstrapper::builder(get_test_config_file()).build().await?;
+    let out = bootstrapper.run_steps(&Step::ALL).await?;
     let eth_bridge_setup = out.eth_bridge_setup_outputs.unwrap();
 
     let _ = eth_bridge_test_helper(
-        &clients,
-        &config,
+        bootstrapper.clients(),
+        bootstrapper.config(),
         eth_bridge_setup.l2_eth_proxy_address,
         eth_bridge_setup.l2_eth_bridge_proxy_address,
-        eth_bridge_setup.l1_bridge,
+        l1_eth_bridge(bootstrapper.clients(), &eth_bridge_setup),
     )
     .await;
 
@@ -79,16 +99,15 @@ async fn deposit_and_withdraw_eth_bridge() -> Result<(), anyhow::Error> {
 #[ignore = "ignored because we have a e2e test, and this is for a local test"]
 async fn deposit_and_withdraw_erc20_bridge() -> Result<(), anyhow::Error> {
     env_logger::init();
-    let mut config = get_test_config_file();
-    let clients = Clients::init_from_config(&config).await;
-    let out = bootstrap(&mut config, &clients).await;
+    let mut bootstrapper = Bootstrapper::builder(get_test_config_file()).build().await?;
+    let out = bootstrapper.run_steps(&Step::ALL).await?;
     let eth_token_setup = out.erc20_bridge_setup_outputs.unwrap();
 
     let _ = erc20_bridge_test_helper(
-        &clients,
-        &config,
+        bootstrapper.clients(),
+        bootstrapper.config(),
         eth_token_setup.test_erc20_token_address,
-        eth_token_setup.token_bridge,
+        l1_token_bridge(bootstrapper.clients(), &eth_token_setup),
         eth_token_setup.l2_token_bridge,
     )
     .await;
@@ -98,33 +117,29 @@ async fn deposit_and_withdraw_erc20_bridge() -> Result<(), anyhow::Error> {
 
 #[rstest]
 #[tokio::test]
-async fn deposit_tests_both_bridges() -> Result<(), anyhow::Error> {
+async fn deposit_tests_both_bridges() -> color_eyre::Result<()> {
     env_logger::init();
-    let config = get_test_config_file();
-
-    // This will kill the madara when this test fails/passes
-    let _port_killer = PortKiller;
-
-    let clients = Clients::init_from_config(&config).await;
-    let out = test_setup(&config, &clients).await;
+    // anvil and madara are killed when the environment is dropped, even if the test fails
+    let mut env = TestEnv::start().await?;
+    let (bootstrapper, out) = test_setup(&mut env).await?;
 
     let eth_bridge_setup = out.eth_bridge_setup_outputs.unwrap();
     let eth_token_setup = out.erc20_bridge_setup_outputs.unwrap();
 
     let _ = eth_bridge_test_helper(
-        &clients,
-        &config,
+        bootstrapper.clients(),
+        bootstrapper.config(),
         eth_bridge_setup.l2_eth_proxy_address,
         eth_bridge_setup.l2_eth_bridge_proxy_address,
-        eth_bridge_setup.l1_bridge,
+        l1_eth_bridge(bootstrapper.clients(), &eth_bridge_setup),
     )
     .await;
 
     let _ = erc20_bridge_test_helper(
-        &clients,
-        &config,
+        bootstrapper.clients(),
+        bootstrapper.config(),
         eth_token_setup.test_erc20_token_address,
-        eth_token_setup.token_bridge,
+        l1_token_bridge(bootstrapper.clients(), &eth_token_setup),
         eth_token_setup.l2_token_bridge,
     )
     .await;
@@ -132,79 +147,24 @@ async fn deposit_tests_both_bridges() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-// Create a struct that will kill the process on port when dropped
-struct PortKiller;
-impl Drop for PortKiller {
-    fn drop(&mut self) {
-        kill_process_on_port(19944);
-    }
+fn l1_eth_bridge(clients: &Clients, output: &EthBridgeSetupOutput) -> StarknetLegacyEthBridge {
+    StarknetLegacyEthBridge::at(address_from_felt(output.l1_bridge_address), clients.eth_client().signer().clone())
 }
 
-fn kill_process_on_port(port: u16) {
-    Command::new("sh")
-        .arg("-c")
-        .arg(format!("lsof -i :{} | grep LISTEN | awk '{{print $2}}' | xargs kill -9", port))
-        .output()
-        .expect("Failed to execute command");
+fn l1_token_bridge(clients: &Clients, output: &Erc20BridgeSetupOutput) -> StarknetTokenBridge {
+    StarknetTokenBridge::at(
+        address_from_felt(output.l1_manager_address),
+        address_from_felt(output.l1_registry_address),
+        address_from_felt(output.l1_token_bridge_proxy),
+        address_from_felt(output.l1_test_token_address),
+        clients.eth_client().signer().clone(),
+    )
 }
 
 fn get_test_config_file() -> ConfigFile {
     ConfigFile::default()
 }
 
-async fn wait_for_madara() -> color_eyre::Result<()> {
-    env::set_current_dir("madara").expect("madara folder doesn't exist.");
-    fs::create_dir_all("../madara-dbs").expect("unable to create folders");
-    env::set_var("RUST_LOG", "info");
-
-    Command::new("cargo")
-        .arg("+1.81")
-        .arg("run")
-        .arg("--release")
-        .arg("--")
-        .arg("--name")
-        .arg("madara")
-        .arg("--base-path")
-        .arg("../madara-dbs/madara_pathfinder_test_11")
-        .arg("--rpc-port")
-        .arg("19944")
-        .arg("--rpc-cors")
-        .arg("*")
-        .arg("--rpc-external")
-        .arg("--sequencer")
-        .arg("--chain-config-path")
-        .arg("configs/presets/devnet.yaml")
-        .arg("--feeder-gateway-enable")
-        .arg("--gateway-enable")
-        .arg("--gateway-external")
-        .arg("--gas-price")
-        .arg("0")
-        .arg("--blob-gas-price")
-        .arg("0")
-        .arg("--rpc-admin")
-        .arg("--rpc-admin-port")
-        .arg("19943")
-        .arg("--l1-endpoint")
-        .arg("http://localhost:8545")
-        .spawn()?;
-
-    env::set_current_dir("../").expect("Navigate back failed.");
-
-    wait_for_madara_to_be_ready(Url::parse("http://localhost:19944")?).await?;
-
-    Ok(())
-}
-
-fn ensure_toolchain() -> color_eyre::Result<()> {
-    let output = Command::new("rustup").arg("toolchain").arg("list").output()?;
-
-    let output_str = String::from_utf8_lossy(&output.stdout);
-    if !output_str.contains("1.81") {
-        Command::new("rustup").arg("install").arg("1.81").status()?;
-    }
-    Ok(())
-}
-
 pub async fn wait_for_madara_to_be_ready(rpc_url: Url) -> color_eyre::Result<()> {
     // We are fine with `expect` here as this function is called in the intial phases of the
     // program execution
@@ -0,0 +1,23 @@
+use serde_json::json;
+
+use crate::contract_clients::core_contract::block_ranges;
+use crate::steps::apply_manifest;
+use crate::ConfigFile;
+
+#[test]
+fn operator_logs_are_paged_from_the_deployment_block() {
+    assert_eq!(block_ranges(100, 25_000, 10_000), vec![(100, 10_099), (10_100, 20_099), (20_100, 25_000)]);
+    assert_eq!(block_ranges(7, 7, 10_000), vec![(7, 7)]);
+    assert!(block_ranges(8, 7, 10_000).is_empty());
+}
+
+#[test]
+fn deployment_block_is_read_from_the_manifest() {
+    let mut config = ConfigFile::default();
+    apply_manifest(&mut config, &json!({ "starknet_contract_deployment_block": 1234 }));
+    assert_eq!(config.core_contract_deployment_block, Some(1234));
+
+    let mut config = ConfigFile { core_contract_deployment_block: Some(1), ..ConfigFile::default() };
+    apply_manifest(&mut config, &json!({ "starknet_contract_deployment_block": 1234 }));
+    assert_eq!(config.core_contract_deployment_block, Some(1));
+}
@@ -0,0 +1,107 @@
+//! Preflight checks against the mock appchain RPC.
+
+use starknet::core::types::Felt;
+use starknet_core::types::contract::SierraClass;
+
+use crate::contract_clients::config::Clients;
+use crate::contract_clients::deployer::{DeploymentMethod, L2DeployerAccount};
+use crate::preflight::{preflight, CheckStatus, PreflightReport};
+use crate::steps::Step;
+use crate::tests::constants::L2_DEPLOYER_ADDRESS;
+use crate::tests::mock_l2::MockL2;
+use crate::utils::artifacts::load_json_artifact;
+use crate::utils::constants::{OZ_ACCOUNT_SIERRA_PATH, STARKNET_UDC_ADDRESS};
+use crate::ConfigFile;
+
+fn status(report: &PreflightReport, name: &str) -> CheckStatus {
+    report.checks.iter().find(|check| check.name == name).unwrap_or_else(|| panic!("No `{name}` check")).status
+}
+
+#[tokio::test]
+async fn preflight_reports_the_wrong_chain_and_the_missing_contracts() {
+    let l2 = MockL2::start();
+    let config = ConfigFile {
+        l2_deployer_address: Some(L2_DEPLOYER_ADDRESS.to_string()),
+        udc_address: Some("0x1234".to_string()),
+        ..l2.config()
+    };
+    let clients = Clients::init_from_config(&config).await.unwrap();
+
+    let report = preflight(&config, &clients, &[Step::Udc]).await;
+
+    assert!(!report.passed());
+    // The mock answers with `SN_SEPOLIA`.
+    assert_eq!(status(&report, "l2_rpc"), CheckStatus::Failed);
+    assert_eq!(status(&report, "l1_deployer_balance"), CheckStatus::Passed);
+    assert_eq!(status(&report, "artifacts"), CheckStatus::Passed);
+    assert_eq!(status(&report, "declare_v0"), CheckStatus::Passed);
+    assert_eq!(status(&report, "contract.udc_address"), CheckStatus::Failed);
+}
+
+#[tokio::test]
+async fn preflight_skips_the_l2_without_l2_step() {
+    let l2 = MockL2::start();
+    // `app_chain_id` doesn't match the mock, the L2 check would fail.
+    let config = l2.config();
+    let clients = Clients::init_from_config(&config).await.unwrap();
+
+    for steps in [&[Step::CoreContract][..], &[]] {
+        let report = preflight(&config, &clients, steps).await;
+
+        assert!(report.checks.iter().all(|check| check.name != "l2_rpc"), "{report}");
+    }
+}
+
+#[tokio::test]
+async fn preflight_passes_on_the_expected_chain() {
+    let l2 = MockL2::start();
+    let config = ConfigFile { app_chain_id: "SN_SEPOLIA".to_string(), ..l2.config() };
+    let clients = Clients::init_from_config(&config).await.unwrap();
+
+    let report = preflight(&config, &clients, &[Step::Argent]).await;
+
+    assert_eq!(status(&report, "l2_rpc"), CheckStatus::Passed);
+    // The legacy OZ account class of the deployer is declared through the admin endpoint of the mock.
+    assert_eq!(status(&report, "declare_v0"), CheckStatus::Passed);
+}
+
+#[tokio::test]
+async fn preflight_checks_the_genesis_classes_in_cairo1_only_mode() {
+    let l2 = MockL2::start();
+    let config = ConfigFile { app_chain_id: "SN_SEPOLIA".to_string(), cairo1_only: Some(true), ..l2.config() };
+    let clients = Clients::init_from_config(&config).await.unwrap();
+
+    let report = preflight(&config, &clients, &[Step::Udc]).await;
+
+    // No legacy class to declare, but neither the OZ account class nor the UDC are in genesis.
+    assert_eq!(status(&report, "declare_v0"), CheckStatus::Passed);
+    assert_eq!(status(&report, "cairo1_only"), CheckStatus::Failed);
+
+    let oz_account_class = load_json_artifact::<SierraClass>(OZ_ACCOUNT_SIERRA_PATH).unwrap();
+    l2.declare_class(oz_account_class.class_hash().unwrap());
+    l2.deploy_at(Felt::from_hex(STARKNET_UDC_ADDRESS).unwrap(), Felt::from(0xdc1u64));
+
+    let report = preflight(&config, &clients, &[Step::Udc]).await;
+
+    assert_eq!(status(&report, "cairo1_only"), CheckStatus::Passed);
+}
+
+#[tokio::test]
+async fn preflight_checks_the_udc_of_an_existing_deployer_account() {
+    let l2 = MockL2::start();
+    let config = ConfigFile {
+        app_chain_id: "SN_SEPOLIA".to_string(),
+        l2_deployer_address: Some(L2_DEPLOYER_ADDRESS.to_string()),
+        l2_deployer_account: Some(L2DeployerAccount::Braavos),
+        l2_deployment_method: Some(DeploymentMethod::Udc),
+        ..l2.config()
+    };
+    let clients = Clients::init_from_config(&config).await.unwrap();
+
+    let report = preflight(&config, &clients, &[Step::Argent]).await;
+    assert_eq!(status(&report, "l2_deployer"), CheckStatus::Failed);
+
+    l2.deploy_at(DeploymentMethod::Udc.default_udc_address(), Felt::from(0xdc1u64));
+    let report = preflight(&config, &clients, &[Step::Argent]).await;
+    assert_eq!(status(&report, "l2_deployer"), CheckStatus::Passed);
+}
@@ -0,0 +1,85 @@
+//! Proxy upgrades against the mock L1.
+
+use ethers::abi::{encode, Token};
+use ethers::types::{Address, Bytes, U256};
+
+use crate::contract_clients::proxy_upgrade::{
+    build_upgrade_calldata, ImplementationSource, L1ProxyUpgrader, PostUpgradeCall, ProxyUpgrade,
+};
+use crate::tests::mock_l1::MockL1;
+
+const PROXY: Address = Address::repeat_byte(0xc0);
+const IMPLEMENTATION: Address = Address::repeat_byte(0x11);
+const EIC: Address = Address::repeat_byte(0xe1);
+
+fn upgrade() -> ProxyUpgrade {
+    ProxyUpgrade {
+        proxy_address: PROXY,
+        implementation: ImplementationSource::Deployed(IMPLEMENTATION),
+        eic: Some(ImplementationSource::Deployed(EIC)),
+        init_data: vec![0xab; 32],
+        finalize: false,
+        post_upgrade: vec![PostUpgradeCall {
+            description: "registerAppGovernor".to_string(),
+            calldata: Bytes::from(vec![0x12, 0x34, 0x56, 0x78]),
+        }],
+    }
+}
+
+fn upgrade_args() -> Vec<u8> {
+    let calldata = build_upgrade_calldata(Some(EIC), &[0xab; 32]);
+    encode(&[Token::Address(IMPLEMENTATION), Token::Bytes(calldata.to_vec()), Token::Bool(false)])
+}
+
+#[tokio::test]
+async fn proxy_without_activation_delay_is_upgraded_right_away() {
+    let l1 = MockL1::start();
+    l1.set_call_result(PROXY, "implementation()", &[Token::Address(IMPLEMENTATION)]);
+
+    let prepared = L1ProxyUpgrader::new(l1.client()).upgrade(&upgrade()).await.unwrap();
+
+    assert!(prepared.upgraded);
+    let calls = l1.calls_to(PROXY);
+    assert_eq!(calls.len(), 3);
+    assert!(calls[0].is(PROXY, "addImplementation(address,bytes,bool)"));
+    assert_eq!(calls[0].args(), upgrade_args());
+    assert!(calls[1].is(PROXY, "upgradeTo(address,bytes,bool)"));
+    assert_eq!(calls[1].args(), upgrade_args());
+    assert_eq!(calls[2].data, upgrade().post_upgrade[0].calldata);
+    assert!(calls.iter().all(|call| call.from == l1.client().address()));
+}
+
+#[tokio::test]
+async fn proxy_with_activation_delay_is_not_upgraded_before_activation() {
+    let l1 = MockL1::start();
+    l1.set_call_result(PROXY, "getUpgradeActivationDelay()", &[Token::Uint(U256::from(3600))]);
+    let upgrader = L1ProxyUpgrader::new(l1.client());
+
+    let mut prepared = upgrader.upgrade(&upgrade()).await.unwrap();
+
+    assert!(!prepared.upgraded);
+    assert_eq!(prepared.activation_timestamp, l1.timestamp() + 3600);
+    assert_eq!(l1.calls_to(PROXY).len(), 1);
+
+    let err = upgrader.upgrade_to(&mut prepared).await.unwrap_err().to_string();
+    assert!(err.contains("is not enabled yet"), "{err}");
+    assert!(!prepared.upgraded);
+    assert_eq!(l1.calls_to(PROXY).len(), 1);
+}
+
+#[tokio::test]
+async fn post_upgrade_calls_are_not_sent_when_the_proxy_is_not_upgraded() {
+    let l1 = MockL1::start();
+    // The proxy doesn't switch to the new implementation.
+    l1.set_call_result(PROXY, "implementation()", &[Token::Address(Address::zero())]);
+    let upgrader = L1ProxyUpgrader::new(l1.client());
+
+    let mut prepared = upgrader.add_implementation(&upgrade()).await.unwrap();
+    let err = upgrader.upgrade_to(&mut prepared).await.unwrap_err().to_string();
+
+    assert!(err.contains("after upgrade"), "{err}");
+    assert!(!prepared.upgraded);
+    let calls = l1.calls_to(PROXY);
+    assert_eq!(calls.len(), 2);
+    assert!(calls[1].is(PROXY, "upgradeTo(address,bytes,bool)"));
+}
@@ -0,0 +1,550 @@
+//! Setup scripts against the in-memory settlement layer and the mock appchain and L1 RPCs. The
+//! clock is paused so the waits of the scripts are skipped. The L1 scripts go through the client of
+//! [`Clients`], which polls the pending transactions every 7 secs of real time.
+
+use std::str::FromStr;
+
+use ethers::abi::{encode, Token};
+use ethers::types::Address;
+use ethers::utils::get_contract_address;
+use starknet::accounts::Account;
+use starknet::core::types::contract::legacy::LegacyContractClass;
+use starknet::core::types::{Event, Felt};
+use starknet::core::utils::get_selector_from_name;
+use starknet_core::types::contract::SierraClass;
+use starknet_core::utils::{get_udc_deployed_address, UdcUniqueness};
+
+use crate::contract_clients::config::Clients;
+use crate::contract_clients::core_contract::{get_calldata_bytes, get_init_data_core_contract, CoreContract};
+use crate::contract_clients::deployer::{contract_deployed_address, Deployer, DeploymentMethod, L2DeployerAccount};
+use crate::contract_clients::utils::{build_single_owner_account, get_bridge_init_configs, RpcAccount};
+use crate::setup_scripts::account_setup::account_init;
+use crate::setup_scripts::argent::ArgentSetup;
+use crate::setup_scripts::braavos::BraavosSetup;
+use crate::setup_scripts::core_contract::CoreContractStarknetL1;
+use crate::setup_scripts::erc20_bridge::Erc20Bridge;
+use crate::setup_scripts::eth_bridge::EthBridge;
+use crate::setup_scripts::udc::UdcSetup;
+use crate::setup_scripts::upgrade_token_bridge::upgrade_token_bridge;
+use crate::tests::constants::{L1_DEPLOYER_ADDRESS, L2_DEPLOYER_ADDRESS};
+use crate::tests::mock_l1::MockL1;
+use crate::tests::mock_l2::MockL2;
+use crate::tests::mock_settlement::{
+    MockSettlement, SettlementCall, MOCK_ETH_BRIDGE_ADDRESS, MOCK_MANAGER_ADDRESS, MOCK_REGISTRY_ADDRESS,
+    MOCK_TEST_TOKEN_ADDRESS, MOCK_TOKEN_BRIDGE_ADDRESS,
+};
+use crate::utils::artifacts::load_json_artifact;
+use crate::utils::constants::{
+    ARGENT_ACCOUNT_SIERRA_PATH, BRAAVOS_ACCOUNT_SIERRA_PATH, BRAAVOS_AGGREGATOR_PATH, BRAAVOS_BASE_ACCOUNT_SIERRA_PATH,
+    EIC_ETH_BRIDGE_SIERRA_PATH, ERC20_SIERRA_PATH, NEW_ETH_BRIDGE_SIERRA_PATH, NEW_ETH_TOKEN_SIERRA_PATH,
+    OZ_ACCOUNT_PATH, OZ_ACCOUNT_SIERRA_PATH, TOKEN_BRIDGE_SIERRA_PATH, UDC_PATH,
+};
+use crate::{Bootstrapper, BootstrapperError, ConfigFile, CoreContractMode};
+
+async fn mock_account<'a>(clients: &'a Clients, config: &ConfigFile) -> RpcAccount<'a> {
+    build_single_owner_account(clients.provider_l2(), &config.rollup_priv_key, L2_DEPLOYER_ADDRESS, false).await
+}
+
+fn sierra_class_hash(path: &str) -> Felt {
+    load_json_artifact::<SierraClass>(path).unwrap().class_hash().unwrap()
+}
+
+fn legacy_class_hash(path: &str) -> Felt {
+    load_json_artifact::<LegacyContractClass>(path).unwrap().class_hash().unwrap()
+}
+
+fn class_hashes(deployments: &[(Felt, Felt)]) -> Vec<Felt> {
+    deployments.iter().map(|(_, class_hash)| *class_hash).collect()
+}
+
+#[tokio::test(start_paused = true)]
+async fn udc_setup_declares_and_deploys_the_udc() {
+    let l2 = MockL2::start();
+    let config = l2.config();
+    let clients = Clients::init_from_config(&config).await.unwrap();
+    let account = mock_account(&clients, &config).await;
+
+    let output =
+        UdcSetup::new(Deployer::from_config(account.clone(), &config).unwrap(), &clients).setup().await.unwrap();
+
+    let udc_class_hash = legacy_class_hash(UDC_PATH);
+    assert_eq!(output.udc_class_hash, udc_class_hash);
+    assert_eq!(l2.legacy_declarations().len(), 1);
+
+    let calls = l2.calls();
+    assert_eq!(calls.len(), 1);
+    assert!(calls[0].is(account.address(), "deploy_contract"));
+    assert_eq!(calls[0].calldata, vec![udc_class_hash, Felt::ZERO, Felt::ONE, Felt::ZERO]);
+    assert_eq!(l2.deployments(), vec![(output.udc_address, udc_class_hash)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn eth_bridge_setup_links_the_settlement_bridge() {
+    let l2 = MockL2::start();
+    let config = l2.config();
+    let clients = Clients::init_from_config(&config).await.unwrap();
+    let account = mock_account(&clients, &config).await;
+    let settlement = MockSettlement::default();
+
+    let output =
+        EthBridge::new(account.clone(), account.address(), &config, &clients, &settlement).setup().await.unwrap();
+
+    assert_eq!(
+        settlement.calls(),
+        vec![
+            SettlementCall::DeployEthBridge,
+            SettlementCall::LinkEthBridge { l2_bridge: output.l2_eth_bridge_proxy_address }
+        ]
+    );
+    assert_eq!(output.l1_bridge_address, MOCK_ETH_BRIDGE_ADDRESS);
+    assert!(output.governance_nominations.is_empty());
+
+    // proxy, starkgate proxy, legacy ERC20 and legacy bridge
+    assert_eq!(l2.legacy_declarations().len(), 4);
+    let set_l1_bridge: Vec<_> =
+        l2.calls().into_iter().filter(|call| call.is(output.l2_eth_bridge_proxy_address, "set_l1_bridge")).collect();
+    assert_eq!(set_l1_bridge.len(), 1);
+    assert_eq!(set_l1_bridge[0].calldata, vec![MOCK_ETH_BRIDGE_ADDRESS]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn eth_bridge_setup_deploys_the_cairo_1_classes_in_cairo1_only_mode() {
+    let l2 = MockL2::start();
+    let config = ConfigFile { cairo1_only: Some(true), ..l2.config() };
+    let clients = Clients::init_from_config(&config).await.unwrap();
+    let account = mock_account(&clients, &config).await;
+    let settlement = MockSettlement::default();
+
+    let output =
+        EthBridge::new(account.clone(), account.address(), &config, &clients, &settlement).setup().await.unwrap();
+
+    assert!(l2.legacy_declarations().is_empty());
+    let (eth_token_class_hash, eth_bridge_class_hash) =
+        (sierra_class_hash(NEW_ETH_TOKEN_SIERRA_PATH), sierra_class_hash(NEW_ETH_BRIDGE_SIERRA_PATH));
+    assert_eq!(
+        l2.declarations(),
+        vec![eth_token_class_hash, eth_bridge_class_hash, sierra_class_hash(EIC_ETH_BRIDGE_SIERRA_PATH)]
+    );
+    assert_eq!(
+        l2.deployments(),
+        vec![
+            (output.l2_eth_bridge_proxy_address, eth_bridge_class_hash),
+            (output.l2_eth_proxy_address, eth_token_class_hash)
+        ]
+    );
+    assert_eq!(output.l2_eth_token_class_hash, Some(eth_token_class_hash));
+    assert!(output.l2_legacy_proxy_class_hash.is_none());
+    assert_eq!(
+        settlement.calls(),
+        vec![
+            SettlementCall::DeployEthBridge,
+            SettlementCall::LinkEthBridge { l2_bridge: output.l2_eth_bridge_proxy_address }
+        ]
+    );
+
+    let l2_bridge_calls: Vec<_> =
+        l2.calls().into_iter().filter(|call| call.to == output.l2_eth_bridge_proxy_address).collect();
+    let selectors: Vec<_> = l2_bridge_calls.iter().map(|call| call.selector).collect();
+    let expected: Vec<_> = [
+        "register_app_role_admin",
+        "register_app_governor",
+        "register_upgrade_governor",
+        "set_l1_bridge",
+        "add_new_implementation",
+        "replace_to",
+    ]
+    .into_iter()
+    .map(|method| get_selector_from_name(method).unwrap())
+    .collect();
+    assert_eq!(selectors, expected);
+    assert_eq!(l2_bridge_calls[3].calldata, vec![MOCK_ETH_BRIDGE_ADDRESS]);
+    // The ETH token is registered by the EIC.
+    assert_eq!(
+        l2_bridge_calls[5].calldata[4..],
+        [Felt::from_hex_unchecked("0x455448"), output.l2_eth_proxy_address, Felt::ZERO]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn bridges_get_their_roles_from_the_account_with_a_udc_deployment_method() {
+    let l2 = MockL2::start();
+    let config = ConfigFile {
+        cairo1_only: Some(true),
+        l2_deployment_method: Some(DeploymentMethod::Udc),
+        udc_address: Some("0xdc".to_string()),
+        ..l2.config()
+    };
+    let clients = Clients::init_from_config(&config).await.unwrap();
+    let account = mock_account(&clients, &config).await;
+    let settlement = MockSettlement::default();
+
+    let eth_bridge =
+        EthBridge::new(account.clone(), account.address(), &config, &clients, &settlement).setup().await.unwrap();
+    let erc20_bridge =
+        Erc20Bridge::new(account.clone(), account.address(), &config, &clients, &settlement).setup().await.unwrap();
+
+    // The bridges grant their governance admin role to their constructor caller, which registers
+    // the other roles.
+    for bridge in [eth_bridge.l2_eth_bridge_proxy_address, erc20_bridge.l2_token_bridge] {
+        assert_eq!(l2.constructor_caller(bridge), Some(account.address()));
+        let role_calls: Vec<_> = l2
+            .calls()
+            .into_iter()
+            .filter(|call| call.is(bridge, "register_app_role_admin") || call.is(bridge, "register_app_governor"))
+            .collect();
+        assert_eq!(role_calls.len(), 2);
+        assert!(role_calls.iter().all(|call| call.sender == account.address()));
+    }
+    // The ETH token takes its governance admin as a constructor argument.
+    assert_eq!(l2.constructor_caller(eth_bridge.l2_eth_proxy_address), Some(Felt::from(0xdcu64)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn bridges_are_not_deployed_through_the_udc_of_an_existing_account() {
+    let l2 = MockL2::start();
+    let config = ConfigFile {
+        cairo1_only: Some(true),
+        l2_deployer_account: Some(L2DeployerAccount::Argent),
+        l2_deployer_address: Some(L2_DEPLOYER_ADDRESS.to_string()),
+        ..l2.config()
+    };
+    let clients = Clients::init_from_config(&config).await.unwrap();
+    let account = mock_account(&clients, &config).await;
+    let settlement = MockSettlement::default();
+
+    let err = EthBridge::new(account.clone(), account.address(), &config, &clients, &settlement)
+        .setup()
+        .await
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(err.contains("constructor"), "{err}");
+    let err = Erc20Bridge::new(account.clone(), account.address(), &config, &clients, &settlement)
+        .setup()
+        .await
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(err.contains("constructor"), "{err}");
+
+    assert!(l2.declarations().is_empty());
+    assert!(l2.calls().is_empty());
+    assert!(settlement.calls().is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn udc_setup_uses_the_predeployed_udc() {
+    let l2 = MockL2::start();
+    let config = l2.config();
+    let clients = Clients::init_from_config(&config).await.unwrap();
+    let account = mock_account(&clients, &config).await;
+    let (udc_address, udc_class_hash) = (Felt::from(0xdcu64), Felt::from(0xdc1u64));
+    l2.deploy_at(udc_address, udc_class_hash);
+
+    let output = UdcSetup::new(Deployer::from_config(account.clone(), &config).unwrap(), &clients)
+        .predeployed(udc_address)
+        .await
+        .unwrap();
+
+    assert_eq!(output.udc_address, udc_address);
+    assert_eq!(output.udc_class_hash, udc_class_hash);
+    assert!(l2.legacy_declarations().is_empty());
+    assert!(l2.calls().is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn deployer_deploys_through_the_legacy_udc() {
+    let l2 = MockL2::start();
+    let config = ConfigFile {
+        l2_deployer_account: Some(L2DeployerAccount::Argent),
+        udc_address: Some("0xdc".to_string()),
+        ..l2.config()
+    };
+    let clients = Clients::init_from_config(&config).await.unwrap();
+    let account = mock_account(&clients, &config).await;
+    let udc_address = Felt::from(0xdcu64);
+    l2.deploy_at(udc_address, Felt::from(0xdc1u64));
+
+    let deployer = Deployer::from_config(account.clone(), &config).unwrap();
+    assert_eq!(deployer.method(), DeploymentMethod::LegacyUdc);
+    let (class_hash, salt) = (Felt::from(0xc1u64), Felt::from(0x5au64));
+    let address = deployer.deploy(class_hash, salt, true, &[Felt::ONE]).await.unwrap();
+
+    assert_eq!(address, deployer.deployed_address(class_hash, salt, true, &[Felt::ONE]));
+    let calls = l2.calls();
+    assert_eq!(calls.len(), 1);
+    assert!(calls[0].is(udc_address, "deployContract"));
+    assert_eq!(calls[0].calldata, vec![class_hash, salt, Felt::ONE, Felt::ONE, Felt::ONE]);
+    assert_eq!(l2.deployments(), vec![(address, class_hash)]);
+}
+
+#[test]
+fn contract_deployed_address_ignores_the_events_of_other_contracts() {
+    let selector = get_selector_from_name("ContractDeployed").unwrap();
+    let (udc_address, inner_udc_address) = (Felt::from(0xdcu64), Felt::from(0xdeu64));
+    let event = |from_address, address| Event { from_address, keys: vec![selector], data: vec![address] };
+    // The constructor of the deployed contract deploys another one through a second UDC.
+    let events = vec![event(inner_udc_address, Felt::from(0xa1u64)), event(udc_address, Felt::from(0xa2u64))];
+
+    assert_eq!(contract_deployed_address(&events, udc_address).unwrap(), Felt::from(0xa2u64));
+    assert!(contract_deployed_address(&events[..1], udc_address).is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn erc20_bridge_setup_links_the_settlement_bridge() {
+    let l2 = MockL2::start();
+    let config = l2.config();
+    let clients = Clients::init_from_config(&config).await.unwrap();
+    let account = mock_account(&clients, &config).await;
+    let settlement = MockSettlement::default();
+    // Already declared classes are not declared again.
+    l2.declare_class(sierra_class_hash(ERC20_SIERRA_PATH));
+    // Deployed from the account with `[class_hash, salt: 0, unique: 0, calldata: [0]]`.
+    let l2_token_bridge = get_udc_deployed_address(
+        Felt::ZERO,
+        sierra_class_hash(TOKEN_BRIDGE_SIERRA_PATH),
+        &UdcUniqueness::NotUnique,
+        &[Felt::ZERO],
+    );
+    l2.set_call_result(l2_token_bridge, "get_l2_token", vec![Felt::from(0x7e57u64)]);
+
+    let output =
+        Erc20Bridge::new(account.clone(), account.address(), &config, &clients, &settlement).setup().await.unwrap();
+
+    assert_eq!(
+        settlement.calls(),
+        vec![SettlementCall::DeployTokenBridge, SettlementCall::LinkTokenBridge { l2_bridge: output.l2_token_bridge }]
+    );
+    assert_eq!(output.l1_token_bridge_proxy, MOCK_TOKEN_BRIDGE_ADDRESS);
+    assert_eq!(output.l1_manager_address, MOCK_MANAGER_ADDRESS);
+    assert_eq!(output.l1_registry_address, MOCK_REGISTRY_ADDRESS);
+    assert_eq!(output.l1_test_token_address, MOCK_TEST_TOKEN_ADDRESS);
+    assert_eq!(output.l2_token_bridge, l2_token_bridge);
+    assert_eq!(output.test_erc20_token_address, Felt::from(0x7e57u64));
+
+    assert_eq!(l2.declarations(), vec![sierra_class_hash(TOKEN_BRIDGE_SIERRA_PATH)]);
+    let l2_bridge_calls: Vec<_> =
+        l2.calls().into_iter().filter(|call| call.to == output.l2_token_bridge).map(|call| call.selector).collect();
+    let expected: Vec<_> = [
+        "register_app_role_admin",
+        "register_app_governor",
+        "set_l2_token_governance",
+        "set_erc20_class_hash",
+        "set_l1_bridge",
+    ]
+    .into_iter()
+    .map(|method| get_selector_from_name(method).unwrap())
+    .collect();
+    assert_eq!(l2_bridge_calls, expected);
+}
+
+#[tokio::test]
+async fn invalid_config_is_an_error_instead_of_a_panic() {
+    let config = ConfigFile { rollup_seq_url: "not a url".to_string(), ..ConfigFile::default() };
+    let err = Bootstrapper::builder(config).build().await.err().unwrap();
+    assert!(matches!(err, BootstrapperError::InvalidConfig { field: "rollup_seq_url", .. }));
+
+    let l2 = MockL2::start();
+    let config = ConfigFile { core_contract_address: None, ..l2.config() };
+    let bootstrapper = Bootstrapper::builder(config).build().await.unwrap();
+    assert!(matches!(
+        bootstrapper.setup_eth_bridge().await,
+        Err(BootstrapperError::MissingConfig("core_contract_address"))
+    ));
+
+    let config = ConfigFile { udc_address: Some("0xnot_a_felt".to_string()), ..l2.config() };
+    let bootstrapper = Bootstrapper::builder(config).build().await.unwrap();
+    assert!(matches!(
+        bootstrapper.setup_udc().await,
+        Err(BootstrapperError::InvalidConfig { field: "udc_address", .. })
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn account_init_declares_the_oz_account_through_a_temp_account() {
+    let l2 = MockL2::start();
+    let config = l2.config();
+    let clients = Clients::init_from_config(&config).await.unwrap();
+
+    let account = account_init(&clients, &config).await.unwrap();
+
+    let oz_account_class_hash = sierra_class_hash(OZ_ACCOUNT_SIERRA_PATH);
+    assert_eq!(l2.legacy_declarations().len(), 1);
+    assert_eq!(l2.declarations(), vec![oz_account_class_hash]);
+    // The temp account (legacy OZ class) declares the Cairo 1 OZ class the account is deployed with.
+    let deployments = l2.deployments();
+    assert_eq!(class_hashes(&deployments), vec![legacy_class_hash(OZ_ACCOUNT_PATH), oz_account_class_hash]);
+    assert_eq!(deployments[1].0, account.address());
+}
+
+#[tokio::test(start_paused = true)]
+async fn account_init_requires_the_declared_oz_account_in_cairo1_only_mode() {
+    let l2 = MockL2::start();
+    let config = ConfigFile { cairo1_only: Some(true), ..l2.config() };
+    let clients = Clients::init_from_config(&config).await.unwrap();
+
+    let err = account_init(&clients, &config).await.err().unwrap().to_string();
+    assert!(err.contains("is not declared"), "{err}");
+    assert!(l2.deployments().is_empty());
+
+    let oz_account_class_hash = sierra_class_hash(OZ_ACCOUNT_SIERRA_PATH);
+    l2.declare_class(oz_account_class_hash);
+    let account = account_init(&clients, &config).await.unwrap();
+
+    assert!(l2.legacy_declarations().is_empty());
+    assert!(l2.declarations().is_empty());
+    assert_eq!(l2.deployments(), vec![(account.address(), oz_account_class_hash)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn argent_setup_declares_the_argent_class_once() {
+    let l2 = MockL2::start();
+    let config = l2.config();
+    let clients = Clients::init_from_config(&config).await.unwrap();
+    let account = mock_account(&clients, &config).await;
+
+    let output = ArgentSetup::new(account.clone()).setup().await.unwrap();
+    assert_eq!(output.argent_class_hash, sierra_class_hash(ARGENT_ACCOUNT_SIERRA_PATH));
+    assert_eq!(l2.declarations(), vec![output.argent_class_hash]);
+
+    ArgentSetup::new(account).setup().await.unwrap();
+    assert_eq!(l2.declarations().len(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn braavos_setup_declares_the_accounts_and_deploys_the_aggregator() {
+    let l2 = MockL2::start();
+    let config = l2.config();
+    let clients = Clients::init_from_config(&config).await.unwrap();
+    let account = mock_account(&clients, &config).await;
+
+    let output =
+        BraavosSetup::new(Deployer::from_config(account, &config).unwrap(), &clients, false).setup().await.unwrap();
+
+    assert_eq!(output.braavos_class_hash, sierra_class_hash(BRAAVOS_ACCOUNT_SIERRA_PATH));
+    assert_eq!(l2.declarations(), vec![output.braavos_class_hash, sierra_class_hash(BRAAVOS_BASE_ACCOUNT_SIERRA_PATH)]);
+    assert_eq!(l2.legacy_declarations().len(), 1);
+    assert_eq!(class_hashes(&l2.deployments()), vec![legacy_class_hash(BRAAVOS_AGGREGATOR_PATH)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn braavos_setup_skips_the_aggregator_in_cairo1_only_mode() {
+    let l2 = MockL2::start();
+    let config = ConfigFile { cairo1_only: Some(true), ..l2.config() };
+    let clients = Clients::init_from_config(&config).await.unwrap();
+    let account = mock_account(&clients, &config).await;
+
+    BraavosSetup::new(Deployer::from_config(account, &config).unwrap(), &clients, true).setup().await.unwrap();
+
+    assert_eq!(l2.declarations().len(), 2);
+    assert!(l2.legacy_declarations().is_empty());
+    assert!(l2.deployments().is_empty());
+}
+
+#[tokio::test]
+async fn core_contract_setup_initialises_the_proxy_and_nominates_the_multisig() {
+    let l1 = MockL1::start();
+    let config = ConfigFile {
+        eth_rpc: l1.url(),
+        core_contract_mode: CoreContractMode::Production,
+        initial_block_number: Some(7),
+        initial_state_root: Some("0x5".to_string()),
+        initial_block_hash: Some("0xb".to_string()),
+        ..ConfigFile::default()
+    };
+    let clients = Clients::init_from_config(&config).await.unwrap();
+
+    let output = CoreContractStarknetL1::new(&config, &clients).setup().await.unwrap();
+
+    let (core_contract, implementation) =
+        (output.core_contract_client.address(), output.core_contract_client.implementation_address());
+    let deployments = l1.deployments();
+    assert!(deployments.contains(&core_contract) && deployments.contains(&implementation));
+    assert_eq!(output.deployment_block, 0);
+    assert_eq!(output.governance_nominations.len(), 2);
+
+    let (program_hash, config_hash) = get_bridge_init_configs(&config);
+    let verifier = Address::from_str(&config.verifier_address).unwrap();
+    let init_data = get_calldata_bytes(get_init_data_core_contract(
+        Felt::from(7u64),
+        Felt::from(0x5u64),
+        Felt::from(0xbu64),
+        program_hash,
+        config_hash,
+        verifier,
+    ));
+    let upgrade_args = encode(&[Token::Address(implementation), Token::Bytes(init_data.to_vec()), Token::Bool(false)]);
+    let operator = Address::from_str(&config.operator_address).unwrap();
+    let multisig = Address::from_str(&config.l1_multisig_address).unwrap();
+
+    let calls = l1.calls_to(core_contract);
+    assert_eq!(calls.len(), 5);
+    assert!(calls[0].is(core_contract, "addImplementation(address,bytes,bool)"));
+    assert_eq!(calls[0].args(), upgrade_args);
+    assert!(calls[1].is(core_contract, "upgradeTo(address,bytes,bool)"));
+    assert_eq!(calls[1].args(), upgrade_args);
+    assert!(calls[2].is(core_contract, "registerOperator(address)"));
+    assert_eq!(calls[2].args(), encode(&[Token::Address(operator)]));
+    assert!(calls[3].is(core_contract, "starknetNominateNewGovernor(address)"));
+    assert_eq!(calls[3].args(), encode(&[Token::Address(multisig)]));
+    assert!(calls[4].is(core_contract, "proxyNominateNewGovernor(address)"));
+    assert_eq!(calls[4].args(), encode(&[Token::Address(multisig)]));
+}
+
+#[tokio::test]
+async fn token_bridge_upgrade_moves_the_l1_proxies_and_the_l2_bridge_to_the_new_classes() {
+    let (manager, registry, token_bridge, token) = (
+        Address::repeat_byte(0x71),
+        Address::repeat_byte(0x72),
+        Address::repeat_byte(0x70),
+        Address::repeat_byte(0x73),
+    );
+    let (l2_token_bridge, l2_token) = (Felt::from(0xb1u64), Felt::from(0x7e57u64));
+    let l1 = MockL1::start();
+    let l2 = MockL2::start();
+    let config = ConfigFile {
+        eth_rpc: l1.url(),
+        l1_manager_address: Some(format!("{manager:?}")),
+        l1_registry_address: Some(format!("{registry:?}")),
+        l1_token_bridge_address: Some(format!("{token_bridge:?}")),
+        l2_token_bridge_address: Some(l2_token_bridge.to_hex_string()),
+        l1_enrolled_token_addresses: Some(vec![format!("{token:?}")]),
+        ..l2.config()
+    };
+    let clients = Clients::init_from_config(&config).await.unwrap();
+    let account = mock_account(&clients, &config).await;
+
+    // Each proxy upgrade is : deploy the implementation, `addImplementation`, `upgradeTo`.
+    let deployer = Address::from_str(L1_DEPLOYER_ADDRESS).unwrap();
+    let proxies = [manager, registry, token_bridge];
+    let implementations = [0u64, 3, 6].map(|nonce| get_contract_address(deployer, nonce));
+    for (proxy, implementation) in proxies.into_iter().zip(implementations) {
+        l1.set_call_result(proxy, "implementation()", &[Token::Address(implementation)]);
+    }
+    l1.set_call_result(registry, "getBridge(address)", &[Token::Address(token_bridge)]);
+    l2.deploy_at(l2_token_bridge, Felt::from(0xc1a55u64));
+    l2.set_call_result(l2_token_bridge, "get_l2_token", vec![l2_token]);
+
+    let output = upgrade_token_bridge(&account, &config, &clients).await.unwrap();
+
+    assert_eq!(l1.deployments(), implementations);
+    for (proxy, implementation) in proxies.into_iter().zip(implementations) {
+        let calls = l1.calls_to(proxy);
+        assert_eq!(calls.len(), 2);
+        assert!(calls[0].is(proxy, "addImplementation(address,bytes,bool)"));
+        assert!(calls[1].is(proxy, "upgradeTo(address,bytes,bool)"));
+        // The proxies are initialised, only the (zero) EIC address word is sent.
+        let args = encode(&[Token::Address(implementation), Token::Bytes(vec![0; 32]), Token::Bool(false)]);
+        assert_eq!(calls[1].args(), args);
+    }
+    assert!(output.l1_token_bridge_upgrade.upgraded);
+
+    let new_class_hash = sierra_class_hash(TOKEN_BRIDGE_SIERRA_PATH);
+    assert_eq!(output.l2_token_bridge_class_hash, new_class_hash);
+    let l2_calls: Vec<_> = l2.calls().into_iter().filter(|call| call.to == l2_token_bridge).collect();
+    assert_eq!(l2_calls.len(), 3);
+    assert!(l2_calls[0].is(l2_token_bridge, "register_upgrade_governor"));
+    assert!(l2_calls[1].is(l2_token_bridge, "add_new_implementation"));
+    assert!(l2_calls[2].is(l2_token_bridge, "replace_to"));
+    assert_eq!(l2_calls[2].calldata, vec![new_class_hash, Felt::ONE, Felt::ZERO]);
+}
@@ -0,0 +1,121 @@
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::{env, io};
+
+use tempfile::TempDir;
+use url::Url;
+
+use crate::devnet::{spawn_anvil, spawn_madara, wait_for_anvil, MadaraLaunch, ManagedProcess};
+use crate::tests::constants::ETH_PRIV_KEY;
+use crate::tests::{get_test_config_file, unverified_artifacts, wait_for_madara_to_be_ready};
+use crate::ConfigFile;
+
+/// Prebuilt Madara binary, defaults to a release build of the `madara` checkout.
+const MADARA_BIN_ENV: &str = "MADARA_BIN";
+const DEFAULT_MADARA_BIN: &str = "madara/target/release/madara";
+/// Chain config Madara is started with.
+const MADARA_CHAIN_CONFIG_ENV: &str = "MADARA_CHAIN_CONFIG";
+const DEFAULT_MADARA_CHAIN_CONFIG: &str = "madara/configs/presets/devnet.yaml";
+const ANVIL_BIN_ENV: &str = "ANVIL_BIN";
+const DEFAULT_ANVIL_BIN: &str = "anvil";
+
+/// Local chains for the e2e tests : anvil and a Madara sequencer settling on it, on free ports
+/// and with their data in a temporary directory, so that several environments can run side by
+/// side. The processes are killed when the environment is dropped.
+pub struct TestEnv {
+    base_path: TempDir,
+    anvil_port: u16,
+    madara_ports: MadaraPorts,
+    _anvil: ManagedProcess,
+    madara: Option<ManagedProcess>,
+}
+
+struct MadaraPorts {
+    rpc: u16,
+    rpc_admin: u16,
+    gateway: u16,
+}
+
+impl TestEnv {
+    /// Starts anvil. Madara is started with [`TestEnv::start_madara`] once the core contract is
+    /// deployed.
+    pub async fn start() -> color_eyre::Result<Self> {
+        unverified_artifacts();
+        let base_path = tempfile::tempdir()?;
+        let anvil_port = free_port()?;
+        let madara_ports = MadaraPorts { rpc: free_port()?, rpc_admin: free_port()?, gateway: free_port()? };
+
+        let anvil_bin = PathBuf::from(env::var(ANVIL_BIN_ENV).unwrap_or(DEFAULT_ANVIL_BIN.to_string()));
+        let anvil = spawn_anvil(&anvil_bin, anvil_port, base_path.path())?;
+        let env = Self { base_path, anvil_port, madara_ports, _anvil: anvil, madara: None };
+
+        let anvil_url = env.anvil_url();
+        wait_for_anvil(&anvil_url).await?;
+        log::info!("anvil running at {}", anvil_url);
+
+        Ok(env)
+    }
+
+    pub async fn start_madara(&mut self) -> color_eyre::Result<()> {
+        let madara_bin = PathBuf::from(env::var(MADARA_BIN_ENV).unwrap_or(DEFAULT_MADARA_BIN.to_string()));
+        if !madara_bin.exists() {
+            color_eyre::eyre::bail!(
+                "Madara binary not found at {}, build it or set {}",
+                madara_bin.display(),
+                MADARA_BIN_ENV
+            );
+        }
+
+        let chain_config =
+            PathBuf::from(env::var(MADARA_CHAIN_CONFIG_ENV).unwrap_or(DEFAULT_MADARA_CHAIN_CONFIG.to_string()));
+        let madara = spawn_madara(
+            &MadaraLaunch {
+                bin: &madara_bin,
+                base_path: &self.base_path.path().join("madara-db"),
+                chain_config: &chain_config,
+                rpc_port: self.madara_ports.rpc,
+                rpc_admin_port: self.madara_ports.rpc_admin,
+                gateway_port: self.madara_ports.gateway,
+                l1_endpoint: &self.anvil_url(),
+            },
+            self.base_path.path(),
+        )?;
+        self.madara = Some(madara);
+
+        wait_for_madara_to_be_ready(self.madara_rpc_url()).await?;
+        log::info!("madara running at {}", self.madara_rpc_url());
+        Ok(())
+    }
+
+    pub fn anvil_url(&self) -> Url {
+        local_url(self.anvil_port)
+    }
+
+    pub fn madara_rpc_url(&self) -> Url {
+        local_url(self.madara_ports.rpc)
+    }
+
+    pub fn madara_admin_url(&self) -> Url {
+        local_url(self.madara_ports.rpc_admin)
+    }
+
+    /// Test config pointing to the chains of this environment.
+    pub fn config(&self) -> ConfigFile {
+        ConfigFile {
+            eth_rpc: self.anvil_url().to_string(),
+            eth_priv_key: ETH_PRIV_KEY.to_string(),
+            rollup_seq_url: self.madara_rpc_url().to_string(),
+            rollup_declare_v0_seq_url: self.madara_admin_url().to_string(),
+            ..get_test_config_file()
+        }
+    }
+}
+
+/// Port assigned by the OS. It is released right away, the process started on it binds it again.
+fn free_port() -> io::Result<u16> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+fn local_url(port: u16) -> Url {
+    Url::parse(&format!("http://127.0.0.1:{port}")).unwrap()
+}
@@ -0,0 +1,79 @@
+use ethers::abi::{encode, Token};
+use ethers::types::Address;
+
+use crate::contract_clients::proxy_upgrade::{build_upgrade_calldata, ImplementationSource};
+use crate::contract_clients::utils::get_bridge_init_configs;
+use crate::setup_scripts::update_core_contract::{core_contract_upgrade, update_core_contract};
+use crate::tests::mock_l1::MockL1;
+use crate::utils::{convert_felt_to_u256, pad_bytes};
+use crate::ConfigFile;
+
+const PROXY: Address = Address::repeat_byte(0xc0);
+const IMPLEMENTATION: Address = Address::repeat_byte(0x11);
+const VERIFIER_EIC: Address = Address::repeat_byte(0xe1);
+const VERIFIER: Address = Address::repeat_byte(0xab);
+
+#[test]
+fn initialised_proxy_is_upgraded_without_init_data() {
+    let upgrade = core_contract_upgrade(PROXY, IMPLEMENTATION, None, VERIFIER);
+
+    assert!(upgrade.eic.is_none());
+    assert!(upgrade.init_data.is_empty());
+    // Only the (zero) EIC address word, `initialize` reverts on init data for an initialised proxy.
+    assert_eq!(build_upgrade_calldata(None, &upgrade.init_data).to_vec(), vec![0u8; 32]);
+}
+
+#[test]
+fn verifier_is_rotated_through_the_eic() {
+    let upgrade = core_contract_upgrade(PROXY, IMPLEMENTATION, Some(VERIFIER_EIC), VERIFIER);
+
+    assert!(matches!(upgrade.eic, Some(ImplementationSource::Deployed(eic)) if eic == VERIFIER_EIC));
+    assert_eq!(
+        build_upgrade_calldata(Some(VERIFIER_EIC), &upgrade.init_data).to_vec(),
+        [pad_bytes(VERIFIER_EIC), pad_bytes(VERIFIER)].concat()
+    );
+}
+
+#[tokio::test]
+async fn only_the_changed_hashes_are_set() {
+    let l1 = MockL1::start();
+    let config = ConfigFile::default();
+    let (program_hash, config_hash) = get_bridge_init_configs(&config);
+    l1.set_call_result(PROXY, "programHash()", &[Token::Uint(convert_felt_to_u256(program_hash))]);
+
+    let output = update_core_contract(PROXY, l1.client(), &config).await.unwrap();
+
+    assert!(output.implementation_upgrade.is_none());
+    let calls = l1.calls_to(PROXY);
+    assert_eq!(calls.len(), 1);
+    assert!(calls[0].is(PROXY, "setConfigHash(uint256)"));
+    assert_eq!(calls[0].args(), encode(&[Token::Uint(convert_felt_to_u256(config_hash))]));
+}
+
+#[tokio::test]
+async fn verifier_is_rotated_on_the_current_implementation() {
+    let l1 = MockL1::start();
+    let config = ConfigFile {
+        core_contract_verifier_eic_address: Some(format!("{VERIFIER_EIC:?}")),
+        verifier_address: format!("{VERIFIER:?}"),
+        ..ConfigFile::default()
+    };
+    let (program_hash, config_hash) = get_bridge_init_configs(&config);
+    l1.set_call_result(PROXY, "programHash()", &[Token::Uint(convert_felt_to_u256(program_hash))]);
+    l1.set_call_result(PROXY, "configHash()", &[Token::Uint(convert_felt_to_u256(config_hash))]);
+    l1.set_call_result(PROXY, "implementation()", &[Token::Address(IMPLEMENTATION)]);
+
+    let output = update_core_contract(PROXY, l1.client(), &config).await.unwrap();
+
+    let upgrade = output.implementation_upgrade.unwrap();
+    assert!(upgrade.upgraded);
+    assert_eq!(upgrade.implementation_address, IMPLEMENTATION);
+    let calldata = build_upgrade_calldata(Some(VERIFIER_EIC), &pad_bytes(VERIFIER));
+    let args = encode(&[Token::Address(IMPLEMENTATION), Token::Bytes(calldata.to_vec()), Token::Bool(false)]);
+    let calls = l1.calls_to(PROXY);
+    assert_eq!(calls.len(), 2);
+    assert!(calls[0].is(PROXY, "addImplementation(address,bytes,bool)"));
+    assert_eq!(calls[0].args(), args);
+    assert!(calls[1].is(PROXY, "upgradeTo(address,bytes,bool)"));
+    assert_eq!(calls[1].args(), args);
+}
@@ -0,0 +1,288 @@
+//! Contract artifacts registry.
+//!
+//! Artifacts are referenced everywhere by the paths in [`crate::utils::constants`] (e.g.
+//! `artifacts/erc20.sierra.json`, `src/contracts/udc.json`). They are resolved in this order :
+//! 1. `--artifacts-dir` : flat directory with the layout of `artifacts/`, the hardcoded
+//!    `src/contracts/` artifacts sit next to the built ones.
+//! 2. The artifacts embedded in the binary (`embedded-artifacts` feature).
+//! 3. The current directory, then the crate directory.
+//!
+//! Every artifact is checked against the pinned class hash / bytecode hash in `manifest.json`
+//! before use. The manifest is generated with the `pin-artifacts` mode from the `make artifacts`
+//! outputs and committed (`artifacts/manifest.json`), CI rebuilds the artifacts and checks that
+//! they still match it. Loading an artifact without it is an error unless the verification is
+//! disabled.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use color_eyre::eyre::{eyre, WrapErr};
+use ethers::types::Bytes;
+use ethers::utils::keccak256;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use starknet::core::types::contract::legacy::LegacyContractClass;
+use starknet_core::types::contract::{CompiledClass, SierraClass};
+
+pub const ARTIFACTS_MANIFEST_FILE: &str = "manifest.json";
+const ARTIFACTS_PREFIXES: [&str; 2] = ["artifacts/", "src/contracts/"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    /// Cairo 1 contract class, pinned by its class hash.
+    Sierra,
+    /// Cairo 1 compiled class, pinned by its compiled class hash.
+    Casm,
+    /// Cairo 0 contract class, pinned by its class hash.
+    Legacy,
+    /// Solidity artifact, pinned by the keccak256 of its bytecode.
+    Solidity,
+}
+
+macro_rules! artifacts {
+    ($($path:literal => $kind:ident),* $(,)?) => {
+        /// Every artifact used by the bootstrapper.
+        pub const KNOWN_ARTIFACTS: &[(&str, ArtifactKind)] = &[$(($path, ArtifactKind::$kind)),*];
+
+        #[cfg(feature = "embedded-artifacts")]
+        const EMBEDDED_ARTIFACTS: &[(&str, &[u8])] =
+            &[$(($path, include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $path)))),*];
+    };
+}
+
+artifacts! {
+    "artifacts/erc20.sierra.json" => Sierra,
+    "artifacts/erc20.casm.json" => Casm,
+    "artifacts/ArgentAccount.sierra.json" => Sierra,
+    "artifacts/ArgentAccount.casm.json" => Casm,
+    "artifacts/BraavosAccount.sierra.json" => Sierra,
+    "artifacts/BraavosAccount.casm.json" => Casm,
+    "artifacts/BraavosBaseAccount.sierra.json" => Sierra,
+    "artifacts/BraavosBaseAccount.casm.json" => Casm,
+    "artifacts/legacy_token_bridge.json" => Legacy,
+    "artifacts/proxy_starkgate.json" => Legacy,
+    "artifacts/ERC20.json" => Legacy,
+    "artifacts/token_bridge.sierra.json" => Sierra,
+    "artifacts/token_bridge.casm.json" => Casm,
+    "artifacts/piltover_appchain.sierra.json" => Sierra,
+    "artifacts/piltover_appchain.casm.json" => Casm,
+    "artifacts/eth_token_eic.sierra.json" => Sierra,
+    "artifacts/eth_token_eic.casm.json" => Casm,
+    "artifacts/ERC20_070.sierra.json" => Sierra,
+    "artifacts/ERC20_070.casm.json" => Casm,
+    "artifacts/token_bridge_eic.sierra.json" => Sierra,
+    "artifacts/token_bridge_eic.casm.json" => Casm,
+    "artifacts/upgrade-contracts/eth_bridge_upgraded.json" => Solidity,
+    "artifacts/upgrade-contracts/eic_eth_bridge.json" => Solidity,
+    "artifacts/upgrade-contracts/starkgate_manager_upgraded.json" => Solidity,
+    "artifacts/upgrade-contracts/starkgate_registry_upgraded.json" => Solidity,
+    "artifacts/upgrade-contracts/token_bridge_upgraded.json" => Solidity,
+    "src/contracts/OpenZeppelinAccountCairoOne.sierra.json" => Sierra,
+    "src/contracts/OpenZeppelinAccountCairoOne.casm.json" => Casm,
+    "src/contracts/OpenZeppelinAccount.json" => Legacy,
+    "src/contracts/proxy_legacy.json" => Legacy,
+    "src/contracts/udc.json" => Legacy,
+    "src/contracts/BraavosCallAggregator.json" => Legacy,
+}
+
+#[cfg(feature = "embedded-artifacts")]
+const EMBEDDED_MANIFEST: &[u8] = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/artifacts/manifest.json"));
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinnedArtifact {
+    pub kind: ArtifactKind,
+    /// Hex encoded class hash (Cairo) or bytecode keccak256 (Solidity).
+    pub hash: String,
+}
+
+/// Pinned hashes of the artifacts, keyed by artifact path.
+pub type ArtifactManifest = BTreeMap<String, PinnedArtifact>;
+
+struct ArtifactRegistry {
+    artifacts_dir: Option<PathBuf>,
+    manifest: Option<ArtifactManifest>,
+    verify: bool,
+}
+
+static ARTIFACT_REGISTRY: OnceLock<ArtifactRegistry> = OnceLock::new();
+
+/// Sets up the registry, must be called before the first artifact is loaded. Without it the
+/// artifacts are resolved from the embedded artifacts or the current directory and verified.
+pub fn init_artifact_registry(artifacts_dir: Option<PathBuf>, verify: bool) -> color_eyre::Result<()> {
+    let registry = ArtifactRegistry::new(artifacts_dir, verify)?;
+    ARTIFACT_REGISTRY.set(registry).map_err(|_| eyre!("Artifact registry already initialised"))
+}
+
+fn registry() -> color_eyre::Result<&'static ArtifactRegistry> {
+    if let Some(registry) = ARTIFACT_REGISTRY.get() {
+        return Ok(registry);
+    }
+    // A malformed manifest is reported by every load instead of being cached.
+    let registry = ArtifactRegistry::new(None, true)?;
+    Ok(ARTIFACT_REGISTRY.get_or_init(|| registry))
+}
+
+/// Loads an artifact and checks it against the manifest.
+pub fn load_artifact(path: &str) -> color_eyre::Result<Vec<u8>> {
+    let registry = registry()?;
+    let bytes = registry.read(path)?;
+    registry.verify(path, &bytes)?;
+    Ok(bytes)
+}
+
+/// Loads and deserializes a json artifact, see [`load_artifact`].
+pub fn load_json_artifact<T: DeserializeOwned>(path: &str) -> color_eyre::Result<T> {
+    serde_json::from_slice(&load_artifact(path)?).wrap_err_with(|| format!("Unable to parse artifact {path}"))
+}
+
+/// Computes the manifest for the artifacts currently available, the ones which are not built
+/// (e.g. Piltover without `PILTOVER_CONTRACTS_COMMIT_HASH`) are left unpinned.
+pub fn pin_artifacts() -> color_eyre::Result<ArtifactManifest> {
+    let registry = registry()?;
+    let mut manifest = ArtifactManifest::new();
+    for (path, kind) in KNOWN_ARTIFACTS {
+        let bytes = match registry.read(path) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                log::warn!("⚠️ Artifact {} not pinned : {:#}", path, err);
+                continue;
+            }
+        };
+        let hash = artifact_hash(*kind, &bytes).wrap_err_with(|| format!("Unable to hash artifact {path}"))?;
+        log::debug!("📌 {} : {}", path, hash);
+        manifest.insert(path.to_string(), PinnedArtifact { kind: *kind, hash });
+    }
+    Ok(manifest)
+}
+
+/// Pins the available artifacts and writes the manifest next to them (`artifacts/manifest.json`
+/// or `<artifacts_dir>/manifest.json`).
+pub fn write_artifacts_manifest(artifacts_dir: &Option<PathBuf>) -> color_eyre::Result<PathBuf> {
+    let manifest = pin_artifacts()?;
+    let path = manifest_path(artifacts_dir);
+    fs::write(&path, serde_json::to_string_pretty(&manifest)?)
+        .wrap_err_with(|| format!("Unable to write manifest {:?}", path))?;
+    log::info!("📌 {} artifacts pinned in {:?}", manifest.len(), path);
+    Ok(path)
+}
+
+fn manifest_path(artifacts_dir: &Option<PathBuf>) -> PathBuf {
+    artifacts_dir.clone().unwrap_or_else(|| PathBuf::from("artifacts")).join(ARTIFACTS_MANIFEST_FILE)
+}
+
+impl ArtifactRegistry {
+    fn new(artifacts_dir: Option<PathBuf>, verify: bool) -> color_eyre::Result<Self> {
+        let manifest: Option<ArtifactManifest> = match &artifacts_dir {
+            Some(dir) => read_manifest_file(&dir.join(ARTIFACTS_MANIFEST_FILE))?,
+            None => match embedded_manifest() {
+                Some(manifest) => Some(serde_json::from_slice(manifest)?),
+                None => match read_manifest_file(&manifest_path(&None))? {
+                    Some(manifest) => Some(manifest),
+                    None => read_manifest_file(&Path::new(env!("CARGO_MANIFEST_DIR")).join(manifest_path(&None)))?,
+                },
+            },
+        };
+        if !verify {
+            log::warn!("⚠️ Artifacts verification disabled, the artifacts are used without checking their hashes.");
+        }
+        Ok(Self { artifacts_dir, manifest, verify })
+    }
+
+    fn read(&self, path: &str) -> color_eyre::Result<Vec<u8>> {
+        // Artifacts which are not part of the bootstrapper (e.g. `custom_contracts`) are read as is.
+        if !is_known_artifact(path) {
+            return fs::read(path).wrap_err_with(|| format!("Unable to read artifact {path}"));
+        }
+        if let Some(dir) = &self.artifacts_dir {
+            let file_name = ARTIFACTS_PREFIXES.iter().find_map(|prefix| path.strip_prefix(prefix)).unwrap_or(path);
+            let artifact_path = dir.join(file_name);
+            return fs::read(&artifact_path).wrap_err_with(|| format!("Unable to read artifact {:?}", artifact_path));
+        }
+        if let Some(bytes) = embedded_artifact(path) {
+            return Ok(bytes.to_vec());
+        }
+        fs::read(path)
+            .or_else(|_| fs::read(Path::new(env!("CARGO_MANIFEST_DIR")).join(path)))
+            .wrap_err_with(|| format!("Unable to read artifact {path}"))
+    }
+
+    fn verify(&self, path: &str, bytes: &[u8]) -> color_eyre::Result<()> {
+        if !self.verify || !is_known_artifact(path) {
+            return Ok(());
+        }
+        let manifest = self.manifest.as_ref().ok_or_else(|| {
+            eyre!(
+                "No artifacts manifest found, can't verify artifact {path}. Run the `pin-artifacts` mode or pass \
+                 `--skip-artifacts-verification`."
+            )
+        })?;
+        let pinned = manifest.get(path).ok_or_else(|| eyre!("Artifact {path} is not pinned in the manifest"))?;
+        let hash = artifact_hash(pinned.kind, bytes).wrap_err_with(|| format!("Unable to hash artifact {path}"))?;
+        if hash != pinned.hash {
+            return Err(eyre!("Artifact {path} hash mismatch : expected {}, found {}", pinned.hash, hash));
+        }
+        Ok(())
+    }
+}
+
+fn is_known_artifact(path: &str) -> bool {
+    KNOWN_ARTIFACTS.iter().any(|(known_path, _)| *known_path == path)
+}
+
+fn read_manifest_file(path: &Path) -> color_eyre::Result<Option<ArtifactManifest>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let manifest = serde_json::from_slice(&fs::read(path)?).wrap_err_with(|| format!("Invalid manifest {:?}", path))?;
+    Ok(Some(manifest))
+}
+
+fn artifact_hash(kind: ArtifactKind, bytes: &[u8]) -> color_eyre::Result<String> {
+    let class_hash = match kind {
+        ArtifactKind::Sierra => serde_json::from_slice::<SierraClass>(bytes)?.class_hash()?,
+        ArtifactKind::Casm => serde_json::from_slice::<CompiledClass>(bytes)?.class_hash()?,
+        ArtifactKind::Legacy => serde_json::from_slice::<LegacyContractClass>(bytes)?.class_hash()?,
+        ArtifactKind::Solidity => {
+            let bytecode = solidity_bytecode(&serde_json::from_slice(bytes)?)?;
+            return Ok(format!("0x{}", hex::encode(keccak256(bytecode))));
+        }
+    };
+    Ok(class_hash.to_hex_string())
+}
+
+/// Both `"bytecode": "0x.."` and `"bytecode": { "object": "0x.." }` layouts are used by the
+/// solidity artifacts we build.
+pub fn solidity_bytecode(artifact: &Value) -> color_eyre::Result<Bytes> {
+    let bytecode = match &artifact["bytecode"] {
+        Value::String(bytecode) => bytecode.as_str(),
+        Value::Object(bytecode) => {
+            bytecode.get("object").and_then(Value::as_str).ok_or_else(|| eyre!("No bytecode found in artifact"))?
+        }
+        _ => return Err(eyre!("No bytecode found in artifact")),
+    };
+    Ok(Bytes::from(hex::decode(bytecode.trim_start_matches("0x"))?))
+}
+
+#[cfg(feature = "embedded-artifacts")]
+fn embedded_artifact(path: &str) -> Option<&'static [u8]> {
+    EMBEDDED_ARTIFACTS.iter().find(|(embedded_path, _)| *embedded_path == path).map(|(_, bytes)| *bytes)
+}
+
+#[cfg(not(feature = "embedded-artifacts"))]
+fn embedded_artifact(_path: &str) -> Option<&'static [u8]> {
+    None
+}
+
+#[cfg(feature = "embedded-artifacts")]
+fn embedded_manifest() -> Option<&'static [u8]> {
+    Some(EMBEDDED_MANIFEST)
+}
+
+#[cfg(not(feature = "embedded-artifacts"))]
+fn embedded_manifest() -> Option<&'static [u8]> {
+    None
+}
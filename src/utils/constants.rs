@@ -12,6 +12,8 @@ pub const STARKGATE_PROXY_PATH: &str = "artifacts/proxy_starkgate.json";
 pub const ERC20_LEGACY_PATH: &str = "artifacts/ERC20.json";
 pub const TOKEN_BRIDGE_SIERRA_PATH: &str = "artifacts/token_bridge.sierra.json";
 pub const TOKEN_BRIDGE_CASM_PATH: &str = "artifacts/token_bridge.casm.json";
+pub const PILTOVER_APPCHAIN_SIERRA_PATH: &str = "artifacts/piltover_appchain.sierra.json";
+pub const PILTOVER_APPCHAIN_CASM_PATH: &str = "artifacts/piltover_appchain.casm.json";
 
 // Hardcoded Artifacts Paths
 pub const OZ_ACCOUNT_SIERRA_PATH: &str = "src/contracts/OpenZeppelinAccountCairoOne.sierra.json";
@@ -30,7 +32,16 @@ pub const EIC_ETH_BRIDGE_SIERRA_PATH: &str = "artifacts/token_bridge_eic.sierra.
 pub const EIC_ETH_BRIDGE_CASM_PATH: &str = "artifacts/token_bridge_eic.casm.json";
 pub const NEW_ETH_BRIDGE_SIERRA_PATH: &str = "artifacts/token_bridge.sierra.json";
 pub const NEW_ETH_BRIDGE_CASM_PATH: &str = "artifacts/token_bridge.casm.json";
+pub const NEW_ETH_BRIDGE_L1_PATH: &str = "artifacts/upgrade-contracts/eth_bridge_upgraded.json";
+pub const EIC_ETH_BRIDGE_L1_PATH: &str = "artifacts/upgrade-contracts/eic_eth_bridge.json";
+pub const NEW_MANAGER_L1_PATH: &str = "artifacts/upgrade-contracts/starkgate_manager_upgraded.json";
+pub const NEW_REGISTRY_L1_PATH: &str = "artifacts/upgrade-contracts/starkgate_registry_upgraded.json";
+pub const NEW_TOKEN_BRIDGE_L1_PATH: &str = "artifacts/upgrade-contracts/token_bridge_upgraded.json";
 
 // Other
 pub const ERC20_L2_CLASS_HASH: &str = "0x5ffbcfeb50d200a0677c48a129a11245a3fc519d1d98d76882d1c9a1b19c6ed";
+/// Universal Deployer Contract of the Starknet networks, used on a Starknet settlement chain.
+pub const STARKNET_UDC_ADDRESS: &str = "0x41a78e741e5af2fec34b695679bc6891742439f7afb8484ecd7766661ad02bf";
+/// Cairo 1 Universal Deployer Contract of the Starknet networks.
+pub const STARKNET_CAIRO_1_UDC_ADDRESS: &str = "0x2ceed65a4bd731034c01113685c831b01c15d7d432f71afb1cf1634b53a2125";
 pub const MAX_FEE_OVERRIDE: &str = "0x10000000000000000000000";
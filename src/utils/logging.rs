@@ -0,0 +1,67 @@
+//! Logging : `tracing` spans for the bootstrap steps and their sub-steps, the `log` records of the
+//! setup scripts are forwarded to the same subscriber. The level is set with `RUST_LOG`.
+//!
+//! Spans are named `<step>.<sub step>` (e.g. `eth_bridge.declare_legacy_proxy`) and carry the
+//! `chain` they act on. In the `json` format, the span close events carry the duration
+//! (`time.busy`) and every event carries the spans it happened in.
+
+use std::fmt;
+use std::future::Future;
+
+use clap::ValueEnum;
+use tracing::{Instrument, Span};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human readable lines, as printed by the setup scripts.
+    #[default]
+    Pretty,
+    /// One JSON object per event, with the spans of the event.
+    Json,
+}
+
+/// Installs the global subscriber, must be called once before the first log.
+pub fn init_logging(format: LogFormat) -> color_eyre::Result<()> {
+    let builder = tracing_subscriber::fmt().with_env_filter(EnvFilter::from_default_env());
+    match format {
+        LogFormat::Pretty => builder.with_target(false).try_init(),
+        LogFormat::Json => {
+            builder.json().with_current_span(true).with_span_list(true).with_span_events(FmtSpan::CLOSE).try_init()
+        }
+    }
+    .map_err(|err| color_eyre::eyre::eyre!("Failed to set up logging : {}", err))
+}
+
+/// Span of a sub step, e.g. `sub_step!("eth_bridge.declare_legacy_proxy", "l2")`, extra fields
+/// follow the chain (`sub_step!("custom_contracts.declare", "l2", contract = %name)`). Its
+/// `result` is recorded by [`traced`] and its `tx_hash` by [`record_tx_hash`].
+macro_rules! sub_step {
+    ($name:literal, $chain:literal) => {
+        tracing::info_span!($name, chain = $chain, result = tracing::field::Empty, tx_hash = tracing::field::Empty)
+    };
+    ($name:literal, $chain:literal, $($fields:tt)+) => {
+        tracing::info_span!(
+            $name,
+            chain = $chain,
+            result = tracing::field::Empty,
+            tx_hash = tracing::field::Empty,
+            $($fields)+
+        )
+    };
+}
+pub(crate) use sub_step;
+
+/// Records the hash of a transaction sent by the bootstrapper on the current sub step. The L1
+/// transactions sent by the zaun clients are only known after the step, from the ledger.
+pub(crate) fn record_tx_hash(hash: impl fmt::Debug) {
+    Span::current().record("tx_hash", tracing::field::debug(&hash));
+}
+
+/// Runs `future` in `span` and records its output (address, class hash) in the `result` field.
+pub(crate) async fn traced<T: fmt::Debug>(span: Span, future: impl Future<Output = T>) -> T {
+    let output = future.instrument(span.clone()).await;
+    span.record("result", tracing::field::debug(&output));
+    output
+}
@@ -1,4 +1,5 @@
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::{fs, io};
 
 use ethers::addressbook::Address;
@@ -14,8 +15,10 @@ use starknet_providers::JsonRpcClient;
 use crate::contract_clients::utils::RpcAccount;
 use crate::helpers::account_actions::{get_transaction_receipt, AccountActions};
 
+pub mod artifacts;
 pub mod banner;
 pub mod constants;
+pub mod logging;
 
 pub async fn invoke_contract<'a>(
     contract: Felt,
@@ -61,6 +64,7 @@ pub async fn wait_for_transaction(
             panic!("Transaction failed");
         }
     };
+    tracing::info!(tx_hash = %transaction_hash.to_hex_string(), tag, "L2 transaction accepted");
 
     Ok(())
 }
@@ -69,12 +73,29 @@ pub fn convert_felt_to_u256(felt: Felt) -> U256 {
     U256::from_big_endian(&felt.to_bytes_be())
 }
 
+pub fn convert_u256_to_felt(value: U256) -> Felt {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    Felt::from_bytes_be(&bytes)
+}
+
 pub enum JsonValueType {
     EthAddress(Address),
     StringType(String),
 }
 
+static SAVE_ADDRESSES: AtomicBool = AtomicBool::new(false);
+
+/// Enables [`save_to_json`]. Disabled by default so that library users don't get files written
+/// in their working directory.
+pub fn enable_addresses_file(enabled: bool) {
+    SAVE_ADDRESSES.store(enabled, Ordering::Relaxed);
+}
+
 pub fn save_to_json(key: &str, value: &JsonValueType) -> Result<(), io::Error> {
+    if !SAVE_ADDRESSES.load(Ordering::Relaxed) {
+        return Ok(());
+    }
     let file_path: &str = "./data/addresses.json";
     let data = fs::read_to_string(file_path);
     let mut json: Map<String, Value> = match data {